@@ -0,0 +1,85 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::core::step::StepExecution;
+
+/// Configures a chunk size that adapts to the observed writer latency and failures.
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveChunkSize {
+    /// The smallest chunk size that can be chosen.
+    pub min: usize,
+    /// The largest chunk size that can be chosen.
+    pub max: usize,
+    /// The writer latency the chunk size is tuned towards.
+    pub target_latency: Duration,
+}
+
+impl AdaptiveChunkSize {
+    /// Validates the configured bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the minimum is zero or greater than the maximum.
+    pub(crate) fn validate(&self) {
+        if self.min == 0 {
+            panic!("Adaptive chunk size minimum must be greater than zero");
+        }
+
+        if self.min > self.max {
+            panic!("Adaptive chunk size minimum must not be greater than the maximum");
+        }
+    }
+}
+
+/// Chooses the chunk size of a running step from the outcome of each write.
+///
+/// Writes faster than the target latency grow the chunk size by a quarter, slower writes shrink it
+/// proportionally to the overshoot, and every failed write halves it, so a higher failure rate keeps
+/// the chunks smaller.
+pub(crate) struct ChunkSizer {
+    /// The configured bounds and target latency.
+    config: AdaptiveChunkSize,
+    /// The chunk size currently in use.
+    current: Mutex<usize>,
+    /// The execution where each chosen chunk size is recorded.
+    execution: Arc<StepExecution>,
+}
+
+impl ChunkSizer {
+    /// Creates a sizer starting at the given chunk size, clamped to the configured bounds.
+    pub(crate) fn new(config: AdaptiveChunkSize, initial: usize, execution: Arc<StepExecution>) -> Self {
+        let initial = initial.clamp(config.min, config.max);
+        execution.record_chunk_size(initial);
+        ChunkSizer {
+            config,
+            current: Mutex::new(initial),
+            execution,
+        }
+    }
+
+    /// Returns the chunk size currently in use.
+    pub(crate) fn current(&self) -> usize {
+        *self.current.lock().unwrap()
+    }
+
+    /// Updates the chunk size from the latency and outcome of a write and returns the new size.
+    pub(crate) fn observe(&self, latency: Duration, failed: bool) -> usize {
+        let mut current = self.current.lock().unwrap();
+        let target = self.config.target_latency;
+        let next = if failed {
+            *current / 2
+        } else if latency > target {
+            (*current as f64 * target.as_secs_f64() / latency.as_secs_f64()) as usize
+        } else {
+            *current + (*current / 4).max(1)
+        };
+        let next = next.clamp(self.config.min, self.config.max);
+
+        if next != *current {
+            *current = next;
+            self.execution.record_chunk_size(next);
+        }
+
+        next
+    }
+}
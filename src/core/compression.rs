@@ -0,0 +1,499 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+#[cfg(any(feature = "compression", feature = "async"))]
+use std::io::Read;
+use std::path::Path;
+#[cfg(feature = "compression")]
+use std::sync::mpsc::{self, Receiver};
+#[cfg(any(feature = "compression", feature = "async"))]
+use std::thread;
+
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::{ready, Context, Poll};
+#[cfg(feature = "async")]
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite, ReadBuf};
+
+#[cfg(feature = "compression")]
+use flate2::bufread::MultiGzDecoder;
+#[cfg(feature = "compression")]
+use flate2::write::GzEncoder;
+#[cfg(feature = "compression")]
+use zip::ZipArchive;
+
+/// The size of the chunks a decompressed file is handed over in by its decompression thread.
+#[cfg(any(feature = "compression", feature = "async"))]
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The number of chunks a decompression thread reads ahead.
+#[cfg(any(feature = "compression", feature = "async"))]
+const CHUNKS_AHEAD: usize = 4;
+
+/// The compression of a file read by an item reader or written by an item writer.
+///
+/// The builders of the file-based readers and writers infer it from the extension of the file,
+/// e.g. `.csv.gz`, and accept an explicit compression for files named otherwise. Compressed files
+/// need the `compression` feature; without it, opening or creating one fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// A plain file.
+    #[default]
+    None,
+    /// A gzip file, `.gz`. Concatenated gzip members are read as a single file.
+    Gzip,
+    /// A zstd file, `.zst`.
+    Zstd,
+    /// A zip archive, `.zip`, of which a single entry is read. Zip archives cannot be written.
+    Zip,
+}
+
+impl Compression {
+    /// Infers the compression of a file from its extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Returns `Compression`
+    ///
+    /// Returns the compression matching the extension, or `Compression::None` for any other
+    /// extension.
+    pub fn from_path(path: &Path) -> Compression {
+        let extension = path.extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("gz") | Some("gzip") => Compression::Gzip,
+            Some("zst") | Some("zstd") => Compression::Zstd,
+            Some("zip") => Compression::Zip,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// The error returned for a compressed file when the crate is built without the codecs.
+#[cfg(not(feature = "compression"))]
+fn compression_disabled(compression: Compression) -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, format!("{:?} compression requires the compression feature", compression))
+}
+
+/// Opens a file for reading, decompressing it on the fly.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file.
+/// * `compression` - The compression of the file.
+/// * `entry` - The name of the entry to read from a zip archive, or `None` for its first file.
+///
+/// # Returns `io::Result<Box<dyn BufRead + Send>>`
+///
+/// Returns the decompressed content of the file, or the error opening it.
+#[cfg_attr(not(feature = "compression"), allow(unused_variables))]
+pub(crate) fn open_input(path: &Path, compression: Compression, entry: Option<&str>) -> io::Result<Box<dyn BufRead + Send>> {
+    let file = File::open(path)?;
+
+    match compression {
+        Compression::None => Ok(Box::new(BufReader::new(file))),
+        #[cfg(feature = "compression")]
+        Compression::Gzip => Ok(Box::new(BufReader::new(MultiGzDecoder::new(BufReader::new(file))))),
+        #[cfg(feature = "compression")]
+        Compression::Zstd => Ok(Box::new(BufReader::new(zstd::Decoder::new(file)?))),
+        #[cfg(feature = "compression")]
+        Compression::Zip => Ok(Box::new(BufReader::new(open_zip_entry(file, entry)?))),
+        #[cfg(not(feature = "compression"))]
+        _ => Err(compression_disabled(compression)),
+    }
+}
+
+/// Opens an entry of a zip archive.
+///
+/// An entry borrows its archive, so the entry is read on its own thread, which owns the archive.
+#[cfg(feature = "compression")]
+fn open_zip_entry(file: File, entry: Option<&str>) -> io::Result<ChunkReader> {
+    let mut archive = ZipArchive::new(file)?;
+
+    let mut index = None;
+    for candidate in 0..archive.len() {
+        let file = archive.by_index_raw(candidate)?;
+        let found = match entry {
+            Some(name) => file.name() == name,
+            None => !file.is_dir(),
+        };
+        if found {
+            index = Some(candidate);
+            break;
+        }
+    }
+
+    let Some(index) = index else {
+        let message = match entry {
+            Some(name) => format!("Entry {} not found in zip archive", name),
+            None => "Zip archive has no file".to_string(),
+        };
+        return Err(io::Error::new(io::ErrorKind::NotFound, message));
+    };
+
+    let (sender, receiver) = mpsc::sync_channel(CHUNKS_AHEAD);
+    thread::spawn(move || match archive.by_index(index) {
+        Ok(mut entry) => pump(&mut entry, |chunk| sender.send(chunk).is_ok()),
+        Err(err) => {
+            let _ = sender.send(Err(err.into()));
+        }
+    });
+
+    Ok(ChunkReader { receiver, chunk: Vec::new(), offset: 0 })
+}
+
+/// Reads a reader to its end in chunks, handing each chunk, or the error reading it, over to
+/// `send`. Stops early when `send` returns `false`, i.e. when the receiving side is gone.
+#[cfg(any(feature = "compression", feature = "async"))]
+fn pump(reader: &mut dyn Read, mut send: impl FnMut(io::Result<Vec<u8>>) -> bool) {
+    loop {
+        let mut chunk = vec![0; CHUNK_SIZE];
+        match reader.read(&mut chunk) {
+            Ok(0) => return,
+            Ok(read) => {
+                chunk.truncate(read);
+                if !send(Ok(chunk)) {
+                    return;
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => {
+                send(Err(err));
+                return;
+            }
+        }
+    }
+}
+
+/// Reads the chunks handed over by a decompression thread.
+#[cfg(feature = "compression")]
+struct ChunkReader {
+    /// Receives the chunks, until the thread is done.
+    receiver: Receiver<io::Result<Vec<u8>>>,
+    /// The chunk being read.
+    chunk: Vec<u8>,
+    /// The position in the chunk being read.
+    offset: usize,
+}
+
+#[cfg(feature = "compression")]
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.offset == self.chunk.len() {
+            match self.receiver.recv() {
+                Ok(chunk) => {
+                    self.chunk = chunk?;
+                    self.offset = 0;
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+
+        let read = buf.len().min(self.chunk.len() - self.offset);
+        buf[..read].copy_from_slice(&self.chunk[self.offset..self.offset + read]);
+        self.offset += read;
+        Ok(read)
+    }
+}
+
+/// The error returned when writing a zip archive.
+fn zip_output_unsupported() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "Writing zip archives is not supported")
+}
+
+/// A file being written, compressed on the fly.
+pub(crate) enum OutputFile {
+    Plain(BufWriter<File>),
+    #[cfg(feature = "compression")]
+    Gzip(GzEncoder<BufWriter<File>>),
+    #[cfg(feature = "compression")]
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl OutputFile {
+    /// Creates, or truncates, a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    /// * `compression` - The compression of the file; zip archives are not supported.
+    ///
+    /// # Returns `io::Result<OutputFile>`
+    ///
+    /// Returns the file, or the error creating it.
+    pub(crate) fn create(path: &Path, compression: Compression) -> io::Result<OutputFile> {
+        if compression == Compression::Zip {
+            return Err(zip_output_unsupported());
+        }
+
+        #[cfg(not(feature = "compression"))]
+        if compression != Compression::None {
+            return Err(compression_disabled(compression));
+        }
+
+        let file = BufWriter::new(File::create(path)?);
+
+        match compression {
+            #[cfg(feature = "compression")]
+            Compression::Gzip => Ok(OutputFile::Gzip(GzEncoder::new(file, flate2::Compression::default()))),
+            #[cfg(feature = "compression")]
+            Compression::Zstd => Ok(OutputFile::Zstd(zstd::Encoder::new(file, 0)?)),
+            _ => Ok(OutputFile::Plain(file)),
+        }
+    }
+
+    /// Writes the end of the compressed stream and flushes the file.
+    pub(crate) fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            OutputFile::Plain(file) => file,
+            #[cfg(feature = "compression")]
+            OutputFile::Gzip(encoder) => encoder.finish()?,
+            #[cfg(feature = "compression")]
+            OutputFile::Zstd(encoder) => encoder.finish()?,
+        };
+        file.flush()
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            OutputFile::Plain(file) => file.write(buf),
+            #[cfg(feature = "compression")]
+            OutputFile::Gzip(encoder) => encoder.write(buf),
+            #[cfg(feature = "compression")]
+            OutputFile::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            OutputFile::Plain(file) => file.flush(),
+            #[cfg(feature = "compression")]
+            OutputFile::Gzip(encoder) => encoder.flush(),
+            #[cfg(feature = "compression")]
+            OutputFile::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Opens a file for reading asynchronously, decompressing it on the fly.
+///
+/// The decoders are blocking, so a compressed file is decompressed on its own thread, which hands
+/// the content over in chunks and reads a few chunks ahead.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file.
+/// * `compression` - The compression of the file.
+/// * `entry` - The name of the entry to read from a zip archive, or `None` for its first file.
+///
+/// # Returns `io::Result<Box<dyn AsyncBufRead + Send + Unpin>>`
+///
+/// Returns the decompressed content of the file, or the error opening it.
+#[cfg(feature = "async")]
+pub(crate) async fn open_async_input(path: &Path, compression: Compression, entry: Option<&str>) -> io::Result<Box<dyn AsyncBufRead + Send + Unpin>> {
+    if compression == Compression::None {
+        let file = tokio::fs::File::open(path).await?;
+        return Ok(Box::new(tokio::io::BufReader::new(file)));
+    }
+
+    let path = path.to_path_buf();
+    let entry = entry.map(str::to_string);
+    let (sender, receiver) = tokio::sync::mpsc::channel(CHUNKS_AHEAD);
+    let (opened, open_result) = tokio::sync::oneshot::channel();
+
+    thread::spawn(move || match open_input(&path, compression, entry.as_deref()) {
+        Ok(mut reader) => {
+            let _ = opened.send(Ok(()));
+            pump(&mut reader, |chunk| sender.blocking_send(chunk).is_ok());
+        }
+        Err(err) => {
+            let _ = opened.send(Err(err));
+        }
+    });
+
+    open_result.await
+        .map_err(|_| io::Error::other("Decompression thread stopped"))??;
+
+    Ok(Box::new(tokio::io::BufReader::new(AsyncChunkReader { receiver, chunk: Vec::new(), offset: 0 })))
+}
+
+/// Reads the chunks handed over by a decompression thread, asynchronously.
+#[cfg(feature = "async")]
+struct AsyncChunkReader {
+    /// Receives the chunks, until the thread is done.
+    receiver: tokio::sync::mpsc::Receiver<io::Result<Vec<u8>>>,
+    /// The chunk being read.
+    chunk: Vec<u8>,
+    /// The position in the chunk being read.
+    offset: usize,
+}
+
+#[cfg(feature = "async")]
+impl AsyncRead for AsyncChunkReader {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if this.offset == this.chunk.len() {
+            match ready!(this.receiver.poll_recv(cx)) {
+                Some(chunk) => {
+                    this.chunk = chunk?;
+                    this.offset = 0;
+                }
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        let read = buf.remaining().min(this.chunk.len() - this.offset);
+        buf.put_slice(&this.chunk[this.offset..this.offset + read]);
+        this.offset += read;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Compresses into memory, the output being moved to the file as it is produced.
+#[cfg(all(feature = "async", feature = "compression"))]
+enum BufferEncoder {
+    Gzip(GzEncoder<Vec<u8>>),
+    Zstd(zstd::Encoder<'static, Vec<u8>>),
+}
+
+/// Without the codecs, no file is compressed.
+#[cfg(all(feature = "async", not(feature = "compression")))]
+enum BufferEncoder {}
+
+#[cfg(all(feature = "async", not(feature = "compression")))]
+impl BufferEncoder {
+    fn compress(&mut self, _bytes: &[u8]) -> io::Result<Vec<u8>> {
+        match *self {}
+    }
+
+    fn finish(self) -> io::Result<Vec<u8>> {
+        match self {}
+    }
+}
+
+#[cfg(all(feature = "async", feature = "compression"))]
+impl BufferEncoder {
+    /// Compresses bytes and takes the output produced so far.
+    fn compress(&mut self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            BufferEncoder::Gzip(encoder) => {
+                encoder.write_all(bytes)?;
+                Ok(std::mem::take(encoder.get_mut()))
+            }
+            BufferEncoder::Zstd(encoder) => {
+                encoder.write_all(bytes)?;
+                Ok(std::mem::take(encoder.get_mut()))
+            }
+        }
+    }
+
+    /// Ends the compressed stream and takes the remaining output.
+    fn finish(self) -> io::Result<Vec<u8>> {
+        match self {
+            BufferEncoder::Gzip(encoder) => encoder.finish(),
+            BufferEncoder::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+/// A file being written asynchronously, compressed on the fly.
+///
+/// Shutting the file down writes the end of the compressed stream.
+#[cfg(feature = "async")]
+pub(crate) struct AsyncOutputFile {
+    /// The file.
+    file: tokio::io::BufWriter<tokio::fs::File>,
+    /// Compresses the bytes written, or `None` for a plain file.
+    encoder: Option<BufferEncoder>,
+    /// The compressed output not written to the file yet.
+    pending: Vec<u8>,
+    /// The position in the pending output.
+    offset: usize,
+}
+
+#[cfg(feature = "async")]
+impl AsyncOutputFile {
+    /// Creates, or truncates, a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    /// * `compression` - The compression of the file; zip archives are not supported.
+    ///
+    /// # Returns `io::Result<AsyncOutputFile>`
+    ///
+    /// Returns the file, or the error creating it.
+    pub(crate) async fn create(path: &Path, compression: Compression) -> io::Result<AsyncOutputFile> {
+        let encoder = match compression {
+            Compression::None => None,
+            #[cfg(feature = "compression")]
+            Compression::Gzip => Some(BufferEncoder::Gzip(GzEncoder::new(Vec::new(), flate2::Compression::default()))),
+            #[cfg(feature = "compression")]
+            Compression::Zstd => Some(BufferEncoder::Zstd(zstd::Encoder::new(Vec::new(), 0)?)),
+            #[cfg(not(feature = "compression"))]
+            Compression::Gzip | Compression::Zstd => return Err(compression_disabled(compression)),
+            Compression::Zip => return Err(zip_output_unsupported()),
+        };
+
+        let file = tokio::fs::File::create(path).await?;
+
+        Ok(AsyncOutputFile {
+            file: tokio::io::BufWriter::new(file),
+            encoder,
+            pending: Vec::new(),
+            offset: 0,
+        })
+    }
+
+    /// Writes the pending compressed output to the file.
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.offset < self.pending.len() {
+            let written = ready!(Pin::new(&mut self.file).poll_write(cx, &self.pending[self.offset..]))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.offset += written;
+        }
+
+        self.pending.clear();
+        self.offset = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncWrite for AsyncOutputFile {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.encoder.is_none() {
+            return Pin::new(&mut this.file).poll_write(cx, buf);
+        }
+
+        ready!(this.poll_drain(cx))?;
+        this.pending = this.encoder.as_mut().unwrap().compress(buf)?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.file).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_drain(cx))?;
+        if let Some(encoder) = this.encoder.take() {
+            this.pending = encoder.finish()?;
+        }
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.file).poll_shutdown(cx)
+    }
+}
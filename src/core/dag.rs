@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+
+/// Schedules the steps of a job following the dependencies they declare.
+///
+/// A step becomes ready once every step it depends on has completed, and a failed step prunes every
+/// step depending on it, directly or transitively.
+pub(crate) struct DagScheduler {
+    /// The indexes of the steps depending on each step.
+    dependents: Vec<Vec<usize>>,
+    /// The number of dependencies of each step that have not completed yet.
+    pending: Vec<usize>,
+    /// Whether each step has already been released or pruned.
+    done: Vec<bool>,
+    /// The steps whose dependencies have all completed.
+    ready: VecDeque<usize>,
+}
+
+impl DagScheduler {
+    /// Creates a scheduler for the given steps.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - The name and the dependency names of each step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a step name is duplicated, if a dependency does not exist or if the dependencies
+    /// form a cycle.
+    pub(crate) fn new(steps: &[(&str, &[String])]) -> Self {
+        let names: Vec<&str> = steps.iter().map(|(name, _)| *name).collect();
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].contains(name) {
+                panic!("Step names must be unique to declare dependencies, found {} more than once", name);
+            }
+        }
+
+        let mut dependents = vec![Vec::new(); steps.len()];
+        let mut pending = vec![0; steps.len()];
+        for (index, (name, dependencies)) in steps.iter().enumerate() {
+            for dependency in dependencies.iter() {
+                let Some(dependency_index) = names.iter().position(|name| name == dependency) else {
+                    panic!("Step {} depends on step {} which is not part of the job", name, dependency);
+                };
+                dependents[dependency_index].push(index);
+                pending[index] += 1;
+            }
+        }
+
+        let ready = (0..steps.len()).filter(|index| pending[*index] == 0).collect();
+        let scheduler = DagScheduler {
+            dependents,
+            pending,
+            done: vec![false; steps.len()],
+            ready,
+        };
+        scheduler.check_cycles(&names);
+        scheduler
+    }
+
+    /// Panics if some steps can never become ready because their dependencies form a cycle.
+    fn check_cycles(&self, names: &[&str]) {
+        let mut pending = self.pending.clone();
+        let mut queue: VecDeque<usize> = self.ready.clone();
+        let mut visited = 0;
+        while let Some(index) = queue.pop_front() {
+            visited += 1;
+            for dependent in &self.dependents[index] {
+                pending[*dependent] -= 1;
+                if pending[*dependent] == 0 {
+                    queue.push_back(*dependent);
+                }
+            }
+        }
+
+        if visited < names.len() {
+            let cycle: Vec<&str> = (0..names.len())
+                .filter(|index| pending[*index] > 0)
+                .map(|index| names[index])
+                .collect();
+            panic!("Step dependencies form a cycle between steps {}", cycle.join(", "));
+        }
+    }
+
+    /// Returns the next step whose dependencies have all completed, if any.
+    pub(crate) fn next_ready(&mut self) -> Option<usize> {
+        let index = self.ready.pop_front()?;
+        self.done[index] = true;
+        Some(index)
+    }
+
+    /// Records the end of a step, releasing its dependents or pruning them if it failed.
+    ///
+    /// # Returns `Vec<usize>`
+    ///
+    /// Returns the indexes of the steps pruned by the failure.
+    pub(crate) fn complete(&mut self, index: usize, succeeded: bool) -> Vec<usize> {
+        if succeeded {
+            for dependent in self.dependents[index].clone() {
+                self.pending[dependent] -= 1;
+                if self.pending[dependent] == 0 && !self.done[dependent] {
+                    self.ready.push_back(dependent);
+                }
+            }
+            return Vec::new();
+        }
+
+        let mut pruned = Vec::new();
+        let mut queue: VecDeque<usize> = self.dependents[index].clone().into();
+        while let Some(dependent) = queue.pop_front() {
+            if self.done[dependent] {
+                continue;
+            }
+            self.done[dependent] = true;
+            pruned.push(dependent);
+            queue.extend(self.dependents[dependent].iter().copied());
+        }
+        pruned
+    }
+}
@@ -0,0 +1,569 @@
+/// The type a field of a flat file record is converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// The text of the field, with its padding removed.
+    Text,
+    /// A signed integer, e.g. `00042` or `-17`.
+    Integer,
+    /// A decimal number, e.g. `0012.50`.
+    Decimal,
+    /// A boolean, written as `Y`/`N`, `true`/`false`, `yes`/`no` or `1`/`0`.
+    Boolean,
+}
+
+/// The side a field value is aligned to within its width, the padding filling the other side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Right,
+}
+
+/// The converted value of a field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Text(String),
+    Integer(i64),
+    Decimal(f64),
+    Boolean(bool),
+    /// A non-text field holding only blanks.
+    Empty,
+}
+
+impl FieldValue {
+    /// Returns the text of a text field.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            FieldValue::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an integer field.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            FieldValue::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a decimal or integer field.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            FieldValue::Decimal(value) => Some(*value),
+            FieldValue::Integer(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a boolean field.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            FieldValue::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the text written for the value.
+    fn to_text(&self) -> String {
+        match self {
+            FieldValue::Text(text) => text.clone(),
+            FieldValue::Integer(value) => value.to_string(),
+            FieldValue::Decimal(value) => value.to_string(),
+            FieldValue::Boolean(true) => String::from("Y"),
+            FieldValue::Boolean(false) => String::from("N"),
+            FieldValue::Empty => String::new(),
+        }
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        FieldValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        FieldValue::Text(value)
+    }
+}
+
+impl From<i64> for FieldValue {
+    fn from(value: i64) -> Self {
+        FieldValue::Integer(value)
+    }
+}
+
+impl From<f64> for FieldValue {
+    fn from(value: f64) -> Self {
+        FieldValue::Decimal(value)
+    }
+}
+
+impl From<bool> for FieldValue {
+    fn from(value: bool) -> Self {
+        FieldValue::Boolean(value)
+    }
+}
+
+/// Describes a field of a flat file record.
+///
+/// In a fixed-width layout, the field spans `length` characters from the zero based `start`
+/// column. In a delimited layout, the fields are the columns in declaration order and their
+/// position is ignored.
+#[derive(Debug, Clone)]
+pub struct FieldSpec {
+    name: String,
+    start: usize,
+    length: usize,
+    field_type: FieldType,
+    alignment: Alignment,
+    /// The character filling the unused width of the field.
+    pad: char,
+    /// Whether the padding is removed from text fields when reading.
+    trim: bool,
+}
+
+impl FieldSpec {
+    /// Creates a left aligned, space padded text field.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the field.
+    /// * `start` - The zero based column the field starts at.
+    /// * `length` - The width of the field.
+    ///
+    /// # Returns
+    ///
+    /// Returns the field specification.
+    pub fn new(name: impl Into<String>, start: usize, length: usize) -> Self {
+        FieldSpec {
+            name: name.into(),
+            start,
+            length,
+            field_type: FieldType::Text,
+            alignment: Alignment::Left,
+            pad: ' ',
+            trim: true,
+        }
+    }
+
+    /// Creates a text field of a delimited layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the field.
+    ///
+    /// # Returns
+    ///
+    /// Returns the field specification.
+    pub fn column(name: impl Into<String>) -> Self {
+        FieldSpec::new(name, 0, 0)
+    }
+
+    /// Sets the type the field is converted to.
+    pub fn field_type(self, field_type: FieldType) -> Self {
+        FieldSpec { field_type, ..self }
+    }
+
+    /// Sets the alignment of the field, e.g. `Alignment::Right` for zero padded numbers.
+    pub fn alignment(self, alignment: Alignment) -> Self {
+        FieldSpec { alignment, ..self }
+    }
+
+    /// Sets the character filling the unused width of the field.
+    pub fn pad(self, pad: char) -> Self {
+        FieldSpec { pad, ..self }
+    }
+
+    /// Sets whether the padding is removed from text fields when reading.
+    pub fn trim(self, trim: bool) -> Self {
+        FieldSpec { trim, ..self }
+    }
+
+    /// Converts the raw text of the field.
+    fn convert(&self, raw: &str) -> Result<FieldValue, String> {
+        if self.field_type == FieldType::Text {
+            let text = match (self.trim, self.alignment) {
+                (false, _) => raw,
+                (true, Alignment::Left) => raw.trim_end_matches(self.pad),
+                (true, Alignment::Right) => raw.trim_start_matches(self.pad),
+            };
+            return Ok(FieldValue::Text(text.to_string()));
+        }
+
+        let text = raw.trim();
+        if text.is_empty() {
+            return Ok(FieldValue::Empty);
+        }
+
+        let invalid = |err: &dyn std::fmt::Display| format!("invalid value {:?} for field {}: {}", text, self.name, err);
+        match self.field_type {
+            FieldType::Integer => text.parse().map(FieldValue::Integer).map_err(|err| invalid(&err)),
+            FieldType::Decimal => text.parse().map(FieldValue::Decimal).map_err(|err| invalid(&err)),
+            FieldType::Boolean => match text.to_ascii_lowercase().as_str() {
+                "y" | "yes" | "true" | "1" => Ok(FieldValue::Boolean(true)),
+                "n" | "no" | "false" | "0" => Ok(FieldValue::Boolean(false)),
+                _ => Err(invalid(&"not a boolean")),
+            },
+            FieldType::Text => unreachable!(),
+        }
+    }
+
+    /// Pads the text of a value to the width of the field.
+    fn pad_text(&self, text: String) -> Result<String, String> {
+        let length = text.chars().count();
+        if length > self.length {
+            return Err(format!("value {:?} of field {} is longer than {} characters", text, self.name, self.length));
+        }
+
+        let padding: String = std::iter::repeat_n(self.pad, self.length - length).collect();
+        Ok(match self.alignment {
+            Alignment::Left => text + &padding,
+            // Zero padding goes between the sign and the digits.
+            Alignment::Right if self.pad == '0' && text.starts_with('-') => format!("-{}{}", padding, &text[1..]),
+            Alignment::Right => padding + &text,
+        })
+    }
+}
+
+/// The layout of a kind of record of a flat file.
+///
+/// A file mixing several kinds of records, e.g. headers, details and trailers, has one layout per
+/// kind, told apart by the prefix their lines start with.
+#[derive(Debug, Clone)]
+pub struct RecordLayout {
+    name: String,
+    /// The text the lines of the record start at, empty to match any line.
+    prefix: String,
+    /// The number of physical lines a record spans, joined before the fields are extracted.
+    lines: usize,
+    /// The field delimiter, or `None` for fixed-width records.
+    delimiter: Option<char>,
+    fields: Vec<FieldSpec>,
+}
+
+impl RecordLayout {
+    /// Creates the layout of fixed-width records spanning a single line.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the record type, reported by the records read.
+    /// * `fields` - The fields of the record.
+    ///
+    /// # Returns
+    ///
+    /// Returns the record layout.
+    pub fn fixed_width(name: impl Into<String>, fields: Vec<FieldSpec>) -> Self {
+        RecordLayout {
+            name: name.into(),
+            prefix: String::new(),
+            lines: 1,
+            delimiter: None,
+            fields,
+        }
+    }
+
+    /// Creates the layout of delimited records spanning a single line.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the record type, reported by the records read.
+    /// * `delimiter` - The field delimiter.
+    /// * `fields` - The fields of the record, in column order.
+    ///
+    /// # Returns
+    ///
+    /// Returns the record layout.
+    pub fn delimited(name: impl Into<String>, delimiter: char, fields: Vec<FieldSpec>) -> Self {
+        RecordLayout {
+            delimiter: Some(delimiter),
+            ..RecordLayout::fixed_width(name, fields)
+        }
+    }
+
+    /// Sets the prefix identifying the lines of this record type.
+    pub fn prefix(self, prefix: impl Into<String>) -> Self {
+        RecordLayout { prefix: prefix.into(), ..self }
+    }
+
+    /// Sets the number of physical lines a record spans. The lines are joined, without their line
+    /// breaks, before the fields are extracted.
+    pub fn lines(self, lines: usize) -> Self {
+        RecordLayout { lines, ..self }
+    }
+
+    /// Returns the name of the record type.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of physical lines a record spans.
+    pub(crate) fn line_count(&self) -> usize {
+        self.lines
+    }
+
+    /// Validates the layout configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layout has no fields, spans no line or has overlapping fixed-width fields.
+    pub(crate) fn validate(&self) {
+        if self.fields.is_empty() {
+            panic!("Record layout {} has no fields", self.name);
+        }
+
+        if self.lines == 0 {
+            panic!("Record layout {} must span at least one line", self.name);
+        }
+
+        if self.delimiter.is_none() {
+            let mut fields: Vec<&FieldSpec> = self.fields.iter().collect();
+            fields.sort_by_key(|field| field.start);
+            for pair in fields.windows(2) {
+                if pair[1].start < pair[0].start + pair[0].length {
+                    panic!("Fields {} and {} of record layout {} overlap", pair[0].name, pair[1].name, self.name);
+                }
+            }
+        }
+    }
+
+    /// Extracts and converts the fields of a record.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text of the record.
+    /// * `line` - The line the record starts at.
+    ///
+    /// # Returns `Result<FlatRecord, String>`
+    ///
+    /// Returns the record, or an error if a delimited record has more columns than fields or a
+    /// field could not be converted.
+    pub(crate) fn tokenize(&self, text: &str, line: u64) -> Result<FlatRecord, String> {
+        let raw_fields: Vec<String> = match self.delimiter {
+            Some(delimiter) => {
+                let columns: Vec<String> = text.split(delimiter).map(str::to_string).collect();
+                if columns.len() > self.fields.len() {
+                    return Err(format!("record layout {} has {} fields, got {} columns", self.name, self.fields.len(), columns.len()));
+                }
+                columns
+            }
+            None => {
+                let chars: Vec<char> = text.chars().collect();
+                self.fields.iter()
+                    .map(|field| {
+                        let start = field.start.min(chars.len());
+                        let end = (field.start + field.length).min(chars.len());
+                        chars[start..end].iter().collect()
+                    })
+                    .collect()
+            }
+        };
+
+        let mut fields = Vec::with_capacity(self.fields.len());
+        for (index, field) in self.fields.iter().enumerate() {
+            let raw = raw_fields.get(index).map(String::as_str).unwrap_or("");
+            fields.push((field.name.clone(), field.convert(raw)?));
+        }
+
+        Ok(FlatRecord {
+            line,
+            record_type: self.name.clone(),
+            fields,
+        })
+    }
+
+    /// Formats the values of a record.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values of the fields, in declaration order.
+    ///
+    /// # Returns `Result<String, String>`
+    ///
+    /// Returns the text of the record, without line break, or an error if a value does not fit
+    /// its field, holds a line break or, in a delimited layout, holds the delimiter.
+    pub(crate) fn format(&self, values: Vec<FieldValue>) -> Result<String, String> {
+        if values.len() != self.fields.len() {
+            return Err(format!("record layout {} has {} fields, got {} values", self.name, self.fields.len(), values.len()));
+        }
+
+        let mut texts = Vec::with_capacity(values.len());
+        for (field, value) in self.fields.iter().zip(values) {
+            let text = value.to_text();
+            if text.contains(['\n', '\r']) {
+                return Err(format!("value {:?} of field {} holds a line break", text, field.name));
+            }
+            if self.delimiter.is_some_and(|delimiter| text.contains(delimiter)) {
+                return Err(format!("value {:?} of field {} holds the delimiter", text, field.name));
+            }
+            texts.push(text);
+        }
+
+        if let Some(delimiter) = self.delimiter {
+            return Ok(texts.join(&delimiter.to_string()));
+        }
+
+        let width = self.fields.iter().map(|field| field.start + field.length).max().unwrap_or(0);
+        let mut chars = vec![' '; width];
+        for (field, text) in self.fields.iter().zip(texts) {
+            let text = field.pad_text(text)?;
+            for (offset, char) in text.chars().enumerate() {
+                chars[field.start + offset] = char;
+            }
+        }
+        Ok(chars.into_iter().collect())
+    }
+}
+
+/// Returns the layout of a line, preferring the longest matching prefix.
+pub(crate) fn select_layout<'a>(layouts: &'a [RecordLayout], line: &str) -> Option<&'a RecordLayout> {
+    layouts.iter()
+        .filter(|layout| line.starts_with(&layout.prefix))
+        .max_by_key(|layout| layout.prefix.len())
+}
+
+/// A record read from a flat file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlatRecord {
+    /// The line the record starts at, starting at one.
+    pub line: u64,
+    /// The name of the layout the record was read with.
+    pub record_type: String,
+    fields: Vec<(String, FieldValue)>,
+}
+
+impl FlatRecord {
+    /// Returns the value of a field.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the field.
+    ///
+    /// # Returns `Option<&FieldValue>`
+    ///
+    /// Returns the value, or `None` if the record has no such field.
+    pub fn get(&self, name: &str) -> Option<&FieldValue> {
+        self.fields.iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the text of a text field.
+    pub fn text(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(FieldValue::as_str)
+    }
+
+    /// Returns the value of an integer field.
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        self.get(name).and_then(FieldValue::as_i64)
+    }
+
+    /// Returns the value of a decimal or integer field.
+    pub fn decimal(&self, name: &str) -> Option<f64> {
+        self.get(name).and_then(FieldValue::as_f64)
+    }
+
+    /// Returns the value of a boolean field.
+    pub fn boolean(&self, name: &str) -> Option<bool> {
+        self.get(name).and_then(FieldValue::as_bool)
+    }
+
+    /// Returns the fields of the record, in declaration order.
+    pub fn fields(&self) -> &[(String, FieldValue)] {
+        &self.fields
+    }
+}
+
+/// A record whose lines are being read.
+struct PendingRecord {
+    /// The index of the layout of the record.
+    layout: usize,
+    /// The lines read so far, joined.
+    text: String,
+    /// The line the record starts at.
+    line: u64,
+    /// The number of lines still to read.
+    remaining: usize,
+}
+
+/// Turns the lines of a flat file into records, shared by the synchronous and asynchronous flat
+/// file readers.
+pub(crate) struct RecordAssembler {
+    layouts: Vec<RecordLayout>,
+    /// The number of lines skipped at the start of the file.
+    skip_lines: usize,
+    /// The number of lines read so far.
+    line: u64,
+    pending: Option<PendingRecord>,
+}
+
+impl RecordAssembler {
+    pub(crate) fn new(layouts: Vec<RecordLayout>, skip_lines: usize) -> Self {
+        RecordAssembler {
+            layouts,
+            skip_lines,
+            line: 0,
+            pending: None,
+        }
+    }
+
+    /// Feeds the next line of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The line, with or without its line break.
+    ///
+    /// # Returns `Result<Option<FlatRecord>, String>`
+    ///
+    /// Returns the record completed by the line, if any, or an error if no layout matches the line
+    /// or a field cannot be converted.
+    pub(crate) fn push(&mut self, text: &str) -> Result<Option<FlatRecord>, String> {
+        self.line += 1;
+        let text = text.trim_end_matches(['\n', '\r']);
+
+        if self.line <= self.skip_lines as u64 {
+            return Ok(None);
+        }
+
+        let mut pending = match self.pending.take() {
+            Some(mut pending) => {
+                pending.text.push_str(text);
+                pending.remaining -= 1;
+                pending
+            }
+            None => {
+                if text.trim().is_empty() {
+                    return Ok(None);
+                }
+
+                let layout = select_layout(&self.layouts, text)
+                    .ok_or_else(|| format!("no record layout matches line {}", self.line))?;
+                PendingRecord {
+                    layout: self.layouts.iter().position(|candidate| std::ptr::eq(candidate, layout)).unwrap(),
+                    text: text.to_string(),
+                    line: self.line,
+                    remaining: layout.line_count() - 1,
+                }
+            }
+        };
+
+        if pending.remaining > 0 {
+            self.pending = Some(pending);
+            return Ok(None);
+        }
+
+        let text = std::mem::take(&mut pending.text);
+        self.layouts[pending.layout].tokenize(&text, pending.line)
+            .map(Some)
+            .map_err(|err| format!("{} at line {}", err, pending.line))
+    }
+
+    /// Checks that the file did not end in the middle of a record.
+    pub(crate) fn finish(&self) -> Result<(), String> {
+        match &self.pending {
+            Some(pending) => Err(format!("incomplete record at line {}", pending.line)),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use crate::core::step::FAILED;
+
+/// Represents what a job does once a step ends with a matching exit status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transition {
+    /// Runs the step with the given name next.
+    To(String),
+    /// Ends the job as completed.
+    End,
+    /// Ends the job as failed.
+    Fail,
+    /// Stops the job, leaving the remaining steps unexecuted.
+    Stop,
+}
+
+/// Represents the transitions between the steps of a job, declared per step exit status.
+#[derive(Debug, Clone)]
+pub struct Flow {
+    /// The name of the first step to run.
+    start: String,
+    /// The transitions of each step, as exit status patterns in declaration order.
+    transitions: HashMap<String, Vec<(String, Transition)>>,
+}
+
+/// Represents how a job continues once a step of its flow ends.
+pub(crate) enum FlowOutcome {
+    /// Runs the step with the given name next.
+    Next(String),
+    /// Finishes the job with the given status.
+    Finished(Result<String, String>),
+}
+
+impl Flow {
+    /// Returns the name of the first step to run.
+    pub fn start(&self) -> &str {
+        &self.start
+    }
+
+    /// Returns the names of every step referenced by the flow.
+    pub fn step_names(&self) -> Vec<&str> {
+        let mut names = vec![self.start.as_str()];
+        for (from, transitions) in &self.transitions {
+            names.push(from);
+            for (_, transition) in transitions {
+                if let Transition::To(to) = transition {
+                    names.push(to);
+                }
+            }
+        }
+        names
+    }
+
+    /// Finds the transition of a step for the given exit status.
+    ///
+    /// An exact pattern takes precedence over wildcard ones, which are evaluated in declaration order.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step that ended.
+    /// * `exit_status` - The exit status of the step.
+    ///
+    /// # Returns `Option<&Transition>`
+    ///
+    /// Returns the matching transition, or `None` when no pattern matches.
+    pub fn next(&self, step_name: &str, exit_status: &str) -> Option<&Transition> {
+        let transitions = self.transitions.get(step_name)?;
+        transitions.iter()
+            .find(|(pattern, _)| pattern == exit_status)
+            .or_else(|| transitions.iter().find(|(pattern, _)| matches_pattern(pattern, exit_status)))
+            .map(|(_, transition)| transition)
+    }
+
+    /// Resolves how the job continues after a step ended with the given exit status.
+    ///
+    /// Without a matching transition the job fails if the step failed and is not throw tolerant,
+    /// and completes otherwise.
+    pub(crate) fn resolve(&self, job_name: &str, step_name: &str, exit_status: &str, throw_tolerant: bool) -> FlowOutcome {
+        match self.next(step_name, exit_status) {
+            Some(Transition::To(next)) => FlowOutcome::Next(next.clone()),
+            Some(Transition::End) => FlowOutcome::Finished(Ok(format!("Job {} completed", job_name))),
+            Some(Transition::Fail) => FlowOutcome::Finished(Err(format!("Job {} failed", job_name))),
+            Some(Transition::Stop) => FlowOutcome::Finished(Ok(format!("Job {} stopped after step {}", job_name, step_name))),
+            None if exit_status == FAILED && !throw_tolerant => FlowOutcome::Finished(Err(format!("Job {} failed", job_name))),
+            None => FlowOutcome::Finished(Ok(format!("Job {} completed", job_name))),
+        }
+    }
+
+    /// Validates that every step referenced by the flow exists, once, in the given step names.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a referenced step is missing or if a step name is used more than once.
+    pub(crate) fn validate(&self, step_names: &[&str]) {
+        for (index, name) in step_names.iter().enumerate() {
+            if step_names[..index].contains(name) {
+                panic!("Step names must be unique to run a flow, found {} more than once", name);
+            }
+        }
+
+        for name in self.step_names() {
+            if !step_names.contains(&name) {
+                panic!("Flow references step {} which is not part of the job", name);
+            }
+        }
+    }
+}
+
+/// Checks if an exit status matches a pattern, where `*` matches any sequence of characters and `?`
+/// matches a single one.
+fn matches_pattern(pattern: &str, exit_status: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let exit_status: Vec<char> = exit_status.chars().collect();
+    let (mut p, mut e) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while e < exit_status.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == exit_status[e]) {
+            p += 1;
+            e += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, e));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            e = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// A builder struct for declaring the transitions of a flow.
+pub struct FlowBuilder {
+    /// The flow being constructed.
+    flow: Flow,
+    /// The step the next transitions are declared for.
+    from: String,
+}
+
+impl FlowBuilder {
+    /// Initializes a new flow starting with the given step.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the first step to run.
+    ///
+    /// # Returns `FlowBuilder`
+    ///
+    /// Returns a new builder instance declaring the transitions of the first step.
+    pub fn start(step_name: &str) -> Self {
+        FlowBuilder {
+            flow: Flow {
+                start: step_name.to_string(),
+                transitions: HashMap::new(),
+            },
+            from: step_name.to_string(),
+        }
+    }
+
+    /// Selects the step the next transitions are declared for.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    pub fn from(self, step_name: &str) -> Self {
+        FlowBuilder {
+            from: step_name.to_string(),
+            ..self
+        }
+    }
+
+    /// Starts a transition for the exit statuses matching the given pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The exit status, where `*` matches any characters and `?` a single one.
+    ///
+    /// # Returns `TransitionBuilder`
+    ///
+    /// Returns a builder for the transition target.
+    pub fn on(self, pattern: &str) -> TransitionBuilder {
+        TransitionBuilder {
+            builder: self,
+            pattern: pattern.to_string(),
+        }
+    }
+
+    /// Builds and returns the configured flow.
+    ///
+    /// # Returns `Flow`
+    ///
+    /// Returns the configured flow.
+    pub fn build(self) -> Flow {
+        self.flow
+    }
+
+    /// Adds a transition to the step currently selected.
+    fn transition(mut self, pattern: String, transition: Transition) -> Self {
+        self.flow.transitions
+            .entry(self.from.clone())
+            .or_default()
+            .push((pattern, transition));
+        self
+    }
+}
+
+/// A builder struct for the target of a transition.
+pub struct TransitionBuilder {
+    /// The flow builder the transition is added to.
+    builder: FlowBuilder,
+    /// The exit status pattern of the transition.
+    pattern: String,
+}
+
+impl TransitionBuilder {
+    /// Runs the given step next.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the next step.
+    ///
+    /// # Returns `FlowBuilder`
+    ///
+    /// Returns the flow builder.
+    pub fn to(self, step_name: &str) -> FlowBuilder {
+        self.builder.transition(self.pattern, Transition::To(step_name.to_string()))
+    }
+
+    /// Ends the job as completed.
+    ///
+    /// # Returns `FlowBuilder`
+    ///
+    /// Returns the flow builder.
+    pub fn end(self) -> FlowBuilder {
+        self.builder.transition(self.pattern, Transition::End)
+    }
+
+    /// Ends the job as failed.
+    ///
+    /// # Returns `FlowBuilder`
+    ///
+    /// Returns the flow builder.
+    pub fn fail(self) -> FlowBuilder {
+        self.builder.transition(self.pattern, Transition::Fail)
+    }
+
+    /// Stops the job, leaving the remaining steps unexecuted.
+    ///
+    /// # Returns `FlowBuilder`
+    ///
+    /// Returns the flow builder.
+    pub fn stop(self) -> FlowBuilder {
+        self.builder.transition(self.pattern, Transition::Stop)
+    }
+}
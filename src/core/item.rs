@@ -0,0 +1,67 @@
+use std::path::Path;
+use std::sync::Arc;
+
+/// An item together with the position it was read from.
+///
+/// File based readers yield this type instead of the bare item when the complex step is built for
+/// it, so that the processor and the listeners can report the line of a faulty record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Positioned<T> {
+    /// The line the item starts at, starting at one.
+    pub line: u64,
+    /// The item read.
+    pub item: T,
+}
+
+/// An item together with the resource, e.g. the file, it was read from.
+///
+/// Yielded by the multi-resource readers. When the per-resource reader yields `Positioned` items,
+/// the line of each item is available as well.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceItem<T> {
+    /// The resource the item was read from.
+    pub resource: Arc<Path>,
+    /// The number of the item within its resource, starting at one.
+    pub index: u64,
+    /// The item read.
+    pub item: T,
+}
+
+/// How a composite writer handles a delegate failing to write a chunk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompositeMode {
+    /// The delegates write the chunk in order and the first failure fails the chunk, without
+    /// calling the remaining delegates. Delegates that already wrote the chunk are not rolled
+    /// back, so the chunk may be left written by some of them only.
+    #[default]
+    FailFast,
+    /// The delegates write the chunk in order and the first failure fails the chunk, after the
+    /// delegates that already wrote it have been compensated in reverse order. Every delegate but
+    /// the last one needs a compensator, e.g. deleting the rows it inserted.
+    AllOrNothing,
+    /// Every delegate writes the chunk and failures are logged. The chunk only fails when every
+    /// delegate failed.
+    BestEffort,
+}
+
+/// A trait for checking an item, used by the validating processors.
+///
+/// Any `Fn(&T) -> Result<(), String>` closure is a validator.
+pub trait Validator<T> {
+    /// Checks an item.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item to check.
+    ///
+    /// # Returns `Result<(), String>`
+    ///
+    /// Returns `Ok` for a valid item, or the reason it is invalid.
+    fn validate(&self, item: &T) -> Result<(), String>;
+}
+
+impl<T, F: Fn(&T) -> Result<(), String>> Validator<T> for F {
+    fn validate(&self, item: &T) -> Result<(), String> {
+        self(item)
+    }
+}
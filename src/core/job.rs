@@ -20,6 +20,32 @@ pub struct JobStatus {
     pub steps_status: Vec<StepStatus>,
 }
 
+/// Describes a job execution to its listeners before its first step runs.
+///
+/// The struct is non-exhaustive so that more details of the execution can be added later, and
+/// cannot be built outside of this crate.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct JobContext {
+    /// The name of the job.
+    pub name: String,
+    /// The names of the steps of the job, in the order they were added. Steps skipped by their
+    /// decider or left out by a flow are included.
+    pub step_names: Vec<String>,
+    /// The start time of the job execution, in milliseconds since the Unix epoch.
+    pub start_time: u128,
+}
+
+impl JobContext {
+    pub(crate) fn new(name: String, step_names: Vec<String>) -> Self {
+        JobContext {
+            name,
+            step_names,
+            start_time: now_time(),
+        }
+    }
+}
+
 /// Generates the end time of a job execution.
 pub fn now_time() -> u128 {
     return SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis();
@@ -0,0 +1,201 @@
+/// The layout of a JSON file read or written by the JSON item readers and writers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonFormat {
+    /// One JSON value per line, also known as NDJSON. Blank lines are ignored when reading.
+    #[default]
+    Lines,
+    /// A single top-level JSON array whose elements are the items.
+    Array,
+}
+
+/// Where the scanner is within the top-level array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    /// Before the opening bracket.
+    Start,
+    /// After the opening bracket, before the first element or the closing bracket.
+    BeforeElement,
+    /// After a comma, before the next element.
+    AfterComma,
+    /// Within an element.
+    Element,
+    /// After the closing bracket.
+    Done,
+}
+
+/// The line an array element starts at, and its bytes.
+pub(crate) type ScannedElement = (u64, Vec<u8>);
+
+/// Splits a top-level JSON array into the bytes of its elements, so that a huge array can be
+/// deserialized one element at a time.
+///
+/// The scanner only tracks strings and nesting to find the commas separating the elements; the
+/// elements themselves are validated when they are deserialized.
+pub(crate) struct JsonArrayScanner {
+    state: ScanState,
+    /// The nesting depth within the current element.
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    /// The bytes of the current element.
+    element: Vec<u8>,
+    /// The current line, starting at one.
+    line: u64,
+    /// The line the current element starts at.
+    element_line: u64,
+}
+
+impl JsonArrayScanner {
+    pub(crate) fn new() -> Self {
+        JsonArrayScanner {
+            state: ScanState::Start,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            element: Vec::new(),
+            line: 1,
+            element_line: 1,
+        }
+    }
+
+    /// Returns the current line, starting at one.
+    pub(crate) fn line(&self) -> u64 {
+        self.line
+    }
+
+    /// Returns whether the closing bracket of the array has been scanned.
+    pub(crate) fn is_done(&self) -> bool {
+        self.state == ScanState::Done
+    }
+
+    /// Scans bytes until the end of the next element.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The bytes available from the input.
+    ///
+    /// # Returns `Result<(usize, Option<ScannedElement>), String>`
+    ///
+    /// Returns the number of bytes consumed and, when an element ended within them, its line and
+    /// bytes, or an error if the input is not an array.
+    pub(crate) fn scan(&mut self, bytes: &[u8]) -> Result<(usize, Option<ScannedElement>), String> {
+        for (index, &byte) in bytes.iter().enumerate() {
+            if let Some(element) = self.scan_byte(byte)? {
+                return Ok((index + 1, Some(element)));
+            }
+        }
+        Ok((bytes.len(), None))
+    }
+
+    fn scan_byte(&mut self, byte: u8) -> Result<Option<ScannedElement>, String> {
+        if byte == b'\n' {
+            self.line += 1;
+        }
+
+        match self.state {
+            ScanState::Start => match byte {
+                b'[' => self.state = ScanState::BeforeElement,
+                byte if byte.is_ascii_whitespace() => {}
+                _ => return Err(format!("expected a JSON array at line {}", self.line)),
+            },
+            ScanState::BeforeElement | ScanState::AfterComma => match byte {
+                b']' if self.state == ScanState::BeforeElement => self.state = ScanState::Done,
+                b']' => return Err(format!("trailing comma in the JSON array at line {}", self.line)),
+                b',' => return Err(format!("missing element in the JSON array at line {}", self.line)),
+                byte if byte.is_ascii_whitespace() => {}
+                _ => {
+                    self.state = ScanState::Element;
+                    self.element_line = self.line;
+                    return self.scan_element_byte(byte);
+                }
+            },
+            ScanState::Element => return self.scan_element_byte(byte),
+            ScanState::Done => {
+                if !byte.is_ascii_whitespace() {
+                    return Err(format!("unexpected data after the JSON array at line {}", self.line));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn scan_element_byte(&mut self, byte: u8) -> Result<Option<ScannedElement>, String> {
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if byte == b'\\' {
+                self.escaped = true;
+            } else if byte == b'"' {
+                self.in_string = false;
+            }
+            self.element.push(byte);
+            return Ok(None);
+        }
+
+        match byte {
+            b',' | b']' if self.depth == 0 => {
+                self.state = if byte == b',' { ScanState::AfterComma } else { ScanState::Done };
+                let element = std::mem::take(&mut self.element);
+                return Ok(Some((self.element_line, element)));
+            }
+            b'"' => self.in_string = true,
+            b'{' | b'[' => self.depth += 1,
+            b'}' | b']' => {
+                self.depth = self.depth.checked_sub(1)
+                    .ok_or_else(|| format!("unbalanced JSON array at line {}", self.line))?;
+            }
+            _ => {}
+        }
+        self.element.push(byte);
+        Ok(None)
+    }
+}
+
+/// Writes the separators of a JSON file around the items serialized by the JSON item writers.
+pub(crate) struct JsonFraming {
+    format: JsonFormat,
+    /// Whether an item has been written.
+    started: bool,
+}
+
+impl JsonFraming {
+    pub(crate) fn new(format: JsonFormat) -> Self {
+        JsonFraming { format, started: false }
+    }
+
+    /// Returns the bytes written when the file is created.
+    pub(crate) fn header(&self) -> &'static [u8] {
+        match self.format {
+            JsonFormat::Lines => b"",
+            JsonFormat::Array => b"[",
+        }
+    }
+
+    /// Returns the bytes written before an item.
+    pub(crate) fn separator(&mut self) -> &'static [u8] {
+        let first = !self.started;
+        self.started = true;
+        match (self.format, first) {
+            (JsonFormat::Lines, _) => b"",
+            (JsonFormat::Array, true) => b"\n",
+            (JsonFormat::Array, false) => b",\n",
+        }
+    }
+
+    /// Returns the bytes written after an item.
+    pub(crate) fn terminator(&self) -> &'static [u8] {
+        match self.format {
+            JsonFormat::Lines => b"\n",
+            JsonFormat::Array => b"",
+        }
+    }
+
+    /// Returns the bytes written when the file is closed.
+    pub(crate) fn footer(&self) -> &'static [u8] {
+        match (self.format, self.started) {
+            (JsonFormat::Lines, _) => b"",
+            (JsonFormat::Array, true) => b"\n]\n",
+            (JsonFormat::Array, false) => b"]\n",
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::any::Any;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A trait for listening to the chunks of a complex step.
+///
+/// The hooks are called from the chunk loop of every worker, so they must be cheap and safe to
+/// call concurrently. Chunk indexes are unique within a step execution, but chunks of different
+/// workers may be reported in any order.
+pub trait ChunkListener: Send + Sync {
+    /// Called when a chunk receives its first item, before it is processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_index` - The index of the chunk, starting at zero.
+    fn before_chunk(&self, _chunk_index: usize) {}
+
+    /// Called once a chunk has been written.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_index` - The index of the chunk.
+    fn after_chunk(&self, _chunk_index: usize) {}
+
+    /// Called when an item of a chunk fails to be processed or the chunk fails to be written.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_index` - The index of the chunk.
+    /// * `error` - The message of the error.
+    fn on_chunk_error(&self, _chunk_index: usize, _error: &str) {}
+}
+
+/// A trait for listening to the items read, processed and written by a complex step.
+///
+/// The hooks are called from the chunk loop of every worker, so they must be cheap and safe to
+/// call concurrently.
+pub trait ItemListener<I, O>: Send + Sync {
+    /// Called before an item is read.
+    fn before_read(&self) {}
+
+    /// Called after an item has been read.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item read.
+    fn after_read(&self, _item: &I) {}
+
+    /// Called when the reader fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The message of the error.
+    fn on_read_error(&self, _error: &str) {}
+
+    /// Called before an item is processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item to process.
+    fn before_process(&self, _item: &I) {}
+
+    /// Called after an item has been processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - The output of the processor.
+    fn after_process(&self, _output: &O) {}
+
+    /// Called when the processor fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The message of the error.
+    fn on_process_error(&self, _error: &str) {}
+
+    /// Called in place of `after_process` when the processor skips an item, e.g. an invalid one.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - The reason the item is skipped.
+    fn on_skip(&self, _reason: &str) {}
+
+    /// Called before a chunk is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items to write.
+    fn before_write(&self, _items: &[O]) {}
+
+    /// Called after a chunk has been written.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of items written.
+    fn after_write(&self, _count: usize) {}
+
+    /// Called when the writer fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The message of the error.
+    fn on_write_error(&self, _error: &str) {}
+}
+
+/// The chunk and item listeners of a complex step, shared by its workers.
+pub(crate) struct ChunkListeners<I, O> {
+    /// The listeners notified of chunk boundaries.
+    chunk_listeners: Vec<Arc<dyn ChunkListener>>,
+    /// The listeners notified of each phase of the items.
+    item_listeners: Vec<Arc<dyn ItemListener<I, O>>>,
+    /// The index given to the next chunk.
+    next_chunk: AtomicUsize,
+}
+
+impl<I, O> ChunkListeners<I, O> {
+    pub(crate) fn new(chunk_listeners: Vec<Arc<dyn ChunkListener>>, item_listeners: Vec<Arc<dyn ItemListener<I, O>>>) -> Self {
+        ChunkListeners {
+            chunk_listeners,
+            item_listeners,
+            next_chunk: AtomicUsize::new(0),
+        }
+    }
+
+    /// Gives an index to a new chunk and notifies its start.
+    pub(crate) fn open_chunk(&self) -> usize {
+        let chunk_index = self.next_chunk.fetch_add(1, Ordering::Relaxed);
+        self.chunk_listeners.iter().for_each(|listener| listener.before_chunk(chunk_index));
+        chunk_index
+    }
+
+    pub(crate) fn after_chunk(&self, chunk_index: usize) {
+        self.chunk_listeners.iter().for_each(|listener| listener.after_chunk(chunk_index));
+    }
+
+    pub(crate) fn on_chunk_error(&self, chunk_index: usize, error: &str) {
+        self.chunk_listeners.iter().for_each(|listener| listener.on_chunk_error(chunk_index, error));
+    }
+
+    pub(crate) fn before_read(&self) {
+        self.item_listeners.iter().for_each(|listener| listener.before_read());
+    }
+
+    pub(crate) fn after_read(&self, item: &I) {
+        self.item_listeners.iter().for_each(|listener| listener.after_read(item));
+    }
+
+    pub(crate) fn on_read_error(&self, error: &str) {
+        self.item_listeners.iter().for_each(|listener| listener.on_read_error(error));
+    }
+
+    pub(crate) fn before_process(&self, item: &I) {
+        self.item_listeners.iter().for_each(|listener| listener.before_process(item));
+    }
+
+    pub(crate) fn after_process(&self, output: &O) {
+        self.item_listeners.iter().for_each(|listener| listener.after_process(output));
+    }
+
+    pub(crate) fn on_skip(&self, reason: &str) {
+        self.item_listeners.iter().for_each(|listener| listener.on_skip(reason));
+    }
+
+    /// Notifies a processor failure to the item listeners and to the listeners of its chunk.
+    pub(crate) fn on_process_error(&self, chunk_index: usize, error: &str) {
+        self.item_listeners.iter().for_each(|listener| listener.on_process_error(error));
+        self.on_chunk_error(chunk_index, error);
+    }
+
+    pub(crate) fn before_write(&self, items: &[O]) {
+        self.item_listeners.iter().for_each(|listener| listener.before_write(items));
+    }
+
+    /// Notifies a successful write to the item listeners and the end of its chunk.
+    pub(crate) fn after_write(&self, chunk_index: usize, count: usize) {
+        self.item_listeners.iter().for_each(|listener| listener.after_write(count));
+        self.after_chunk(chunk_index);
+    }
+
+    /// Notifies a writer failure to the item listeners and to the listeners of its chunk.
+    pub(crate) fn on_write_error(&self, chunk_index: usize, error: &str) {
+        self.item_listeners.iter().for_each(|listener| listener.on_write_error(error));
+        self.on_chunk_error(chunk_index, error);
+    }
+}
+
+/// Returns the message carried by the payload of a panic.
+pub(crate) fn panic_message(cause: &(dyn Any + Send)) -> String {
+    if let Some(message) = cause.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    if let Some(message) = cause.downcast_ref::<String>() {
+        return message.clone();
+    }
+    String::from("unknown error")
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// The number of items read by complex steps.
+pub const ITEMS_READ: &str = "batch_items_read_total";
+/// The number of items written by complex steps.
+pub const ITEMS_WRITTEN: &str = "batch_items_written_total";
+/// The number of items dropped by throw tolerant complex steps after a processing or write error.
+pub const ITEMS_SKIPPED: &str = "batch_items_skipped_total";
+/// The latency of the chunk writes of complex steps.
+pub const CHUNK_WRITE_SECONDS: &str = "batch_chunk_write_seconds";
+/// The number of items waiting in the queues of the workers of complex steps.
+pub const WORKER_QUEUE_DEPTH: &str = "batch_worker_queue_depth";
+/// The duration of step runs.
+pub const STEP_DURATION_SECONDS: &str = "batch_step_duration_seconds";
+/// The number of job runs, by outcome.
+pub const JOB_RUNS: &str = "batch_job_runs_total";
+/// The duration of job runs.
+pub const JOB_DURATION_SECONDS: &str = "batch_job_duration_seconds";
+
+/// The upper bounds of the buckets of latency histograms, in seconds.
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// The upper bounds of the buckets of step and job durations, in seconds, from one second to
+/// six hours.
+const RUN_BUCKETS: [f64; 11] = [1.0, 5.0, 15.0, 30.0, 60.0, 300.0, 900.0, 1800.0, 3600.0, 10800.0, 21600.0];
+
+/// Returns the upper bounds of the buckets of a histogram.
+fn buckets(name: &str) -> &'static [f64] {
+    match name {
+        STEP_DURATION_SECONDS | JOB_DURATION_SECONDS => &RUN_BUCKETS,
+        _ => &LATENCY_BUCKETS,
+    }
+}
+
+/// The metrics recorded by the crate, with their type and description.
+const DESCRIPTORS: [(&str, &str, &str); 8] = [
+    (ITEMS_READ, "counter", "Items read by complex steps."),
+    (ITEMS_WRITTEN, "counter", "Items written by complex steps."),
+    (ITEMS_SKIPPED, "counter", "Items dropped by throw tolerant complex steps after an error."),
+    (CHUNK_WRITE_SECONDS, "histogram", "Latency of the chunk writes of complex steps."),
+    (WORKER_QUEUE_DEPTH, "gauge", "Items waiting in the queues of the workers of complex steps."),
+    (STEP_DURATION_SECONDS, "histogram", "Duration of step runs."),
+    (JOB_RUNS, "counter", "Job runs by outcome."),
+    (JOB_DURATION_SECONDS, "histogram", "Duration of job runs."),
+];
+
+/// The labels of a metric sample, sorted by name.
+type Labels = Vec<(&'static str, String)>;
+
+/// The observations of a histogram.
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// The number of observations in each bucket, not cumulated.
+    buckets: Vec<u64>,
+    /// The sum of the observations.
+    sum: f64,
+    /// The number of observations.
+    count: u64,
+}
+
+/// The value of a metric sample.
+#[derive(Debug, Clone)]
+enum Value {
+    Number(f64),
+    Histogram(Histogram),
+}
+
+/// Holds the metrics recorded by jobs and steps, labelled by job and step name.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    samples: Mutex<BTreeMap<(&'static str, Labels), Value>>,
+}
+
+impl MetricsRegistry {
+    /// Adds a value to a counter.
+    pub(crate) fn increment(&self, name: &'static str, labels: Labels, value: u64) {
+        let mut samples = self.samples.lock().unwrap();
+        if let Value::Number(total) = samples.entry((name, labels)).or_insert(Value::Number(0.0)) {
+            *total += value as f64;
+        }
+    }
+
+    /// Sets the value of a gauge.
+    #[cfg(feature = "async")]
+    pub(crate) fn set(&self, name: &'static str, labels: Labels, value: f64) {
+        self.samples.lock().unwrap().insert((name, labels), Value::Number(value));
+    }
+
+    /// Adds an observation to a histogram.
+    pub(crate) fn observe(&self, name: &'static str, labels: Labels, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let mut samples = self.samples.lock().unwrap();
+        let bounds = buckets(name);
+        let value = samples.entry((name, labels)).or_insert_with(|| Value::Histogram(Histogram {
+            buckets: vec![0; bounds.len()],
+            ..Histogram::default()
+        }));
+        if let Value::Histogram(histogram) = value {
+            if let Some(bucket) = bounds.iter().position(|bound| seconds <= *bound) {
+                histogram.buckets[bucket] += 1;
+            }
+            histogram.sum += seconds;
+            histogram.count += 1;
+        }
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format.
+    ///
+    /// # Returns `String`
+    ///
+    /// Returns the metrics, ready to be served with the `text/plain; version=0.0.4` content type.
+    pub fn render(&self) -> String {
+        let samples = self.samples.lock().unwrap();
+        let mut output = String::new();
+
+        for (name, kind, help) in DESCRIPTORS {
+            let mut family = samples.iter().filter(|((sample_name, _), _)| *sample_name == name).peekable();
+            if family.peek().is_none() {
+                continue;
+            }
+            writeln!(output, "# HELP {} {}", name, help).unwrap();
+            writeln!(output, "# TYPE {} {}", name, kind).unwrap();
+
+            for ((_, labels), value) in family {
+                match value {
+                    Value::Number(number) => {
+                        writeln!(output, "{}{} {}", name, format_labels(labels, None), number).unwrap();
+                    }
+                    Value::Histogram(histogram) => {
+                        let mut cumulated = 0;
+                        for (bound, count) in buckets(name).iter().zip(&histogram.buckets) {
+                            cumulated += *count;
+                            let bucket = format_labels(labels, Some(&bound.to_string()));
+                            writeln!(output, "{}_bucket{} {}", name, bucket, cumulated).unwrap();
+                        }
+                        writeln!(output, "{}_bucket{} {}", name, format_labels(labels, Some("+Inf")), histogram.count).unwrap();
+                        writeln!(output, "{}_sum{} {}", name, format_labels(labels, None), histogram.sum).unwrap();
+                        writeln!(output, "{}_count{} {}", name, format_labels(labels, None), histogram.count).unwrap();
+                    }
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Removes all the recorded metrics.
+    pub fn clear(&self) {
+        self.samples.lock().unwrap().clear();
+    }
+}
+
+/// Formats labels as `{name="value",...}`, adding the `le` label of a histogram bucket.
+fn format_labels(labels: &Labels, bucket: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels.iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+        .collect();
+    if let Some(bound) = bucket {
+        pairs.push(format!("le=\"{}\"", bound));
+    }
+    if pairs.is_empty() {
+        return String::new();
+    }
+    format!("{{{}}}", pairs.join(","))
+}
+
+/// Escapes a label value for the exposition format.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Returns the registry the crate records its metrics in.
+///
+/// # Returns `&'static MetricsRegistry`
+///
+/// Returns the process-wide registry, whose `render` output can be served from a scrape endpoint.
+pub fn registry() -> &'static MetricsRegistry {
+    static REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetricsRegistry::default)
+}
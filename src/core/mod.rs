@@ -1,2 +1,22 @@
 pub mod step;
-pub mod job;
\ No newline at end of file
+pub mod job;
+pub mod chunk;
+pub mod flow;
+pub mod partition;
+pub mod listener;
+pub mod progress;
+pub mod item;
+pub mod flat_file;
+pub mod compression;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub(crate) mod dag;
+pub(crate) mod trace;
+pub(crate) mod recorder;
+#[cfg(feature = "glob")]
+pub(crate) mod resource;
+pub(crate) mod restart;
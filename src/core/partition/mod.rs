@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub mod process;
+
+/// Represents the slice of work handled by one partition of a partitioned step.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Partition {
+    /// The name of the partition, unique within the step.
+    pub name: String,
+    /// The partition-specific parameters, such as an id range or a file path.
+    pub parameters: HashMap<String, String>,
+}
+
+impl Partition {
+    /// Creates a partition with the given name and no parameters.
+    pub fn new(name: String) -> Self {
+        Partition {
+            name,
+            parameters: HashMap::new(),
+        }
+    }
+
+    /// Adds a parameter to the partition.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the parameter.
+    /// * `value` - The value of the parameter.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns the modified partition.
+    pub fn parameter(mut self, key: &str, value: String) -> Self {
+        self.parameters.insert(key.to_string(), value);
+        self
+    }
+
+    /// Returns the value of a parameter.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.parameters.get(key).map(String::as_str)
+    }
+
+    /// Returns the value of a parameter parsed into the requested type.
+    pub fn parse<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|value| value.parse().ok())
+    }
+}
+
+/// A trait for splitting the work of a step into partitions.
+pub trait Partitioner: Send + Sync {
+    /// Splits the work into partitions.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid_size` - The number of partitions requested, which the partitioner may adjust.
+    ///
+    /// # Returns `Vec<Partition>`
+    ///
+    /// Returns the partitions, each one processed by its own worker step.
+    fn partition(&self, grid_size: usize) -> Vec<Partition>;
+}
+
+impl<F: Fn(usize) -> Vec<Partition> + Send + Sync> Partitioner for F {
+    fn partition(&self, grid_size: usize) -> Vec<Partition> {
+        self(grid_size)
+    }
+}
+
+/// The name of the parameter holding the first id of a range partition.
+pub const RANGE_MIN: &str = "min";
+/// The name of the parameter holding the last id of a range partition.
+pub const RANGE_MAX: &str = "max";
+/// The name of the parameter holding the file of a file partition.
+pub const FILE: &str = "file";
+
+/// Splits an inclusive range of ids into contiguous ranges of similar size.
+///
+/// Each partition holds its bounds in the `min` and `max` parameters.
+#[derive(Debug, Clone, Copy)]
+pub struct RangePartitioner {
+    /// The first id of the range.
+    pub min: i64,
+    /// The last id of the range.
+    pub max: i64,
+}
+
+impl Partitioner for RangePartitioner {
+    fn partition(&self, grid_size: usize) -> Vec<Partition> {
+        if self.min > self.max {
+            return Vec::new();
+        }
+
+        // The widest range, from `i64::MIN` to `i64::MAX`, holds one more id than `u64` can count.
+        let total = u128::from(self.max.abs_diff(self.min)) + 1;
+        let grid_size = (grid_size.max(1) as u128).min(total);
+        let size = total / grid_size;
+        let remainder = total % grid_size;
+        let mut partitions = Vec::with_capacity(grid_size as usize);
+        let mut start = self.min;
+
+        for index in 0..grid_size {
+            let length = size + if index < remainder { 1 } else { 0 };
+            let end = (i128::from(start) + (length as i128 - 1)) as i64;
+            partitions.push(
+                Partition::new(format!("partition{}", index))
+                    .parameter(RANGE_MIN, start.to_string())
+                    .parameter(RANGE_MAX, end.to_string())
+            );
+            start = end.saturating_add(1);
+        }
+
+        partitions
+    }
+}
+
+/// Creates one partition per file, ignoring the grid size.
+///
+/// Each partition holds its file path in the `file` parameter.
+#[derive(Debug, Clone)]
+pub struct FileListPartitioner {
+    /// The files to partition, in processing order.
+    pub files: Vec<PathBuf>,
+}
+
+impl FileListPartitioner {
+    /// Creates a partitioner over the files of a directory, sorted by path.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory to list.
+    ///
+    /// # Returns `io::Result<FileListPartitioner>`
+    ///
+    /// Returns the partitioner, or the error raised while listing the directory.
+    pub fn from_directory(directory: &Path) -> io::Result<Self> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(FileListPartitioner { files })
+    }
+}
+
+impl Partitioner for FileListPartitioner {
+    fn partition(&self, _grid_size: usize) -> Vec<Partition> {
+        self.files.iter()
+            .enumerate()
+            .map(|(index, file)| {
+                Partition::new(format!("partition{}", index))
+                    .parameter(FILE, file.to_string_lossy().to_string())
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,377 @@
+use std::env;
+use std::io::{self, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info};
+
+use crate::core::job::now_time;
+use crate::core::partition::Partition;
+use crate::core::step::{mount_step_status, StepStatus};
+
+/// The environment variable holding the name of the partitioned step a worker process serves.
+pub const WORKER_ENV: &str = "BATCH_PROCESSING_PARTITION_WORKER";
+
+/// The bytes opening every frame, so that anything else the worker prints on stdout is skipped.
+const FRAME_MAGIC: &[u8; 4] = b"BPPF";
+
+/// The largest payload a frame may carry, so that a corrupt length cannot exhaust the memory of the
+/// reading process.
+const MAX_FRAME_LENGTH: usize = 16 * 1024 * 1024;
+
+/// Returns the name of the partitioned step served by the current process, when it has been
+/// spawned as a partition worker.
+///
+/// The binary is expected to check it at startup and, when set, call `serve_stdio` with the
+/// worker of that step instead of running its jobs.
+pub fn worker_step_name() -> Option<String> {
+    env::var(WORKER_ENV).ok()
+}
+
+/// Reads one partition from the input, runs it and writes its status to the output.
+///
+/// # Arguments
+///
+/// * `input` - The stream the partition frame is read from.
+/// * `output` - The stream the status frame is written to.
+/// * `worker` - The function running the partition and returning its status.
+///
+/// # Returns `io::Result<()>`
+///
+/// Returns an error when the frames cannot be read or written.
+pub fn serve<R: Read, W: Write>(input: R, mut output: W, worker: impl FnOnce(Partition) -> StepStatus) -> io::Result<()> {
+    let partition = decode_partition(read_frame(&mut BufReader::new(input))?)?;
+    let step_status = worker(partition);
+    write_frame(&mut output, encode_step_status(&step_status))
+}
+
+/// Serves one partition over the stdin and stdout of the current process.
+///
+/// # Arguments
+///
+/// * `worker` - The function running the partition and returning its status.
+///
+/// # Returns `io::Result<()>`
+///
+/// Returns an error when the frames cannot be read or written.
+pub fn serve_stdio(worker: impl FnOnce(Partition) -> StepStatus) -> io::Result<()> {
+    serve(io::stdin().lock(), io::stdout().lock(), worker)
+}
+
+/// Runs each partition in a child process of the same binary.
+///
+/// The child receives the partition on its stdin and answers with its `StepStatus` on its
+/// stdout. A child that exits without answering, or that is killed for running longer than the
+/// timeout, is considered crashed and is restarted up to `max_restarts` times before its partition
+/// is reported as failed. Nested job and partition statuses of the child are not sent back.
+#[derive(Debug, Clone)]
+pub struct ProcessPartitionHandler {
+    /// The program spawned for each partition.
+    program: PathBuf,
+    /// The arguments passed to the program.
+    args: Vec<String>,
+    /// The number of times a crashed partition is restarted.
+    max_restarts: usize,
+    /// The time a child may take to answer before it is killed.
+    timeout: Option<Duration>,
+}
+
+impl ProcessPartitionHandler {
+    /// Creates a handler spawning the current executable without arguments and no restarts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path of the current executable cannot be read.
+    pub fn new() -> Self {
+        ProcessPartitionHandler {
+            program: env::current_exe().expect("The current executable is required"),
+            args: Vec::new(),
+            max_restarts: 0,
+            timeout: None,
+        }
+    }
+
+    /// Sets the program spawned for each partition.
+    ///
+    /// # Arguments
+    ///
+    /// * `program` - The path of the program.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns the modified handler.
+    pub fn program(self, program: PathBuf) -> Self {
+        ProcessPartitionHandler {
+            program,
+            ..self
+        }
+    }
+
+    /// Sets the arguments passed to the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns the modified handler.
+    pub fn args(self, args: Vec<String>) -> Self {
+        ProcessPartitionHandler {
+            args,
+            ..self
+        }
+    }
+
+    /// Sets the number of times a crashed partition is restarted.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_restarts` - The number of restarts.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns the modified handler.
+    pub fn max_restarts(self, max_restarts: usize) -> Self {
+        ProcessPartitionHandler {
+            max_restarts,
+            ..self
+        }
+    }
+
+    /// Sets the time a child may take to answer, after which it is killed and handled like a
+    /// crashed one. Without a timeout, a hung child blocks its partition forever.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The time given to each attempt of a partition.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns the modified handler.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        ProcessPartitionHandler {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Runs a partition in a child process, restarting it when it crashes.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the partitioned step, passed to the child in `WORKER_ENV`.
+    /// * `partition` - The partition to run.
+    ///
+    /// # Returns `StepStatus`
+    ///
+    /// Returns the status sent back by the child, or a failed status once the restarts are exhausted.
+    pub fn run(&self, step_name: &str, partition: &Partition) -> StepStatus {
+        self.run_with_restarts(step_name, partition, |_, _| {})
+    }
+
+    /// Runs a partition like `run`, calling `on_restart` with the attempt and the crash message
+    /// before each restart.
+    pub(crate) fn run_with_restarts(&self, step_name: &str, partition: &Partition, on_restart: impl Fn(usize, &str)) -> StepStatus {
+        let start_time = now_time();
+        let mut crash = String::new();
+        for attempt in 0..=self.max_restarts {
+            if attempt > 0 {
+                info!("Restarting the worker process of partition {} ({}/{})", partition.name, attempt, self.max_restarts);
+                on_restart(attempt, &crash);
+            }
+            match self.spawn(step_name, partition) {
+                Ok(step_status) => return step_status,
+                Err(error) => {
+                    error!("The worker process of partition {} crashed: {}", partition.name, error);
+                    crash = error.to_string();
+                }
+            }
+        }
+        let message = format!("Partition {} failed, its worker process crashed", partition.name);
+        mount_step_status(partition.name.clone(), Err(message), start_time)
+    }
+
+    /// Spawns a child process, sends it the partition and waits for its status.
+    ///
+    /// With a timeout, a watchdog thread kills the child once it expires, which closes its pipes
+    /// and so unblocks the exchange of frames.
+    fn spawn(&self, step_name: &str, partition: &Partition) -> io::Result<StepStatus> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .env(WORKER_ENV, step_name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let mut stdin = child.stdin.take().unwrap();
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let child = Mutex::new(child);
+
+        let (done, finished) = mpsc::channel::<()>();
+        let (received, timed_out) = thread::scope(|scope| {
+            let child = &child;
+            let watchdog = self.timeout.map(|timeout| scope.spawn(move || {
+                let expired = finished.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout);
+                if expired {
+                    let _ = child.lock().unwrap().kill();
+                }
+                expired
+            }));
+
+            let sent = write_frame(&mut stdin, encode_partition(partition));
+            drop(stdin);
+            let received = sent.and_then(|_| read_frame(&mut stdout));
+            if received.is_ok() {
+                // Drains what the child prints after its status, so it does not fail on a closed pipe.
+                let _ = io::copy(&mut stdout, &mut io::sink());
+            }
+            drop(done);
+            (received, watchdog.is_some_and(|watchdog| watchdog.join().unwrap()))
+        });
+
+        let mut child = child.into_inner().unwrap();
+        if received.is_err() {
+            let _ = child.kill();
+        }
+        let exit_status = child.wait()?;
+
+        match received {
+            Ok(frame) => decode_step_status(frame),
+            Err(_) if timed_out => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("The worker process timed out after {:?} ({})", self.timeout.unwrap(), exit_status),
+            )),
+            Err(error) => Err(io::Error::new(error.kind(), format!("{} ({})", error, exit_status))),
+        }
+    }
+}
+
+impl Default for ProcessPartitionHandler {
+    fn default() -> Self {
+        ProcessPartitionHandler::new()
+    }
+}
+
+/// Writes a frame made of the magic bytes, the payload length and the length-prefixed fields.
+fn write_frame<W: Write>(output: &mut W, fields: Vec<String>) -> io::Result<()> {
+    let mut payload = Vec::new();
+    for field in fields {
+        payload.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        payload.extend_from_slice(field.as_bytes());
+    }
+    if payload.len() > MAX_FRAME_LENGTH {
+        return Err(invalid_data("The frame is larger than the maximum frame length"));
+    }
+    let mut frame = Vec::with_capacity(payload.len() + 8);
+    frame.extend_from_slice(FRAME_MAGIC);
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&payload);
+    output.write_all(&frame)?;
+    output.flush()
+}
+
+/// Skips the input up to the magic bytes and reads the fields of the frame that follows.
+fn read_frame<R: Read>(input: &mut R) -> io::Result<Vec<String>> {
+    let mut window = [0u8; 4];
+    let mut byte = [0u8; 1];
+    while &window != FRAME_MAGIC {
+        input.read_exact(&mut byte)?;
+        window.rotate_left(1);
+        window[3] = byte[0];
+    }
+
+    let length = read_length(input)?;
+    if length > MAX_FRAME_LENGTH {
+        return Err(invalid_data("The frame is larger than the maximum frame length"));
+    }
+    let mut payload = vec![0u8; length];
+    input.read_exact(&mut payload)?;
+
+    let mut fields = Vec::new();
+    let mut payload = payload.as_slice();
+    while !payload.is_empty() {
+        let length = read_length(&mut payload)?;
+        if length > payload.len() {
+            return Err(invalid_data("The field is longer than the frame"));
+        }
+        let (field, rest) = payload.split_at(length);
+        fields.push(String::from_utf8(field.to_vec()).map_err(|_| invalid_data("The field is not valid UTF-8"))?);
+        payload = rest;
+    }
+    Ok(fields)
+}
+
+/// Reads a big-endian length prefix.
+fn read_length<R: Read>(input: &mut R) -> io::Result<usize> {
+    let mut length = [0u8; 4];
+    input.read_exact(&mut length)?;
+    Ok(u32::from_be_bytes(length) as usize)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Encodes a partition as its name followed by its parameters as key and value pairs.
+fn encode_partition(partition: &Partition) -> Vec<String> {
+    let mut fields = vec![partition.name.clone()];
+    for (key, value) in &partition.parameters {
+        fields.push(key.clone());
+        fields.push(value.clone());
+    }
+    fields
+}
+
+fn decode_partition(fields: Vec<String>) -> io::Result<Partition> {
+    if fields.len() % 2 != 1 {
+        return Err(invalid_data("The partition frame is malformed"));
+    }
+    let mut fields = fields.into_iter();
+    let mut partition = Partition::new(fields.next().unwrap());
+    while let (Some(key), Some(value)) = (fields.next(), fields.next()) {
+        partition.parameters.insert(key, value);
+    }
+    Ok(partition)
+}
+
+/// Encodes the flat fields of a status, leaving out nested job and partition statuses.
+fn encode_step_status(step_status: &StepStatus) -> Vec<String> {
+    let (result, message) = match &step_status.status {
+        Ok(message) => ("ok", message),
+        Err(message) => ("err", message),
+    };
+    let chunk_sizes: Vec<String> = step_status.chunk_sizes.iter().map(usize::to_string).collect();
+    vec![
+        step_status.name.clone(),
+        step_status.start_time.map(|time| time.to_string()).unwrap_or_default(),
+        step_status.end_time.map(|time| time.to_string()).unwrap_or_default(),
+        result.to_string(),
+        message.clone(),
+        step_status.exit_status.clone(),
+        chunk_sizes.join(","),
+    ]
+}
+
+fn decode_step_status(fields: Vec<String>) -> io::Result<StepStatus> {
+    let [name, start_time, end_time, result, message, exit_status, chunk_sizes]: [String; 7] = fields.try_into()
+        .map_err(|_| invalid_data("The status frame is malformed"))?;
+    let parse_time = |time: String| -> io::Result<Option<u128>> {
+        if time.is_empty() {
+            return Ok(None);
+        }
+        time.parse().map(Some).map_err(|_| invalid_data("The time is not a number"))
+    };
+    let mut step_status = mount_step_status(name, if result == "ok" { Ok(message) } else { Err(message) }, 0);
+    step_status.start_time = parse_time(start_time)?;
+    step_status.end_time = parse_time(end_time)?;
+    step_status.exit_status = exit_status;
+    step_status.chunk_sizes = chunk_sizes.split(',')
+        .filter(|size| !size.is_empty())
+        .map(|size| size.parse().map_err(|_| invalid_data("The chunk size is not a number")))
+        .collect::<io::Result<Vec<usize>>>()?;
+    Ok(step_status)
+}
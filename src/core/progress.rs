@@ -0,0 +1,150 @@
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+/// An event emitted while a job runs, so that callers can follow its progress.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressEvent {
+    /// The job started running its steps.
+    JobStarted {
+        job_name: String,
+        /// The number of steps of the job, some of which may be skipped.
+        step_count: usize,
+    },
+    /// A step started running.
+    StepStarted {
+        step_name: String,
+        /// The number of items the step is expected to read, when it has been given one.
+        expected_total: Option<usize>,
+    },
+    /// A step was skipped by its decider or because a step it depends on failed.
+    StepSkipped {
+        step_name: String,
+    },
+    /// A step executed successfully.
+    StepCompleted {
+        step_name: String,
+        duration: Duration,
+    },
+    /// A step failed to execute.
+    StepFailed {
+        step_name: String,
+        error: String,
+    },
+    /// A chunk of a complex step has been written.
+    ChunkWritten {
+        step_name: String,
+        chunk_index: usize,
+        /// The number of items of the chunk.
+        item_count: usize,
+        /// The number of items written by the step so far, across all its workers.
+        items_written: usize,
+        /// The number of items the step is expected to read, when it has been given one.
+        expected_total: Option<usize>,
+    },
+    /// Items were dropped by a throw tolerant complex step after a processing or write error.
+    ItemsSkipped {
+        step_name: String,
+        item_count: usize,
+        error: String,
+    },
+    /// A crashed partition worker process is being restarted.
+    Retried {
+        step_name: String,
+        /// The restart attempt, starting at one.
+        attempt: usize,
+        error: String,
+    },
+    /// The job finished running.
+    JobFinished {
+        job_name: String,
+        succeeded: bool,
+        duration: Duration,
+    },
+}
+
+impl ProgressEvent {
+    /// Returns the fraction of the expected items written so far.
+    ///
+    /// # Returns `Option<f64>`
+    ///
+    /// Returns a value between 0 and 1 for a `ChunkWritten` event of a step with an expected
+    /// total, and `None` otherwise.
+    pub fn fraction(&self) -> Option<f64> {
+        match self {
+            ProgressEvent::ChunkWritten { items_written, expected_total: Some(expected_total), .. } => {
+                if *expected_total == 0 {
+                    return Some(1.0);
+                }
+                Some((*items_written as f64 / *expected_total as f64).min(1.0))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A channel an event is delivered to.
+#[derive(Debug)]
+enum Subscriber {
+    Channel(mpsc::Sender<ProgressEvent>),
+    #[cfg(feature = "async")]
+    Stream(futures::channel::mpsc::UnboundedSender<ProgressEvent>),
+}
+
+impl Subscriber {
+    /// Delivers an event, returning `false` once the receiving end has been dropped.
+    fn send(&self, event: ProgressEvent) -> bool {
+        match self {
+            Subscriber::Channel(sender) => sender.send(event).is_ok(),
+            #[cfg(feature = "async")]
+            Subscriber::Stream(sender) => sender.unbounded_send(event).is_ok(),
+        }
+    }
+}
+
+/// Broadcasts the progress events of a job to its subscribers.
+///
+/// Cloning gives a handle to the same subscribers, so a single instance can follow several jobs.
+/// Events are dropped when nobody subscribed, and subscribers whose receiver has been dropped are
+/// removed on the next event.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressEvents {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl ProgressEvents {
+    /// Creates a broadcaster without subscribers.
+    pub fn new() -> Self {
+        ProgressEvents::default()
+    }
+
+    /// Subscribes to the events with a standard channel.
+    ///
+    /// # Returns `mpsc::Receiver<ProgressEvent>`
+    ///
+    /// Returns the receiving end of the channel, which disconnects once every handle of the
+    /// broadcaster has been dropped.
+    pub fn subscribe(&self) -> mpsc::Receiver<ProgressEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(Subscriber::Channel(sender));
+        receiver
+    }
+
+    /// Subscribes to the events with a stream.
+    ///
+    /// # Returns `UnboundedReceiver<ProgressEvent>`
+    ///
+    /// Returns a stream of the events, which ends once every handle of the broadcaster has been
+    /// dropped.
+    #[cfg(feature = "async")]
+    pub fn stream(&self) -> futures::channel::mpsc::UnboundedReceiver<ProgressEvent> {
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        self.subscribers.lock().unwrap().push(Subscriber::Stream(sender));
+        receiver
+    }
+
+    /// Delivers an event to every subscriber.
+    pub(crate) fn emit(&self, event: ProgressEvent) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| subscriber.send(event.clone()));
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use crate::core::metrics::{self, registry};
+
+/// Records the metrics of a step in the registry of the `metrics` feature, or does nothing when
+/// the feature is disabled.
+#[derive(Debug, Clone)]
+pub(crate) struct StepMetrics {
+    #[cfg(feature = "metrics")]
+    labels: Vec<(&'static str, String)>,
+}
+
+impl StepMetrics {
+    /// Creates the recorder of a step, labelled by its job and step names.
+    pub(crate) fn new(_job_name: Option<String>, _step_name: &str) -> Self {
+        StepMetrics {
+            #[cfg(feature = "metrics")]
+            labels: vec![("job", _job_name.unwrap_or_default()), ("step", _step_name.to_string())],
+        }
+    }
+
+    pub(crate) fn items_read(&self, _count: usize) {
+        #[cfg(feature = "metrics")]
+        registry().increment(metrics::ITEMS_READ, self.labels.clone(), _count as u64);
+    }
+
+    pub(crate) fn items_written(&self, _count: usize) {
+        #[cfg(feature = "metrics")]
+        registry().increment(metrics::ITEMS_WRITTEN, self.labels.clone(), _count as u64);
+    }
+
+    pub(crate) fn items_skipped(&self, _count: usize) {
+        #[cfg(feature = "metrics")]
+        registry().increment(metrics::ITEMS_SKIPPED, self.labels.clone(), _count as u64);
+    }
+
+    pub(crate) fn chunk_written(&self, _latency: Duration) {
+        #[cfg(feature = "metrics")]
+        registry().observe(metrics::CHUNK_WRITE_SECONDS, self.labels.clone(), _latency);
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn queue_depth(&self, _depth: usize) {
+        #[cfg(feature = "metrics")]
+        registry().set(metrics::WORKER_QUEUE_DEPTH, self.labels.clone(), _depth as f64);
+    }
+
+    pub(crate) fn step_finished(&self, _duration: Duration) {
+        #[cfg(feature = "metrics")]
+        registry().observe(metrics::STEP_DURATION_SECONDS, self.labels.clone(), _duration);
+    }
+}
+
+/// Records the outcome and duration of a job run.
+pub(crate) fn job_finished(_job_name: &str, _succeeded: bool, _duration: Duration) {
+    #[cfg(feature = "metrics")]
+    {
+        let status = if _succeeded { "completed" } else { "failed" };
+        registry().increment(metrics::JOB_RUNS, vec![("job", _job_name.to_string()), ("status", status.to_string())], 1);
+        registry().observe(metrics::JOB_DURATION_SECONDS, vec![("job", _job_name.to_string())], _duration);
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use crate::core::restart::RestartFile;
+
+/// Lists the files matching a glob pattern, sorted by path.
+///
+/// # Arguments
+///
+/// * `pattern` - The glob pattern, e.g. `input/*.csv`.
+///
+/// # Returns `Vec<PathBuf>`
+///
+/// Returns the matching files; directories are left out.
+///
+/// # Panics
+///
+/// Panics if the pattern is invalid.
+pub(crate) fn matching_resources(pattern: &str) -> Vec<PathBuf> {
+    let paths = glob::glob(pattern)
+        .unwrap_or_else(|err| panic!("Invalid resource pattern {}: {}", pattern, err));
+
+    let mut resources: Vec<PathBuf> = paths
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .collect();
+    resources.sort();
+    resources
+}
+
+/// Records the resource holding the first item of a multi-resource reader that has not been
+/// written yet, so that a new run of the step after a failure resumes at that resource instead of
+/// the first one.
+pub(crate) struct ResourceCheckpoint {
+    /// The file the resource is recorded in.
+    file: RestartFile,
+    /// The resources opened whose items may not all be written yet, with the number of items read
+    /// before each was opened.
+    started: VecDeque<(PathBuf, u64)>,
+    /// The resource last recorded in the file.
+    saved: Option<PathBuf>,
+}
+
+impl ResourceCheckpoint {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        ResourceCheckpoint {
+            file: RestartFile::new(path),
+            started: VecDeque::new(),
+            saved: None,
+        }
+    }
+
+    /// Drops the resources read completely by a previous run.
+    ///
+    /// # Arguments
+    ///
+    /// * `resources` - The sorted resources to read.
+    ///
+    /// # Returns `Vec<PathBuf>`
+    ///
+    /// Returns the resources from the one being written when the previous run stopped, or all of
+    /// them when there is no previous run to resume.
+    pub(crate) fn resume(&mut self, resources: Vec<PathBuf>) -> Vec<PathBuf> {
+        self.started.clear();
+        self.saved = None;
+        match self.file.load() {
+            Some(current) => {
+                let current = PathBuf::from(current);
+                resources.into_iter().filter(|resource| *resource >= current).collect()
+            }
+            None => resources,
+        }
+    }
+
+    /// Notes that a resource is opened.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - The resource.
+    /// * `read` - The number of items read from the previous resources.
+    pub(crate) fn start(&mut self, resource: &Path, read: u64) {
+        self.started.push_back((resource.to_path_buf(), read));
+    }
+
+    /// Records the resource holding the first item not written yet, or forgets the previous run
+    /// once every item has been read and written.
+    ///
+    /// # Arguments
+    ///
+    /// * `written` - The number of items written.
+    /// * `read` - The number of items read.
+    /// * `exhausted` - Whether every resource has been read.
+    pub(crate) fn written(&mut self, written: u64, read: u64, exhausted: bool) {
+        if exhausted && written == read {
+            self.started.clear();
+            self.file.clear();
+            return;
+        }
+
+        // Item `written`, counting from zero, is the first one not written yet.
+        while self.started.len() > 1 && self.started[1].1 <= written {
+            self.started.pop_front();
+        }
+
+        if let Some((resource, _)) = self.started.front() {
+            if self.saved.as_ref() != Some(resource) {
+                self.file.save(&resource.to_string_lossy());
+                self.saved = Some(resource.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// A file recording where a reader is in its source, so that a new run of the step after a failure
+/// resumes there instead of at the start.
+pub(crate) struct RestartFile {
+    /// The path of the file.
+    path: PathBuf,
+}
+
+impl RestartFile {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        RestartFile { path }
+    }
+
+    /// Returns the position recorded by a previous run, or `None` when there is no previous run to
+    /// resume.
+    pub(crate) fn load(&self) -> Option<String> {
+        fs::read_to_string(&self.path).ok()
+    }
+
+    /// Records the position being read.
+    pub(crate) fn save(&self, position: &str) {
+        fs::write(&self.path, position.as_bytes())
+            .unwrap_or_else(|err| panic!("Error writing restart file {}: {}", self.path.display(), err));
+    }
+
+    /// Forgets the previous run once the whole source has been read.
+    pub(crate) fn clear(&self) {
+        if self.path.exists() {
+            fs::remove_file(&self.path)
+                .unwrap_or_else(|err| panic!("Error removing restart file {}: {}", self.path.display(), err));
+        }
+    }
+}
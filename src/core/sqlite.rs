@@ -0,0 +1,148 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, Row};
+
+/// Maps a row of a query to an item, e.g. `|row| Ok(Car { id: row.get("id")?, make: row.get("make")? })`.
+pub type RowMapper<T> = Box<dyn Fn(&Row<'_>) -> rusqlite::Result<T> + Send + Sync>;
+
+/// A row mapper shared by a reader and the threads running its query.
+pub(crate) type SharedRowMapper<T> = Arc<dyn Fn(&Row<'_>) -> rusqlite::Result<T> + Send + Sync>;
+
+/// The number of rows a query thread maps ahead of the reader.
+pub(crate) const ROWS_AHEAD: usize = 256;
+
+/// How long a connection waits for another one to release its lock before failing.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Opens a connection of a reader or a writer.
+///
+/// With `wal`, the database is switched to write-ahead logging, so that a step reading and writing
+/// the same database can commit its chunks while the query of its reader is still running. The
+/// journal mode is stored in the database file, so it outlives the connection.
+///
+/// # Arguments
+///
+/// * `path` - The path of the database.
+/// * `wal` - Whether to switch the database to write-ahead logging.
+///
+/// # Returns `rusqlite::Result<Connection>`
+///
+/// Returns the connection, or the error opening it.
+pub(crate) fn open_connection(path: &Path, wal: bool) -> rusqlite::Result<Connection> {
+    let connection = Connection::open(path)?;
+    connection.busy_timeout(BUSY_TIMEOUT)?;
+    if wal {
+        connection.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+    }
+    Ok(connection)
+}
+
+/// Builds the statement inserting a row, or updating it when a row with the same key exists.
+///
+/// # Arguments
+///
+/// * `table` - The table to write to.
+/// * `columns` - The columns written, bound in order to the parameters `?1`, `?2`...
+/// * `keys` - The columns identifying a row, or none for a plain insert.
+///
+/// # Returns `String`
+///
+/// Returns the statement.
+pub(crate) fn insert_statement(table: &str, columns: &[&str], keys: &[&str]) -> String {
+    let names: Vec<String> = columns.iter().map(|column| quote(column)).collect();
+    let parameters: Vec<String> = (1..=columns.len()).map(|index| format!("?{}", index)).collect();
+    let mut statement = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote(table),
+        names.join(", "),
+        parameters.join(", ")
+    );
+
+    if !keys.is_empty() {
+        let keys: Vec<String> = keys.iter().map(|key| quote(key)).collect();
+        let updates: Vec<String> = names.iter()
+            .filter(|name| !keys.contains(name))
+            .map(|name| format!("{} = excluded.{}", name, name))
+            .collect();
+
+        if updates.is_empty() {
+            statement.push_str(&format!(" ON CONFLICT ({}) DO NOTHING", keys.join(", ")));
+        } else {
+            statement.push_str(&format!(" ON CONFLICT ({}) DO UPDATE SET {}", keys.join(", "), updates.join(", ")));
+        }
+    }
+
+    statement
+}
+
+/// Quotes an identifier, e.g. a table or a column name.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Runs a query and maps its rows one at a time, handing each item, or the error reading it, over
+/// to `send`. Stops early when `send` returns `false`, i.e. when the reader is gone.
+///
+/// Rows borrow their statement, which borrows its connection, so a reader streaming a query runs
+/// it on its own thread through this function.
+pub(crate) fn stream_rows<T>(
+    path: &Path,
+    wal: bool,
+    query: &str,
+    parameters: &[Value],
+    mapper: &(dyn Fn(&Row<'_>) -> rusqlite::Result<T> + Send + Sync),
+    mut send: impl FnMut(rusqlite::Result<T>) -> bool,
+) {
+    let connection = match open_connection(path, wal) {
+        Ok(connection) => connection,
+        Err(err) => {
+            send(Err(err));
+            return;
+        }
+    };
+
+    let mut statement = match connection.prepare(query) {
+        Ok(statement) => statement,
+        Err(err) => {
+            send(Err(err));
+            return;
+        }
+    };
+
+    let mut rows = match statement.query(params_from_iter(parameters)) {
+        Ok(rows) => rows,
+        Err(err) => {
+            send(Err(err));
+            return;
+        }
+    };
+
+    loop {
+        let item = match rows.next() {
+            Ok(Some(row)) => mapper(row),
+            Ok(None) => return,
+            Err(err) => Err(err),
+        };
+
+        let failed = item.is_err();
+        if !send(item) || failed {
+            return;
+        }
+    }
+}
+
+/// Writes the rows of a chunk in a single transaction, so that a failing row leaves none of the
+/// rows of its chunk behind.
+pub(crate) fn write_rows(connection: &mut Connection, statement: &str, rows: Vec<Vec<Value>>) -> rusqlite::Result<()> {
+    let transaction = connection.transaction()?;
+    {
+        let mut statement = transaction.prepare_cached(statement)?;
+        for row in rows {
+            statement.execute(params_from_iter(row))?;
+        }
+    }
+    transaction.commit()
+}
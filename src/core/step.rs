@@ -1,8 +1,23 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::SystemTime;
 use log::error;
+use crate::core::job::JobStatus;
+use crate::core::progress::{ProgressEvent, ProgressEvents};
+
+/// The exit status of a step that executed successfully.
+pub const COMPLETED: &str = "COMPLETED";
+/// The exit status of a step that failed to execute.
+pub const FAILED: &str = "FAILED";
+/// The exit status used to evaluate the transitions of a step skipped by its decider.
+pub const SKIPPED: &str = "SKIPPED";
 
 /// Represents the status of a step execution.
+///
+/// The status is built by the steps, and may gain fields as steps report more of their execution,
+/// so it cannot be built outside of this crate.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct StepStatus {
     /// The step name.
     pub name: String,
@@ -12,6 +27,122 @@ pub struct StepStatus {
     pub end_time: Option<u128>,
     /// The status result of the step execution.
     pub status: Result<String, String>,
+    /// The exit status of the step, `COMPLETED`, `FAILED` or a custom one set by a tasklet.
+    pub exit_status: String,
+    /// The chunk sizes adopted by an adaptive chunk size, in the order they were chosen.
+    pub chunk_sizes: Vec<usize>,
+    /// The status of the job run by the step, when the step wraps a whole job.
+    pub job_status: Option<JobStatus>,
+    /// The status of each partition, when the step is partitioned.
+    pub partitions_status: Vec<StepStatus>,
+}
+
+/// Holds the data collected while a step is running, reported in its `StepStatus` once it finishes.
+#[derive(Debug, Default)]
+pub struct StepExecution {
+    /// The chunk sizes adopted by an adaptive chunk size.
+    chunk_sizes: Mutex<Vec<usize>>,
+    /// The custom exit status set while the step is running.
+    exit_status: Mutex<Option<String>>,
+    /// The status of the job run by the step.
+    job_status: Mutex<Option<JobStatus>>,
+    /// The status of each partition of the step.
+    partitions_status: Mutex<Vec<StepStatus>>,
+    /// The name of the job running the step.
+    job_name: Mutex<Option<String>>,
+    /// The broadcaster of the progress events of the job running the step.
+    progress: Mutex<ProgressEvents>,
+    /// The number of items the step is expected to read.
+    expected_total: Mutex<Option<usize>>,
+    /// The number of items written so far.
+    items_written: AtomicUsize,
+}
+
+impl StepExecution {
+    /// Records a chunk size chosen while the step is running.
+    pub fn record_chunk_size(&self, chunk_size: usize) {
+        self.chunk_sizes.lock().unwrap().push(chunk_size);
+    }
+
+    /// Sets a custom exit status, reported instead of `COMPLETED` when the step succeeds.
+    pub fn set_exit_status(&self, exit_status: String) {
+        *self.exit_status.lock().unwrap() = Some(exit_status);
+    }
+
+    /// Records the status of the job run by the step.
+    pub fn set_job_status(&self, job_status: JobStatus) {
+        *self.job_status.lock().unwrap() = Some(job_status);
+    }
+
+    /// Records the status of a finished partition of the step.
+    pub fn add_partition_status(&self, partition_status: StepStatus) {
+        self.partitions_status.lock().unwrap().push(partition_status);
+    }
+
+    /// Attaches the step to the job running it.
+    pub(crate) fn join_job(&self, job_name: String, progress: ProgressEvents) {
+        *self.job_name.lock().unwrap() = Some(job_name);
+        *self.progress.lock().unwrap() = progress;
+    }
+
+    /// Attaches the step to the job running a parent step, such as a partitioned step.
+    pub(crate) fn inherit(&self, parent: &StepExecution) {
+        *self.job_name.lock().unwrap() = parent.job_name();
+        *self.progress.lock().unwrap() = parent.progress.lock().unwrap().clone();
+    }
+
+    /// Returns the name of the job running the step, if it runs inside a job.
+    pub fn job_name(&self) -> Option<String> {
+        self.job_name.lock().unwrap().clone()
+    }
+
+    /// Sets the number of items the step is expected to read, reported in its progress events.
+    pub(crate) fn set_expected_total(&self, expected_total: usize) {
+        *self.expected_total.lock().unwrap() = Some(expected_total);
+    }
+
+    /// Returns the number of items the step is expected to read, if it has been given one.
+    pub fn expected_total(&self) -> Option<usize> {
+        *self.expected_total.lock().unwrap()
+    }
+
+    /// Emits a progress event to the subscribers of the job running the step.
+    pub(crate) fn emit(&self, event: ProgressEvent) {
+        let progress = self.progress.lock().unwrap().clone();
+        progress.emit(event);
+    }
+
+    /// Counts the items of a written chunk and emits its progress event.
+    pub(crate) fn chunk_written(&self, step_name: &str, chunk_index: usize, item_count: usize) {
+        let items_written = self.items_written.fetch_add(item_count, Ordering::Relaxed) + item_count;
+        self.emit(ProgressEvent::ChunkWritten {
+            step_name: step_name.to_string(),
+            chunk_index,
+            item_count,
+            items_written,
+            expected_total: self.expected_total(),
+        });
+    }
+
+    /// Emits the progress event of items dropped after an error.
+    pub(crate) fn items_skipped(&self, step_name: &str, item_count: usize, error: &str) {
+        self.emit(ProgressEvent::ItemsSkipped {
+            step_name: step_name.to_string(),
+            item_count,
+            error: error.to_string(),
+        });
+    }
+
+    /// Copies the collected data into the status of the finished step.
+    pub fn report(&self, mut step_status: StepStatus) -> StepStatus {
+        step_status.chunk_sizes = self.chunk_sizes.lock().unwrap().clone();
+        step_status.job_status = self.job_status.lock().unwrap().clone();
+        step_status.partitions_status = self.partitions_status.lock().unwrap().clone();
+        if let (Ok(_), Some(exit_status)) = (&step_status.status, self.exit_status.lock().unwrap().clone()) {
+            step_status.exit_status = exit_status;
+        }
+        step_status
+    }
 }
 
 pub fn throw_tolerant_exception(throw_tolerant: bool, step_name: String) -> StepStatus {
@@ -21,6 +152,10 @@ pub fn throw_tolerant_exception(throw_tolerant: bool, step_name: String) -> Step
             start_time: None,
             end_time: None,
             status: Ok(String::from("callback is required, please provide a callback to the step")),
+            exit_status: String::from(COMPLETED),
+            chunk_sizes: Vec::new(),
+            job_status: None,
+            partitions_status: Vec::new(),
         }
     }
     let error_message = format!("callback is required, please provide a callback to the step with name: {}", step_name);
@@ -29,7 +164,11 @@ pub fn throw_tolerant_exception(throw_tolerant: bool, step_name: String) -> Step
         name: step_name,
         start_time: None,
         end_time: None,
-        status: Err(String::from(error_message)),
+        status: Err(error_message),
+        exit_status: String::from(FAILED),
+        chunk_sizes: Vec::new(),
+        job_status: None,
+        partitions_status: Vec::new(),
     };
 }
 
@@ -41,12 +180,20 @@ pub fn mount_step_status(step_name: String, step_result: Result<String, String>,
             start_time: Some(start_time),
             end_time: Some(end_time),
             status: Ok(message),
+            exit_status: String::from(COMPLETED),
+            chunk_sizes: Vec::new(),
+            job_status: None,
+            partitions_status: Vec::new(),
         },
         Err(message) => StepStatus {
             name: step_name,
             start_time: Some(start_time),
             end_time: None,
             status: Err(message),
+            exit_status: String::from(FAILED),
+            chunk_sizes: Vec::new(),
+            job_status: None,
+            partitions_status: Vec::new(),
         },
     };
 }
\ No newline at end of file
@@ -0,0 +1,128 @@
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+/// A span of the `tracing` feature, which does nothing when the feature is disabled.
+///
+/// Threads and tasks do not inherit the current span, so the runners capture it with `current`
+/// before spawning and enter it again in the spawned code.
+#[derive(Clone)]
+pub(crate) struct TraceSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "tracing")]
+    start: Instant,
+}
+
+#[cfg(feature = "tracing")]
+impl TraceSpan {
+    fn new(span: tracing::Span) -> Self {
+        TraceSpan {
+            span,
+            start: Instant::now(),
+        }
+    }
+
+    /// Opens the span of a job run.
+    pub(crate) fn job(job_name: &str) -> Self {
+        TraceSpan::new(tracing::info_span!(
+            "job",
+            job_name = %job_name,
+            status = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+        ))
+    }
+
+    /// Opens the span of a step run.
+    pub(crate) fn step(step_name: &str) -> Self {
+        TraceSpan::new(tracing::info_span!(
+            "step",
+            step_name = %step_name,
+            status = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+        ))
+    }
+
+    /// Opens the span of a worker of a step.
+    #[cfg(feature = "async")]
+    pub(crate) fn worker(step_name: &str, worker: usize) -> Self {
+        TraceSpan::new(tracing::info_span!("worker", step_name = %step_name, worker))
+    }
+
+    /// Opens the span of a chunk, closed once the chunk has been written.
+    pub(crate) fn chunk(step_name: &str, chunk_index: usize) -> Self {
+        TraceSpan::new(tracing::info_span!(
+            "chunk",
+            step_name = %step_name,
+            chunk_index,
+            item_count = tracing::field::Empty,
+            status = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+        ))
+    }
+
+    /// Returns the span the current thread or task is in.
+    pub(crate) fn current() -> Self {
+        TraceSpan::new(tracing::Span::current())
+    }
+
+    /// Runs a function inside the span.
+    pub(crate) fn in_scope<R>(&self, function: impl FnOnce() -> R) -> R {
+        self.span.in_scope(function)
+    }
+
+    /// Runs a future inside the span.
+    #[cfg(feature = "async")]
+    pub(crate) fn instrument<F: Future>(self, future: F) -> impl Future<Output=F::Output> {
+        tracing::Instrument::instrument(future, self.span)
+    }
+
+    /// Records the number of items of a chunk.
+    pub(crate) fn record_items(&self, item_count: usize) {
+        self.span.record("item_count", item_count);
+    }
+
+    /// Records the outcome of the span and the time elapsed since it was opened.
+    pub(crate) fn finish(&self, succeeded: bool) {
+        self.span.record("status", if succeeded { "COMPLETED" } else { "FAILED" });
+        self.span.record("duration_ms", self.start.elapsed().as_millis() as u64);
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+impl TraceSpan {
+    pub(crate) fn job(_job_name: &str) -> Self {
+        TraceSpan {}
+    }
+
+    pub(crate) fn step(_step_name: &str) -> Self {
+        TraceSpan {}
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn worker(_step_name: &str, _worker: usize) -> Self {
+        TraceSpan {}
+    }
+
+    pub(crate) fn chunk(_step_name: &str, _chunk_index: usize) -> Self {
+        TraceSpan {}
+    }
+
+    pub(crate) fn current() -> Self {
+        TraceSpan {}
+    }
+
+    pub(crate) fn in_scope<R>(&self, function: impl FnOnce() -> R) -> R {
+        function()
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn instrument<F: Future>(self, future: F) -> impl Future<Output=F::Output> {
+        future
+    }
+
+    pub(crate) fn record_items(&self, _item_count: usize) {}
+
+    pub(crate) fn finish(&self, _succeeded: bool) {}
+}
@@ -0,0 +1,360 @@
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+
+use log::error;
+
+use crate::core::item::CompositeMode;
+use crate::core::listener::panic_message;
+use crate::sync::item::ItemWriter;
+
+/// Returns the class of an item, which selects the writer of a classifier writer it is routed to.
+pub type Classifier<O, C> = Box<dyn Fn(&O) -> C + Send>;
+
+/// Undoes the write of a chunk by a delegate of a composite writer, when a later delegate fails
+/// to write it in all-or-nothing mode.
+pub type Compensator<O> = Box<dyn FnMut(Vec<O>) + Send>;
+
+/// Writes each chunk with several writers, e.g. to a database and to an audit file.
+///
+/// Every delegate gets its own copy of the chunk and is opened and closed with the composite
+/// writer. How a failing delegate is handled depends on the `CompositeMode`.
+pub struct CompositeItemWriter<O> {
+    /// The writers of a chunk, in order.
+    writers: Vec<Box<dyn ItemWriter<O> + Send>>,
+    /// The compensator of each writer, if any.
+    compensators: Vec<Option<Compensator<O>>>,
+    /// How a failing writer is handled.
+    mode: CompositeMode,
+}
+
+impl<O: Clone> CompositeItemWriter<O> {
+    /// Undoes the write of a chunk by the writers before the failed one, in reverse order. A
+    /// failing compensator is logged, so that the others still run.
+    fn compensate(&mut self, failed: usize, items: &[O]) {
+        for (index, compensator) in self.compensators[..failed].iter_mut().enumerate().rev() {
+            let compensator = compensator.as_mut().unwrap();
+            if let Err(cause) = panic::catch_unwind(AssertUnwindSafe(|| compensator(items.to_vec()))) {
+                error!("Compensation of composite writer {} failed: {}", index, panic_message(cause.as_ref()));
+            }
+        }
+    }
+}
+
+impl<O: Clone> ItemWriter<O> for CompositeItemWriter<O> {
+    fn open(&mut self) {
+        for writer in &mut self.writers {
+            writer.open();
+        }
+    }
+
+    fn write(&mut self, items: Vec<O>) {
+        match self.mode {
+            CompositeMode::FailFast => {
+                for writer in &mut self.writers {
+                    writer.write(items.clone());
+                }
+            }
+            CompositeMode::AllOrNothing => {
+                for index in 0..self.writers.len() {
+                    let writer = &mut self.writers[index];
+                    if let Err(cause) = panic::catch_unwind(AssertUnwindSafe(|| writer.write(items.clone()))) {
+                        self.compensate(index, &items);
+                        panic::resume_unwind(cause);
+                    }
+                }
+            }
+            CompositeMode::BestEffort => {
+                let mut failure = None;
+                let mut failures = 0;
+                for (index, writer) in self.writers.iter_mut().enumerate() {
+                    if let Err(cause) = panic::catch_unwind(AssertUnwindSafe(|| writer.write(items.clone()))) {
+                        error!("Composite writer {} failed: {}", index, panic_message(cause.as_ref()));
+                        failures += 1;
+                        failure = Some(cause);
+                    }
+                }
+
+                if failures == self.writers.len() {
+                    if let Some(cause) = failure {
+                        panic::resume_unwind(cause);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Counts the items of a chunk as the first writer does, as every writer gets the same chunk.
+    fn item_count(&self, items: &[O]) -> usize {
+        self.writers[0].item_count(items)
+    }
+
+    fn close(&mut self) {
+        for writer in &mut self.writers {
+            writer.close();
+        }
+    }
+}
+
+/// A builder struct for constructing composite item writers.
+pub struct CompositeItemWriterBuilder<O> {
+    /// The writer being constructed.
+    writer: CompositeItemWriter<O>,
+}
+
+impl<O: Clone> CompositeItemWriterBuilder<O> {
+    /// Initializes a new builder without writers, in fail-fast mode.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get() -> Self {
+        CompositeItemWriterBuilder {
+            writer: CompositeItemWriter {
+                writers: Vec::new(),
+                compensators: Vec::new(),
+                mode: CompositeMode::default(),
+            }
+        }
+    }
+
+    /// Adds a writer, called after the writers added before it.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn writer(mut self, writer: Box<dyn ItemWriter<O> + Send>) -> Self {
+        self.writer.writers.push(writer);
+        self.writer.compensators.push(None);
+        self
+    }
+
+    /// Adds a writer with the function undoing its write of a chunk, called in all-or-nothing mode
+    /// when a writer added after it fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer.
+    /// * `compensator` - The function undoing the write of a chunk.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn compensated_writer(mut self, writer: Box<dyn ItemWriter<O> + Send>, compensator: Compensator<O>) -> Self {
+        self.writer.writers.push(writer);
+        self.writer.compensators.push(Some(compensator));
+        self
+    }
+
+    /// Sets how a failing writer is handled.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The composite mode.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn mode(self, mode: CompositeMode) -> Self {
+        CompositeItemWriterBuilder {
+            writer: CompositeItemWriter {
+                mode,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.writer.writers.is_empty() {
+            panic!("At least one writer is required");
+        }
+
+        let compensated = &self.writer.compensators[..self.writer.compensators.len() - 1];
+        if self.writer.mode == CompositeMode::AllOrNothing && compensated.iter().any(Option::is_none) {
+            panic!("Every writer but the last requires a compensator in all-or-nothing mode");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured writer.
+    pub fn build(self) -> CompositeItemWriter<O> {
+        self.validate().writer
+    }
+}
+
+/// Splits each chunk by the class of its items and writes each part with the writer of its class,
+/// e.g. to route the records of a file to a table per record type.
+///
+/// The items keep their order within a part. The parts are written in the order the routes were
+/// added, then the part of the default writer. A chunk with an item of a class without a route
+/// fails before any part is written, unless a default writer is set.
+pub struct ClassifierItemWriter<O, C> {
+    /// Returns the class of an item.
+    classifier: Classifier<O, C>,
+    /// The writer of each class.
+    routes: Vec<(C, Box<dyn ItemWriter<O> + Send>)>,
+    /// The writer of the items of a class without a route.
+    default: Option<Box<dyn ItemWriter<O> + Send>>,
+}
+
+impl<O, C: PartialEq + Debug> ClassifierItemWriter<O, C> {
+    /// Returns the writer an item is routed to, or `None` when its class has no route.
+    fn writer_of(&self, item: &O) -> Option<&(dyn ItemWriter<O> + Send)> {
+        let class = (self.classifier)(item);
+        match self.routes.iter().find(|(route, _)| *route == class) {
+            Some((_, writer)) => Some(writer.as_ref()),
+            None => self.default.as_deref(),
+        }
+    }
+}
+
+impl<O, C: PartialEq + Debug> ItemWriter<O> for ClassifierItemWriter<O, C> {
+    fn open(&mut self) {
+        for (_, writer) in &mut self.routes {
+            writer.open();
+        }
+        if let Some(writer) = &mut self.default {
+            writer.open();
+        }
+    }
+
+    fn write(&mut self, items: Vec<O>) {
+        let mut parts: Vec<Vec<O>> = (0..=self.routes.len()).map(|_| Vec::new()).collect();
+        for item in items {
+            let class = (self.classifier)(&item);
+            let index = match self.routes.iter().position(|(route, _)| *route == class) {
+                Some(index) => index,
+                None if self.default.is_some() => self.routes.len(),
+                None => panic!("No writer for class {:?}", class),
+            };
+            parts[index].push(item);
+        }
+
+        let default = parts.pop().unwrap();
+        for ((_, writer), part) in self.routes.iter_mut().zip(parts) {
+            if !part.is_empty() {
+                writer.write(part);
+            }
+        }
+        if let Some(writer) = &mut self.default {
+            if !default.is_empty() {
+                writer.write(default);
+            }
+        }
+    }
+
+    /// Counts each item as the writer it is routed to does.
+    fn item_count(&self, items: &[O]) -> usize {
+        items.iter()
+            .map(|item| self.writer_of(item).map_or(1, |writer| writer.item_count(std::slice::from_ref(item))))
+            .sum()
+    }
+
+    fn close(&mut self) {
+        for (_, writer) in &mut self.routes {
+            writer.close();
+        }
+        if let Some(writer) = &mut self.default {
+            writer.close();
+        }
+    }
+}
+
+/// A builder struct for constructing classifier item writers.
+pub struct ClassifierItemWriterBuilder<O, C> {
+    /// The writer being constructed.
+    writer: ClassifierItemWriter<O, C>,
+}
+
+impl<O, C: PartialEq + Debug> ClassifierItemWriterBuilder<O, C> {
+    /// Initializes a new builder classifying the items with the given function.
+    ///
+    /// # Arguments
+    ///
+    /// * `classifier` - The function returning the class of an item.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(classifier: Classifier<O, C>) -> Self {
+        ClassifierItemWriterBuilder {
+            writer: ClassifierItemWriter {
+                classifier,
+                routes: Vec::new(),
+                default: None,
+            }
+        }
+    }
+
+    /// Routes the items of a class to a writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - The class.
+    /// * `writer` - The writer of the items of the class.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn route(mut self, class: C, writer: Box<dyn ItemWriter<O> + Send>) -> Self {
+        if self.writer.routes.iter().any(|(route, _)| *route == class) {
+            panic!("Class {:?} is already routed", class);
+        }
+
+        self.writer.routes.push((class, writer));
+        self
+    }
+
+    /// Sets the writer of the items of a class without a route.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The default writer.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn default_writer(self, writer: Box<dyn ItemWriter<O> + Send>) -> Self {
+        ClassifierItemWriterBuilder {
+            writer: ClassifierItemWriter {
+                default: Some(writer),
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.writer.routes.is_empty() && self.writer.default.is_none() {
+            panic!("At least one writer is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured writer.
+    pub fn build(self) -> ClassifierItemWriter<O, C> {
+        self.validate().writer
+    }
+}
@@ -0,0 +1,473 @@
+use std::io::BufRead;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use csv::{ReaderBuilder, StringRecord, Writer, WriterBuilder};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::core::compression::{open_input, Compression, OutputFile};
+use crate::core::item::Positioned;
+use crate::sync::item::{ItemReader, ItemWriter};
+
+/// Reads the rows of a CSV file, deserializing each of them into `T`.
+///
+/// The file is opened when the step opens the reader. With headers, the columns are mapped to the
+/// fields of `T` by name, otherwise by position. A row that cannot be read or deserialized panics
+/// with its line number, which fails the step. Gzip and zstd files are decompressed on the fly,
+/// see [`Compression`].
+///
+/// Besides `T`, the reader yields `Positioned<T>` items carrying the line of each row.
+pub struct CsvItemReader<T> {
+    /// The path of the file to read.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The entry to read from a zip archive, or `None` for its first file.
+    zip_entry: Option<String>,
+    /// The field delimiter.
+    delimiter: u8,
+    /// The quote character.
+    quote: u8,
+    /// Whether the first row holds the column names.
+    has_headers: bool,
+    /// The number of lines skipped before the first row, e.g. a title or a comment.
+    skip_lines: usize,
+    /// The open reader.
+    reader: Option<csv::Reader<Box<dyn BufRead + Send>>>,
+    /// The column names, when the file has headers.
+    headers: Option<StringRecord>,
+    /// The last row read.
+    record: StringRecord,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> CsvItemReader<T> {
+    /// Returns the line the last row read starts at, or zero before the first row.
+    pub fn line(&self) -> u64 {
+        match self.record.position() {
+            Some(position) => position.line() + self.skip_lines as u64,
+            None => 0,
+        }
+    }
+
+    /// Reads and deserializes the next row.
+    fn read_positioned(&mut self) -> Option<Positioned<T>> {
+        let reader = self.reader.as_mut().expect("CSV reader is not open");
+
+        match reader.read_record(&mut self.record) {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(err) => panic!("Error reading CSV file {} after line {}: {}", self.path.display(), self.line(), err),
+        }
+
+        let line = self.line();
+
+        match self.record.deserialize(self.headers.as_ref()) {
+            Ok(item) => Some(Positioned { line, item }),
+            Err(err) => panic!("Error deserializing line {} of CSV file {}: {}", line, self.path.display(), err),
+        }
+    }
+}
+
+impl<T: DeserializeOwned> ItemReader<Positioned<T>> for CsvItemReader<T> {
+    fn open(&mut self) {
+        let mut file = open_input(&self.path, self.compression, self.zip_entry.as_deref())
+            .unwrap_or_else(|err| panic!("Error opening CSV file {}: {}", self.path.display(), err));
+
+        let mut line = String::new();
+        for _ in 0..self.skip_lines {
+            line.clear();
+            file.read_line(&mut line)
+                .unwrap_or_else(|err| panic!("Error reading CSV file {}: {}", self.path.display(), err));
+        }
+
+        let mut reader = ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .has_headers(self.has_headers)
+            .from_reader(file);
+
+        if self.has_headers {
+            let headers = reader.headers()
+                .unwrap_or_else(|err| panic!("Error reading the headers of CSV file {}: {}", self.path.display(), err));
+            self.headers = Some(headers.clone());
+        }
+
+        self.reader = Some(reader);
+    }
+
+    fn read(&mut self) -> Option<Positioned<T>> {
+        self.read_positioned()
+    }
+
+    fn close(&mut self) {
+        self.reader = None;
+    }
+}
+
+impl<T: DeserializeOwned> ItemReader<T> for CsvItemReader<T> {
+    fn open(&mut self) {
+        ItemReader::<Positioned<T>>::open(self);
+    }
+
+    fn read(&mut self) -> Option<T> {
+        self.read_positioned().map(|positioned| positioned.item)
+    }
+
+    fn close(&mut self) {
+        ItemReader::<Positioned<T>>::close(self);
+    }
+}
+
+/// A builder struct for constructing CSV item readers.
+pub struct CsvItemReaderBuilder<T> {
+    /// The reader being constructed.
+    reader: CsvItemReader<T>,
+}
+
+impl<T: DeserializeOwned> CsvItemReaderBuilder<T> {
+    /// Initializes a new builder reading the given file, comma delimited, with headers and
+    /// the compression its extension implies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to read.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        CsvItemReaderBuilder {
+            reader: CsvItemReader {
+                compression: Compression::from_path(&path),
+                zip_entry: None,
+                path,
+                delimiter: b',',
+                quote: b'"',
+                has_headers: true,
+                skip_lines: 0,
+                reader: None,
+                headers: None,
+                record: StringRecord::new(),
+                _item: PhantomData,
+            }
+        }
+    }
+
+    /// Sets the field delimiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `delimiter` - The field delimiter, e.g. `b';'`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn delimiter(self, delimiter: u8) -> Self {
+        CsvItemReaderBuilder {
+            reader: CsvItemReader {
+                delimiter,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the quote character.
+    ///
+    /// # Arguments
+    ///
+    /// * `quote` - The quote character, e.g. `b'\''`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn quote(self, quote: u8) -> Self {
+        CsvItemReaderBuilder {
+            reader: CsvItemReader {
+                quote,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets whether the first row holds the column names. Without headers, the columns are mapped
+    /// to the fields of the item by position.
+    ///
+    /// # Arguments
+    ///
+    /// * `has_headers` - Whether the first row holds the column names.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn has_headers(self, has_headers: bool) -> Self {
+        CsvItemReaderBuilder {
+            reader: CsvItemReader {
+                has_headers,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the number of lines skipped before the headers or the first row.
+    ///
+    /// # Arguments
+    ///
+    /// * `skip_lines` - The number of lines to skip.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn skip_lines(self, skip_lines: usize) -> Self {
+        CsvItemReaderBuilder {
+            reader: CsvItemReader {
+                skip_lines,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Arguments
+    ///
+    /// * `compression` - The compression of the file.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        CsvItemReaderBuilder {
+            reader: CsvItemReader {
+                compression,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the entry to read when the file is a zip archive. By default, the first file of the
+    /// archive is read.
+    ///
+    /// # Arguments
+    ///
+    /// * `zip_entry` - The name of the entry, e.g. `data/records.csv`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn zip_entry(self, zip_entry: impl Into<String>) -> Self {
+        CsvItemReaderBuilder {
+            reader: CsvItemReader {
+                zip_entry: Some(zip_entry.into()),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.reader.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured reader.
+    pub fn build(self) -> CsvItemReader<T> {
+        self.validate().reader
+    }
+}
+
+/// Serializes the items of each chunk as rows of a CSV file.
+///
+/// The file is created, or truncated, when the step opens the writer and flushed when it closes
+/// it. With headers, the column names are taken from the fields of the first item written. The
+/// rows can be compressed while they are written, see [`Compression`].
+pub struct CsvItemWriter<O> {
+    /// The path of the file to write.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The field delimiter.
+    delimiter: u8,
+    /// The quote character.
+    quote: u8,
+    /// Whether a header row is written before the first row.
+    has_headers: bool,
+    /// The open writer.
+    writer: Option<Writer<OutputFile>>,
+    _item: PhantomData<fn(O)>,
+}
+
+impl<O: Serialize> ItemWriter<O> for CsvItemWriter<O> {
+    fn open(&mut self) {
+        let file = OutputFile::create(&self.path, self.compression)
+            .unwrap_or_else(|err| panic!("Error creating CSV file {}: {}", self.path.display(), err));
+        let writer = WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .has_headers(self.has_headers)
+            .from_writer(file);
+        self.writer = Some(writer);
+    }
+
+    fn write(&mut self, items: Vec<O>) {
+        let writer = self.writer.as_mut().expect("CSV writer is not open");
+
+        for item in items {
+            writer.serialize(item)
+                .unwrap_or_else(|err| panic!("Error writing CSV file {}: {}", self.path.display(), err));
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            let file = writer.into_inner()
+                .unwrap_or_else(|err| panic!("Error flushing CSV file {}: {}", self.path.display(), err.error()));
+            file.finish()
+                .unwrap_or_else(|err| panic!("Error flushing CSV file {}: {}", self.path.display(), err));
+        }
+    }
+}
+
+/// A builder struct for constructing CSV item writers.
+pub struct CsvItemWriterBuilder<O> {
+    /// The writer being constructed.
+    writer: CsvItemWriter<O>,
+}
+
+impl<O: Serialize> CsvItemWriterBuilder<O> {
+    /// Initializes a new builder writing the given file, comma delimited, with headers and
+    /// the compression its extension implies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to write.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        CsvItemWriterBuilder {
+            writer: CsvItemWriter {
+                compression: Compression::from_path(&path),
+                path,
+                delimiter: b',',
+                quote: b'"',
+                has_headers: true,
+                writer: None,
+                _item: PhantomData,
+            }
+        }
+    }
+
+    /// Sets the field delimiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `delimiter` - The field delimiter, e.g. `b';'`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn delimiter(self, delimiter: u8) -> Self {
+        CsvItemWriterBuilder {
+            writer: CsvItemWriter {
+                delimiter,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets the quote character.
+    ///
+    /// # Arguments
+    ///
+    /// * `quote` - The quote character, e.g. `b'\''`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn quote(self, quote: u8) -> Self {
+        CsvItemWriterBuilder {
+            writer: CsvItemWriter {
+                quote,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets whether a header row is written before the first row.
+    ///
+    /// # Arguments
+    ///
+    /// * `has_headers` - Whether to write a header row.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn has_headers(self, has_headers: bool) -> Self {
+        CsvItemWriterBuilder {
+            writer: CsvItemWriter {
+                has_headers,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Arguments
+    ///
+    /// * `compression` - The compression of the file; zip archives cannot be written.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        CsvItemWriterBuilder {
+            writer: CsvItemWriter {
+                compression,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.writer.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.writer.compression == Compression::Zip {
+            panic!("Zip archives cannot be written, use gzip or zstd compression");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured writer.
+    pub fn build(self) -> CsvItemWriter<O> {
+        self.validate().writer
+    }
+}
@@ -0,0 +1,368 @@
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use crate::core::compression::{open_input, Compression, OutputFile};
+use crate::core::flat_file::{FieldValue, FlatRecord, RecordAssembler, RecordLayout};
+use crate::sync::item::{ItemReader, ItemWriter};
+
+/// Reads the records of a fixed-width or delimited flat file.
+///
+/// Each line is matched to a record layout by its prefix, and the fields of the layout are
+/// extracted and converted. A layout may span several lines, which are read as a single record.
+/// A line matching no layout, a delimited line with more columns than fields or a field that
+/// cannot be converted panics with its line number, which fails the step. Gzip and zstd input is
+/// supported as described by [`Compression`].
+pub struct FlatFileItemReader {
+    /// The path of the file to read.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The entry to read from a zip archive, or `None` for its first file.
+    zip_entry: Option<String>,
+    /// The layouts of the records of the file.
+    layouts: Vec<RecordLayout>,
+    /// The number of lines skipped at the start of the file.
+    skip_lines: usize,
+    /// The open file.
+    reader: Option<Box<dyn BufRead + Send>>,
+    /// Turns the lines read into records.
+    assembler: RecordAssembler,
+    /// The last line read.
+    buffer: String,
+}
+
+impl ItemReader<FlatRecord> for FlatFileItemReader {
+    fn open(&mut self) {
+        let file = open_input(&self.path, self.compression, self.zip_entry.as_deref())
+            .unwrap_or_else(|err| panic!("Error opening flat file {}: {}", self.path.display(), err));
+        self.reader = Some(file);
+        self.assembler = RecordAssembler::new(self.layouts.clone(), self.skip_lines);
+    }
+
+    fn read(&mut self) -> Option<FlatRecord> {
+        let reader = self.reader.as_mut().expect("Flat file reader is not open");
+
+        loop {
+            self.buffer.clear();
+            let read = reader.read_line(&mut self.buffer)
+                .unwrap_or_else(|err| panic!("Error reading flat file {}: {}", self.path.display(), err));
+            if read == 0 {
+                self.assembler.finish()
+                    .unwrap_or_else(|err| panic!("Error reading flat file {}: {}", self.path.display(), err));
+                return None;
+            }
+
+            let record = self.assembler.push(&self.buffer)
+                .unwrap_or_else(|err| panic!("Error reading flat file {}: {}", self.path.display(), err));
+            if record.is_some() {
+                return record;
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        self.reader = None;
+    }
+}
+
+/// A builder struct for constructing flat file item readers.
+pub struct FlatFileItemReaderBuilder {
+    /// The reader being constructed.
+    reader: FlatFileItemReader,
+}
+
+impl FlatFileItemReaderBuilder {
+    /// Initializes a new builder reading the given file, with the compression its extension
+    /// implies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to read.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        FlatFileItemReaderBuilder {
+            reader: FlatFileItemReader {
+                compression: Compression::from_path(&path),
+                zip_entry: None,
+                path,
+                layouts: Vec::new(),
+                skip_lines: 0,
+                reader: None,
+                assembler: RecordAssembler::new(Vec::new(), 0),
+                buffer: String::new(),
+            }
+        }
+    }
+
+    /// Adds the layout of a kind of record. A file with a single kind of record has a single
+    /// layout, without prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The record layout.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn layout(mut self, layout: RecordLayout) -> Self {
+        self.reader.layouts.push(layout);
+        self
+    }
+
+    /// Sets the number of lines skipped at the start of the file, e.g. a title.
+    ///
+    /// # Arguments
+    ///
+    /// * `skip_lines` - The number of lines to skip.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn skip_lines(self, skip_lines: usize) -> Self {
+        FlatFileItemReaderBuilder {
+            reader: FlatFileItemReader {
+                skip_lines,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Arguments
+    ///
+    /// * `compression` - The compression of the file.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        FlatFileItemReaderBuilder {
+            reader: FlatFileItemReader {
+                compression,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the entry to read when the file is a zip archive. By default, the first file of the
+    /// archive is read.
+    ///
+    /// # Arguments
+    ///
+    /// * `zip_entry` - The name of the entry, e.g. `data/records.txt`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn zip_entry(self, zip_entry: impl Into<String>) -> Self {
+        FlatFileItemReaderBuilder {
+            reader: FlatFileItemReader {
+                zip_entry: Some(zip_entry.into()),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.reader.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.reader.layouts.is_empty() {
+            panic!("At least one record layout is required");
+        }
+
+        self.reader.layouts.iter().for_each(RecordLayout::validate);
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured reader.
+    pub fn build(self) -> FlatFileItemReader {
+        self.validate().reader
+    }
+}
+
+/// Formats an item into the values of the fields of a record, in declaration order.
+pub type FieldFormatter<O> = Box<dyn Fn(&O) -> Vec<FieldValue> + Send>;
+
+/// Writes the items of each chunk as the records of a fixed-width or delimited flat file.
+///
+/// The file is created, or truncated, when the step opens the writer. Fixed-width values are
+/// padded to the width of their field and delimited values are written unquoted; a value too
+/// long for its field, or holding the delimiter or a line break, panics, which fails the chunk.
+/// The output can be compressed, see [`Compression`].
+pub struct FlatFileItemWriter<O> {
+    /// The path of the file to write.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The layout of the records.
+    layout: Option<RecordLayout>,
+    /// Formats an item into the values of its fields.
+    formatter: Option<FieldFormatter<O>>,
+    /// The open file.
+    writer: Option<OutputFile>,
+}
+
+impl<O> ItemWriter<O> for FlatFileItemWriter<O> {
+    fn open(&mut self) {
+        let file = OutputFile::create(&self.path, self.compression)
+            .unwrap_or_else(|err| panic!("Error creating flat file {}: {}", self.path.display(), err));
+        self.writer = Some(file);
+    }
+
+    fn write(&mut self, items: Vec<O>) {
+        let writer = self.writer.as_mut().expect("Flat file writer is not open");
+        let layout = self.layout.as_ref().unwrap();
+        let formatter = self.formatter.as_ref().unwrap();
+
+        for item in items {
+            let record = layout.format(formatter(&item))
+                .unwrap_or_else(|err| panic!("Error formatting a record of flat file {}: {}", self.path.display(), err));
+            writeln!(writer, "{}", record)
+                .unwrap_or_else(|err| panic!("Error writing flat file {}: {}", self.path.display(), err));
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            writer.finish()
+                .unwrap_or_else(|err| panic!("Error flushing flat file {}: {}", self.path.display(), err));
+        }
+    }
+}
+
+/// A builder struct for constructing flat file item writers.
+pub struct FlatFileItemWriterBuilder<O> {
+    /// The writer being constructed.
+    writer: FlatFileItemWriter<O>,
+}
+
+impl<O> FlatFileItemWriterBuilder<O> {
+    /// Initializes a new builder writing the given file, with the compression its extension
+    /// implies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to write.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        FlatFileItemWriterBuilder {
+            writer: FlatFileItemWriter {
+                compression: Compression::from_path(&path),
+                path,
+                layout: None,
+                formatter: None,
+                writer: None,
+            }
+        }
+    }
+
+    /// Sets the layout of the records.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The record layout.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn layout(self, layout: RecordLayout) -> Self {
+        FlatFileItemWriterBuilder {
+            writer: FlatFileItemWriter {
+                layout: Some(layout),
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets the function formatting an item into the values of the fields of its record.
+    ///
+    /// # Arguments
+    ///
+    /// * `formatter` - The formatting function.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn formatter(self, formatter: FieldFormatter<O>) -> Self {
+        FlatFileItemWriterBuilder {
+            writer: FlatFileItemWriter {
+                formatter: Some(formatter),
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Arguments
+    ///
+    /// * `compression` - The compression of the file; zip archives cannot be written.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        FlatFileItemWriterBuilder {
+            writer: FlatFileItemWriter {
+                compression,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.writer.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.writer.compression == Compression::Zip {
+            panic!("Zip archives cannot be written, use gzip or zstd compression");
+        }
+
+        match &self.writer.layout {
+            Some(layout) => layout.validate(),
+            None => panic!("Record layout is required"),
+        }
+
+        if self.writer.formatter.is_none() {
+            panic!("Formatter is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured writer.
+    pub fn build(self) -> FlatFileItemWriter<O> {
+        self.validate().writer
+    }
+}
@@ -0,0 +1,394 @@
+use std::io::{BufRead, Write};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::core::compression::{open_input, Compression, OutputFile};
+use crate::core::item::Positioned;
+use crate::core::json::{JsonArrayScanner, JsonFormat, JsonFraming};
+use crate::sync::item::{ItemReader, ItemWriter};
+
+/// Reads the values of a JSON file one at a time, deserializing each of them into `T`.
+///
+/// Depending on its format, the file holds one value per line or a single top-level array, which
+/// is streamed element by element so that only one item is held in memory. A value that cannot be
+/// deserialized panics with its line number, which fails the step. For compressed input, see
+/// [`Compression`].
+///
+/// Besides `T`, the reader yields `Positioned<T>` items carrying the line of each value.
+pub struct JsonItemReader<T> {
+    /// The path of the file to read.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The entry to read from a zip archive, or `None` for its first file.
+    zip_entry: Option<String>,
+    /// The layout of the file.
+    format: JsonFormat,
+    /// The open file.
+    reader: Option<Box<dyn BufRead + Send>>,
+    /// Splits a top-level array into its elements.
+    scanner: JsonArrayScanner,
+    /// The line of the last value read.
+    line: u64,
+    /// The last line read, for the lines format.
+    buffer: String,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> JsonItemReader<T> {
+    /// Returns the line the last value read starts at, or zero before the first value.
+    pub fn line(&self) -> u64 {
+        self.line
+    }
+
+    /// Reads the bytes of the next value and the line it starts at.
+    fn read_value(&mut self) -> Option<(u64, Vec<u8>)> {
+        let reader = self.reader.as_mut().expect("JSON reader is not open");
+
+        match self.format {
+            JsonFormat::Lines => loop {
+                self.buffer.clear();
+                let read = reader.read_line(&mut self.buffer)
+                    .unwrap_or_else(|err| panic!("Error reading JSON file {}: {}", self.path.display(), err));
+                if read == 0 {
+                    return None;
+                }
+
+                self.line += 1;
+                if !self.buffer.trim().is_empty() {
+                    return Some((self.line, self.buffer.as_bytes().to_vec()));
+                }
+            },
+            JsonFormat::Array => loop {
+                let bytes = reader.fill_buf()
+                    .unwrap_or_else(|err| panic!("Error reading JSON file {}: {}", self.path.display(), err));
+                if bytes.is_empty() {
+                    if self.scanner.is_done() {
+                        return None;
+                    }
+                    panic!("Unexpected end of JSON file {} at line {}", self.path.display(), self.scanner.line());
+                }
+
+                let (consumed, value) = self.scanner.scan(bytes)
+                    .unwrap_or_else(|err| panic!("Error reading JSON file {}: {}", self.path.display(), err));
+                reader.consume(consumed);
+
+                if value.is_some() {
+                    return value;
+                }
+            },
+        }
+    }
+
+    /// Reads and deserializes the next value.
+    fn read_positioned(&mut self) -> Option<Positioned<T>> {
+        let (line, value) = self.read_value()?;
+        self.line = line;
+
+        match serde_json::from_slice(&value) {
+            Ok(item) => Some(Positioned { line, item }),
+            Err(err) => panic!("Error deserializing line {} of JSON file {}: {}", line, self.path.display(), err),
+        }
+    }
+}
+
+impl<T: DeserializeOwned> ItemReader<Positioned<T>> for JsonItemReader<T> {
+    fn open(&mut self) {
+        let file = open_input(&self.path, self.compression, self.zip_entry.as_deref())
+            .unwrap_or_else(|err| panic!("Error opening JSON file {}: {}", self.path.display(), err));
+        self.reader = Some(file);
+        self.scanner = JsonArrayScanner::new();
+        self.line = 0;
+    }
+
+    fn read(&mut self) -> Option<Positioned<T>> {
+        self.read_positioned()
+    }
+
+    fn close(&mut self) {
+        self.reader = None;
+    }
+}
+
+impl<T: DeserializeOwned> ItemReader<T> for JsonItemReader<T> {
+    fn open(&mut self) {
+        ItemReader::<Positioned<T>>::open(self);
+    }
+
+    fn read(&mut self) -> Option<T> {
+        self.read_positioned().map(|positioned| positioned.item)
+    }
+
+    fn close(&mut self) {
+        ItemReader::<Positioned<T>>::close(self);
+    }
+}
+
+/// A builder struct for constructing JSON item readers.
+pub struct JsonItemReaderBuilder<T> {
+    /// The reader being constructed.
+    reader: JsonItemReader<T>,
+}
+
+impl<T: DeserializeOwned> JsonItemReaderBuilder<T> {
+    /// Initializes a new builder reading the given file, with one value per line and the
+    /// compression its extension implies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to read.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        JsonItemReaderBuilder {
+            reader: JsonItemReader {
+                compression: Compression::from_path(&path),
+                zip_entry: None,
+                path,
+                format: JsonFormat::Lines,
+                reader: None,
+                scanner: JsonArrayScanner::new(),
+                line: 0,
+                buffer: String::new(),
+                _item: PhantomData,
+            }
+        }
+    }
+
+    /// Sets the layout of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The layout of the file.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn format(self, format: JsonFormat) -> Self {
+        JsonItemReaderBuilder {
+            reader: JsonItemReader {
+                format,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Arguments
+    ///
+    /// * `compression` - The compression of the file.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        JsonItemReaderBuilder {
+            reader: JsonItemReader {
+                compression,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the entry to read when the file is a zip archive. By default, the first file of the
+    /// archive is read.
+    ///
+    /// # Arguments
+    ///
+    /// * `zip_entry` - The name of the entry, e.g. `data/records.json`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn zip_entry(self, zip_entry: impl Into<String>) -> Self {
+        JsonItemReaderBuilder {
+            reader: JsonItemReader {
+                zip_entry: Some(zip_entry.into()),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.reader.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured reader.
+    pub fn build(self) -> JsonItemReader<T> {
+        self.validate().reader
+    }
+}
+
+/// Serializes the items of each chunk to a JSON file.
+///
+/// The file is created, or truncated, when the step opens the writer. With the array format, the
+/// closing bracket is written when the step closes the writer, so the file is a well-formed array
+/// even when no item has been written. To compress the file, see [`Compression`].
+pub struct JsonItemWriter<O> {
+    /// The path of the file to write.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The layout of the file.
+    format: JsonFormat,
+    /// The open file.
+    writer: Option<OutputFile>,
+    /// Writes the separators around the items.
+    framing: JsonFraming,
+    _item: PhantomData<fn(O)>,
+}
+
+impl<O: Serialize> JsonItemWriter<O> {
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        let writer = self.writer.as_mut().expect("JSON writer is not open");
+        writer.write_all(bytes)
+            .unwrap_or_else(|err| panic!("Error writing JSON file {}: {}", self.path.display(), err));
+    }
+}
+
+impl<O: Serialize> ItemWriter<O> for JsonItemWriter<O> {
+    fn open(&mut self) {
+        let file = OutputFile::create(&self.path, self.compression)
+            .unwrap_or_else(|err| panic!("Error creating JSON file {}: {}", self.path.display(), err));
+        self.writer = Some(file);
+        self.framing = JsonFraming::new(self.format);
+        self.write_bytes(self.framing.header());
+    }
+
+    fn write(&mut self, items: Vec<O>) {
+        for item in items {
+            let value = serde_json::to_vec(&item)
+                .unwrap_or_else(|err| panic!("Error serializing an item of JSON file {}: {}", self.path.display(), err));
+            let separator = self.framing.separator();
+            self.write_bytes(separator);
+            self.write_bytes(&value);
+            self.write_bytes(self.framing.terminator());
+        }
+    }
+
+    fn close(&mut self) {
+        if self.writer.is_none() {
+            return;
+        }
+
+        self.write_bytes(self.framing.footer());
+        if let Some(writer) = self.writer.take() {
+            writer.finish()
+                .unwrap_or_else(|err| panic!("Error flushing JSON file {}: {}", self.path.display(), err));
+        }
+    }
+}
+
+/// A builder struct for constructing JSON item writers.
+pub struct JsonItemWriterBuilder<O> {
+    /// The writer being constructed.
+    writer: JsonItemWriter<O>,
+}
+
+impl<O: Serialize> JsonItemWriterBuilder<O> {
+    /// Initializes a new builder writing the given file, with one value per line and the
+    /// compression its extension implies.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to write.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        JsonItemWriterBuilder {
+            writer: JsonItemWriter {
+                compression: Compression::from_path(&path),
+                path,
+                format: JsonFormat::Lines,
+                writer: None,
+                framing: JsonFraming::new(JsonFormat::Lines),
+                _item: PhantomData,
+            }
+        }
+    }
+
+    /// Sets the layout of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The layout of the file.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn format(self, format: JsonFormat) -> Self {
+        JsonItemWriterBuilder {
+            writer: JsonItemWriter {
+                format,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Arguments
+    ///
+    /// * `compression` - The compression of the file; zip archives cannot be written.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        JsonItemWriterBuilder {
+            writer: JsonItemWriter {
+                compression,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.writer.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.writer.compression == Compression::Zip {
+            panic!("Zip archives cannot be written, use gzip or zstd compression");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured writer.
+    pub fn build(self) -> JsonItemWriter<O> {
+        self.validate().writer
+    }
+}
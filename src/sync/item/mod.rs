@@ -0,0 +1,127 @@
+pub mod composite;
+pub mod flat_file;
+pub mod paging;
+pub mod processor;
+#[cfg(feature = "glob")]
+pub mod multi_resource;
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// A trait for reading the items of a synchronous complex step.
+///
+/// The reader is opened on the thread running the step, before the first item is read, and
+/// closed once the last chunk has been written or the step has failed. Any iterator is a reader.
+pub trait ItemReader<I> {
+    /// Called before the first item is read, e.g. to open a file or a connection.
+    fn open(&mut self) {}
+
+    /// Reads the next item.
+    ///
+    /// # Returns `Option<I>`
+    ///
+    /// Returns the item, or `None` once the input is exhausted.
+    fn read(&mut self) -> Option<I>;
+
+    /// Called once the chunks holding the first items read have been written, e.g. to record a
+    /// restart position. Once every chunk has been written, it is called with the number of
+    /// items read, so a reader that has been exhausted knows the step succeeded.
+    ///
+    /// # Arguments
+    ///
+    /// * `written` - The number of items, counted from the first one read since the reader was
+    ///   opened, that have been written.
+    fn checkpoint(&mut self, _written: u64) {}
+
+    /// Called once the step has stopped reading, e.g. to release a file or a connection.
+    fn close(&mut self) {}
+}
+
+/// A trait for transforming the items of a synchronous complex step.
+///
+/// Any `FnMut(I) -> O` closure is a processor.
+pub trait ItemProcessor<I, O> {
+    /// Called before the first item is processed.
+    fn open(&mut self) {}
+
+    /// Transforms an item.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item read.
+    ///
+    /// # Returns `O`
+    ///
+    /// Returns the item to write.
+    fn process(&mut self, item: I) -> O;
+
+    /// Transforms an item, or skips it, e.g. when it is invalid. The step counts a skipped item
+    /// as skipped and writes nothing for it. Defaults to `process`.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item read.
+    ///
+    /// # Returns `Result<O, String>`
+    ///
+    /// Returns the item to write, or the reason the item is skipped.
+    fn process_or_skip(&mut self, item: I) -> Result<O, String> {
+        Ok(self.process(item))
+    }
+
+    /// Called once the step has stopped processing.
+    fn close(&mut self) {}
+}
+
+/// A trait for writing the chunks of a synchronous complex step.
+///
+/// Any `FnMut(Vec<O>)` closure is a writer.
+pub trait ItemWriter<O> {
+    /// Called before the first chunk is written.
+    fn open(&mut self) {}
+
+    /// Writes a chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items of the chunk.
+    fn write(&mut self, items: Vec<O>);
+
+    /// Counts the items of a chunk, as reported to the listeners, the metrics and the execution
+    /// of the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items of the chunk.
+    ///
+    /// # Returns `usize`
+    ///
+    /// Returns the number of items written, the length of the chunk unless the writer expands it.
+    fn item_count(&self, items: &[O]) -> usize {
+        items.len()
+    }
+
+    /// Called once the step has stopped writing, e.g. to flush buffered output.
+    fn close(&mut self) {}
+}
+
+impl<I, T: Iterator<Item=I>> ItemReader<I> for T {
+    fn read(&mut self) -> Option<I> {
+        self.next()
+    }
+}
+
+impl<I, O, F: FnMut(I) -> O> ItemProcessor<I, O> for F {
+    fn process(&mut self, item: I) -> O {
+        self(item)
+    }
+}
+
+impl<O, F: FnMut(Vec<O>)> ItemWriter<O> for F {
+    fn write(&mut self, items: Vec<O>) {
+        self(items)
+    }
+}
@@ -0,0 +1,198 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::core::item::ResourceItem;
+use crate::core::resource::{matching_resources, ResourceCheckpoint};
+use crate::sync::item::ItemReader;
+
+/// Creates the reader of a resource, e.g. `|path| Box::new(CsvItemReaderBuilder::get(path).build())`.
+pub type ResourceReaderFactory<I> = Box<dyn FnMut(&Path) -> Box<dyn ItemReader<I> + Send> + Send>;
+
+/// Reads the items of every file matching a glob pattern, one file after the other in path order.
+///
+/// Each file is read by its own reader, opened when the previous file has been read completely and
+/// closed once it is exhausted. The items are wrapped with the file they come from.
+///
+/// With a restart file, the file holding the first item not written yet is recorded each time the
+/// step checkpoints the reader, and a new run after a failure skips the files before it. That file
+/// is read again from its start. The restart file is removed once every item has been written.
+pub struct MultiResourceItemReader<I> {
+    /// The glob pattern of the files to read.
+    pattern: String,
+    /// Creates the reader of each file.
+    factory: Option<ResourceReaderFactory<I>>,
+    /// Records the file holding the first item not written yet.
+    checkpoint: Option<ResourceCheckpoint>,
+    /// The files to read, resolved when the reader is opened.
+    resources: Vec<PathBuf>,
+    /// The index of the next file to open.
+    next: usize,
+    /// The file being read and its reader.
+    current: Option<(Arc<Path>, Box<dyn ItemReader<I> + Send>)>,
+    /// The number of items read from the current file.
+    index: u64,
+    /// The number of items read since the reader was opened.
+    read: u64,
+    /// Whether every file has been read.
+    exhausted: bool,
+}
+
+impl<I> ItemReader<ResourceItem<I>> for MultiResourceItemReader<I> {
+    fn open(&mut self) {
+        let resources = matching_resources(&self.pattern);
+        self.resources = match &mut self.checkpoint {
+            Some(checkpoint) => checkpoint.resume(resources),
+            None => resources,
+        };
+        self.next = 0;
+        self.read = 0;
+        self.exhausted = false;
+    }
+
+    fn read(&mut self) -> Option<ResourceItem<I>> {
+        loop {
+            if self.current.is_none() {
+                let Some(resource) = self.resources.get(self.next) else {
+                    self.exhausted = true;
+                    return None;
+                };
+                self.next += 1;
+
+                if let Some(checkpoint) = &mut self.checkpoint {
+                    checkpoint.start(resource, self.read);
+                }
+
+                let factory = self.factory.as_mut().unwrap();
+                let mut reader = factory(resource);
+                reader.open();
+                self.current = Some((Arc::from(resource.as_path()), reader));
+                self.index = 0;
+            }
+
+            let (resource, reader) = self.current.as_mut().unwrap();
+            match reader.read() {
+                Some(item) => {
+                    self.index += 1;
+                    self.read += 1;
+                    return Some(ResourceItem {
+                        resource: Arc::clone(resource),
+                        index: self.index,
+                        item,
+                    });
+                }
+                None => {
+                    reader.close();
+                    self.current = None;
+                }
+            }
+        }
+    }
+
+    fn checkpoint(&mut self, written: u64) {
+        if let Some(checkpoint) = &mut self.checkpoint {
+            checkpoint.written(written, self.read, self.exhausted);
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some((_, mut reader)) = self.current.take() {
+            reader.close();
+        }
+    }
+}
+
+/// A builder struct for constructing multi-resource item readers.
+pub struct MultiResourceItemReaderBuilder<I> {
+    /// The reader being constructed.
+    reader: MultiResourceItemReader<I>,
+}
+
+impl<I> MultiResourceItemReaderBuilder<I> {
+    /// Initializes a new builder reading the files matching the given glob pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The glob pattern, e.g. `input/*.csv`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(pattern: impl Into<String>) -> Self {
+        MultiResourceItemReaderBuilder {
+            reader: MultiResourceItemReader {
+                pattern: pattern.into(),
+                factory: None,
+                checkpoint: None,
+                resources: Vec::new(),
+                next: 0,
+                current: None,
+                index: 0,
+                read: 0,
+                exhausted: false,
+            }
+        }
+    }
+
+    /// Sets the function creating the reader of each file.
+    ///
+    /// # Arguments
+    ///
+    /// * `factory` - The function creating the reader of a file.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn reader(self, factory: ResourceReaderFactory<I>) -> Self {
+        MultiResourceItemReaderBuilder {
+            reader: MultiResourceItemReader {
+                factory: Some(factory),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the file recording the file holding the first item not written yet, so that a new run
+    /// resumes at that file.
+    ///
+    /// # Arguments
+    ///
+    /// * `restart_file` - The path of the restart file.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn restart_file(self, restart_file: impl Into<PathBuf>) -> Self {
+        MultiResourceItemReaderBuilder {
+            reader: MultiResourceItemReader {
+                checkpoint: Some(ResourceCheckpoint::new(restart_file.into())),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.reader.pattern.is_empty() {
+            panic!("Pattern is required");
+        }
+
+        if self.reader.factory.is_none() {
+            panic!("Reader is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured reader.
+    pub fn build(self) -> MultiResourceItemReader<I> {
+        self.validate().reader
+    }
+}
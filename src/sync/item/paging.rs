@@ -0,0 +1,524 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::vec;
+
+use crate::core::restart::RestartFile;
+use crate::sync::item::ItemReader;
+
+/// The number of items of a page, unless configured otherwise.
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Fetches the page of at most `limit` items starting at `offset`, e.g. with a
+/// `SELECT ... ORDER BY id LIMIT ? OFFSET ?` query. A page shorter than `limit` is the last one.
+pub type PageFetcher<I> = Box<dyn FnMut(u64, usize) -> Vec<I> + Send>;
+
+/// Fetches the page of at most `limit` items following the key `after`, or the first page when
+/// `after` is `None`, e.g. with a `SELECT ... WHERE id > ? ORDER BY id LIMIT ?` query. A page
+/// shorter than `limit` is the last one.
+pub type KeysetPageFetcher<I, K> = Box<dyn FnMut(Option<&K>, usize) -> Vec<I> + Send>;
+
+/// Returns the key of an item, by which the pages of a keyset reader are ordered.
+pub type KeyExtractor<I, K> = Box<dyn Fn(&I) -> K + Send>;
+
+/// Fetches pages on its own thread, so that the next page is fetched while the current one is
+/// processed.
+struct PageWorker<R, I> {
+    /// Sends the requests of the pages to fetch.
+    requests: Sender<R>,
+    /// Receives the pages fetched, or the panic fetching them.
+    pages: Receiver<thread::Result<Vec<I>>>,
+    /// The thread fetching the pages.
+    thread: JoinHandle<()>,
+}
+
+impl<R: Send + 'static, I: Send + 'static> PageWorker<R, I> {
+    fn spawn(mut fetch: impl FnMut(R) -> Vec<I> + Send + 'static) -> Self {
+        let (requests, request_receiver) = mpsc::channel::<R>();
+        let (page_sender, pages) = mpsc::channel();
+
+        let thread = thread::spawn(move || {
+            for request in request_receiver {
+                let page = panic::catch_unwind(AssertUnwindSafe(|| fetch(request)));
+                let failed = page.is_err();
+                if page_sender.send(page).is_err() || failed {
+                    return;
+                }
+            }
+        });
+
+        PageWorker { requests, pages, thread }
+    }
+
+    /// Starts fetching a page.
+    fn request(&self, request: R) {
+        let _ = self.requests.send(request);
+    }
+
+    /// Waits for the page requested last. A panic fetching it is resumed on the calling thread.
+    fn receive(&self) -> Vec<I> {
+        match self.pages.recv() {
+            Ok(Ok(page)) => page,
+            Ok(Err(cause)) => panic::resume_unwind(cause),
+            Err(_) => panic!("Page fetching thread stopped"),
+        }
+    }
+
+    /// Stops the thread once the page being fetched, if any, has been fetched, so that the fetcher
+    /// is no longer running when the reader is closed.
+    fn stop(self) {
+        drop(self.requests);
+        drop(self.pages);
+        let _ = self.thread.join();
+    }
+}
+
+/// Reads the items of a source one page at a time, by offset and limit.
+///
+/// The next page is fetched on another thread while the items of the current page are processed.
+/// With a restart file, the offset of the first item not written yet is recorded each time the
+/// step checkpoints the reader, and a new run after a failure resumes at that offset. The restart
+/// file is removed once every item has been written.
+pub struct PagingItemReader<I> {
+    /// Fetches a page.
+    fetcher: Arc<Mutex<PageFetcher<I>>>,
+    /// The number of items of a page.
+    page_size: usize,
+    /// Records the offset of the first item not written yet.
+    restart: Option<RestartFile>,
+    /// Fetches the pages, while the reader is open.
+    worker: Option<PageWorker<(u64, usize), I>>,
+    /// The offset the reader was opened at.
+    start_offset: u64,
+    /// The number of items read since the reader was opened.
+    read: u64,
+    /// The items of the current page not read yet.
+    page: vec::IntoIter<I>,
+    /// The offset of the next page.
+    next_offset: u64,
+    /// Whether the next page has been requested.
+    pending: bool,
+    /// Whether every page has been read.
+    exhausted: bool,
+}
+
+impl<I: Send + 'static> ItemReader<I> for PagingItemReader<I> {
+    fn open(&mut self) {
+        self.next_offset = match self.restart.as_ref().and_then(RestartFile::load) {
+            Some(offset) => offset.trim().parse()
+                .unwrap_or_else(|_| panic!("Invalid offset {} in restart file", offset)),
+            None => 0,
+        };
+
+        let fetcher = Arc::clone(&self.fetcher);
+        let worker = PageWorker::spawn(move |(offset, limit)| {
+            let mut fetch = fetcher.lock().unwrap_or_else(PoisonError::into_inner);
+            fetch(offset, limit)
+        });
+        worker.request((self.next_offset, self.page_size));
+
+        self.worker = Some(worker);
+        self.start_offset = self.next_offset;
+        self.read = 0;
+        self.page = Vec::new().into_iter();
+        self.pending = true;
+        self.exhausted = false;
+    }
+
+    fn read(&mut self) -> Option<I> {
+        let worker = self.worker.as_ref().expect("Paging reader is not open");
+
+        loop {
+            if let Some(item) = self.page.next() {
+                self.read += 1;
+                return Some(item);
+            }
+
+            if !self.pending {
+                self.exhausted = true;
+                return None;
+            }
+
+            let page = worker.receive();
+            self.next_offset += page.len() as u64;
+            self.pending = page.len() >= self.page_size;
+            if self.pending {
+                worker.request((self.next_offset, self.page_size));
+            }
+            self.page = page.into_iter();
+        }
+    }
+
+    fn checkpoint(&mut self, written: u64) {
+        let Some(restart) = &self.restart else {
+            return;
+        };
+
+        if self.exhausted && written == self.read {
+            restart.clear();
+        } else {
+            restart.save(&(self.start_offset + written).to_string());
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.stop();
+        }
+        self.page = Vec::new().into_iter();
+    }
+}
+
+/// A builder struct for constructing paging item readers.
+pub struct PagingItemReaderBuilder<I> {
+    /// The reader being constructed.
+    reader: PagingItemReader<I>,
+}
+
+impl<I: Send + 'static> PagingItemReaderBuilder<I> {
+    /// Initializes a new builder reading the pages fetched by the given function, 100 items at a
+    /// time.
+    ///
+    /// # Arguments
+    ///
+    /// * `fetcher` - The function fetching a page.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(fetcher: PageFetcher<I>) -> Self {
+        PagingItemReaderBuilder {
+            reader: PagingItemReader {
+                fetcher: Arc::new(Mutex::new(fetcher)),
+                page_size: DEFAULT_PAGE_SIZE,
+                restart: None,
+                worker: None,
+                start_offset: 0,
+                read: 0,
+                page: Vec::new().into_iter(),
+                next_offset: 0,
+                pending: false,
+                exhausted: false,
+            }
+        }
+    }
+
+    /// Sets the number of items of a page.
+    ///
+    /// # Arguments
+    ///
+    /// * `page_size` - The number of items of a page.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn page_size(self, page_size: usize) -> Self {
+        PagingItemReaderBuilder {
+            reader: PagingItemReader {
+                page_size,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the file recording the offset of the first item not written yet, so that a new run
+    /// resumes at that item.
+    ///
+    /// # Arguments
+    ///
+    /// * `restart_file` - The path of the restart file.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn restart_file(self, restart_file: impl Into<PathBuf>) -> Self {
+        PagingItemReaderBuilder {
+            reader: PagingItemReader {
+                restart: Some(RestartFile::new(restart_file.into())),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.reader.page_size == 0 {
+            panic!("Page size must be greater than zero");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured reader.
+    pub fn build(self) -> PagingItemReader<I> {
+        self.validate().reader
+    }
+}
+
+/// Reads the items of a source one page at a time, each page following the key of the last item
+/// of the previous one.
+///
+/// Unlike an offset, a key keeps its cost constant on large tables and is not shifted by rows
+/// inserted or deleted during the reading. The next page is fetched on another thread while the
+/// items of the current page are processed. With a restart file, the key of the last item written
+/// is recorded each time the step checkpoints the reader, and a new run after a failure resumes
+/// after that key. The restart file is removed once every item has been written.
+pub struct KeysetItemReader<I, K> {
+    /// Fetches a page.
+    fetcher: Arc<Mutex<KeysetPageFetcher<I, K>>>,
+    /// Returns the key of an item.
+    key: Option<KeyExtractor<I, K>>,
+    /// The number of items of a page.
+    page_size: usize,
+    /// Records the key of the last item written.
+    restart: Option<RestartFile>,
+    /// Fetches the pages, while the reader is open.
+    worker: Option<PageWorker<(Option<K>, usize), I>>,
+    /// The keys of the items read but not written yet, when there is a restart file.
+    unwritten: VecDeque<K>,
+    /// The number of items read since the reader was opened.
+    read: u64,
+    /// The number of items written when the reader was last checkpointed.
+    written: u64,
+    /// The items of the current page not read yet.
+    page: vec::IntoIter<I>,
+    /// The key the next page follows.
+    next_key: Option<K>,
+    /// The key of the last item read.
+    last_key: Option<K>,
+    /// Whether the next page has been requested.
+    pending: bool,
+    /// Whether every page has been read.
+    exhausted: bool,
+}
+
+impl<I, K> KeysetItemReader<I, K> {
+    /// Returns the key of the last item read, or `None` before the first item.
+    pub fn last_key(&self) -> Option<&K> {
+        self.last_key.as_ref()
+    }
+}
+
+impl<I, K> ItemReader<I> for KeysetItemReader<I, K>
+where
+    I: Send + 'static,
+    K: Clone + Display + FromStr + Send + 'static,
+{
+    fn open(&mut self) {
+        // Only a final line break is ignored, as the spaces around a key may be part of it.
+        self.next_key = self.restart.as_ref().and_then(RestartFile::load).map(|key| {
+            key.strip_suffix('\n').unwrap_or(&key).parse()
+                .unwrap_or_else(|_| panic!("Invalid key {:?} in restart file", key))
+        });
+
+        let fetcher = Arc::clone(&self.fetcher);
+        let worker = PageWorker::spawn(move |(after, limit): (Option<K>, usize)| {
+            let mut fetch = fetcher.lock().unwrap_or_else(PoisonError::into_inner);
+            fetch(after.as_ref(), limit)
+        });
+        worker.request((self.next_key.clone(), self.page_size));
+
+        self.worker = Some(worker);
+        self.unwritten.clear();
+        self.read = 0;
+        self.written = 0;
+        self.page = Vec::new().into_iter();
+        self.last_key = None;
+        self.pending = true;
+        self.exhausted = false;
+    }
+
+    fn read(&mut self) -> Option<I> {
+        let worker = self.worker.as_ref().expect("Keyset reader is not open");
+        let key = self.key.as_ref().unwrap();
+
+        loop {
+            if let Some(item) = self.page.next() {
+                let item_key = key(&item);
+                if self.restart.is_some() {
+                    self.unwritten.push_back(item_key.clone());
+                }
+                self.last_key = Some(item_key);
+                self.read += 1;
+                return Some(item);
+            }
+
+            if !self.pending {
+                self.exhausted = true;
+                return None;
+            }
+
+            let page = worker.receive();
+            let after = self.next_key.take();
+            self.next_key = page.last().map(key).or(after);
+            self.pending = page.len() >= self.page_size;
+            if self.pending {
+                worker.request((self.next_key.clone(), self.page_size));
+            }
+            self.page = page.into_iter();
+        }
+    }
+
+    fn checkpoint(&mut self, written: u64) {
+        let Some(restart) = &self.restart else {
+            return;
+        };
+
+        if self.exhausted && written == self.read {
+            restart.clear();
+            return;
+        }
+
+        let mut last_written = None;
+        while self.written < written {
+            last_written = self.unwritten.pop_front();
+            self.written += 1;
+        }
+        if let Some(key) = last_written {
+            restart.save(&key.to_string());
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.stop();
+        }
+        self.page = Vec::new().into_iter();
+    }
+}
+
+/// A builder struct for constructing keyset item readers.
+pub struct KeysetItemReaderBuilder<I, K> {
+    /// The reader being constructed.
+    reader: KeysetItemReader<I, K>,
+}
+
+impl<I, K> KeysetItemReaderBuilder<I, K>
+where
+    I: Send + 'static,
+    K: Clone + Display + FromStr + Send + 'static,
+{
+    /// Initializes a new builder reading the pages fetched by the given function, 100 items at a
+    /// time.
+    ///
+    /// # Arguments
+    ///
+    /// * `fetcher` - The function fetching a page.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(fetcher: KeysetPageFetcher<I, K>) -> Self {
+        KeysetItemReaderBuilder {
+            reader: KeysetItemReader {
+                fetcher: Arc::new(Mutex::new(fetcher)),
+                key: None,
+                page_size: DEFAULT_PAGE_SIZE,
+                restart: None,
+                worker: None,
+                unwritten: VecDeque::new(),
+                read: 0,
+                written: 0,
+                page: Vec::new().into_iter(),
+                next_key: None,
+                last_key: None,
+                pending: false,
+                exhausted: false,
+            }
+        }
+    }
+
+    /// Sets the function returning the key of an item.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key function.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn key(self, key: KeyExtractor<I, K>) -> Self {
+        KeysetItemReaderBuilder {
+            reader: KeysetItemReader {
+                key: Some(key),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the number of items of a page.
+    ///
+    /// # Arguments
+    ///
+    /// * `page_size` - The number of items of a page.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn page_size(self, page_size: usize) -> Self {
+        KeysetItemReaderBuilder {
+            reader: KeysetItemReader {
+                page_size,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the file recording the key of the last item written, so that a new run resumes after
+    /// that key.
+    ///
+    /// # Arguments
+    ///
+    /// * `restart_file` - The path of the restart file.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn restart_file(self, restart_file: impl Into<PathBuf>) -> Self {
+        KeysetItemReaderBuilder {
+            reader: KeysetItemReader {
+                restart: Some(RestartFile::new(restart_file.into())),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.reader.key.is_none() {
+            panic!("Key is required");
+        }
+
+        if self.reader.page_size == 0 {
+            panic!("Page size must be greater than zero");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured reader.
+    pub fn build(self) -> KeysetItemReader<I, K> {
+        self.validate().reader
+    }
+}
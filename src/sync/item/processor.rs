@@ -0,0 +1,252 @@
+use crate::core::item::Validator;
+use crate::sync::item::{ItemProcessor, ItemWriter};
+
+/// Expands an item into any number of outputs.
+pub type Expander<I, O> = Box<dyn FnMut(I) -> Vec<O> + Send>;
+
+/// Transforms an item with a processor, then its output with the next one.
+struct Chain<I, M, O> {
+    /// The first processor.
+    first: Box<dyn ItemProcessor<I, M> + Send>,
+    /// The processor of the outputs of the first one.
+    second: Box<dyn ItemProcessor<M, O> + Send>,
+}
+
+impl<I, M, O> ItemProcessor<I, O> for Chain<I, M, O> {
+    fn open(&mut self) {
+        self.first.open();
+        self.second.open();
+    }
+
+    fn process(&mut self, item: I) -> O {
+        let intermediate = self.first.process(item);
+        self.second.process(intermediate)
+    }
+
+    fn process_or_skip(&mut self, item: I) -> Result<O, String> {
+        let intermediate = self.first.process_or_skip(item)?;
+        self.second.process_or_skip(intermediate)
+    }
+
+    fn close(&mut self) {
+        self.first.close();
+        self.second.close();
+    }
+}
+
+/// Runs an item through a pipeline of processors, e.g. parse, validate, enrich and map, each one
+/// transforming the output of the previous one.
+///
+/// Every processor is opened and closed with the chain. A processor failing fails the whole chain
+/// for that item, so the step handles the failure, notifies its listeners and counts it as if a
+/// single processor had failed. Likewise, a processor skipping an item skips it for the chain.
+pub struct ChainedItemProcessor<I, O> {
+    /// The processors, chained into one.
+    processor: Box<dyn ItemProcessor<I, O> + Send>,
+}
+
+impl<I, O> ItemProcessor<I, O> for ChainedItemProcessor<I, O> {
+    fn open(&mut self) {
+        self.processor.open();
+    }
+
+    fn process(&mut self, item: I) -> O {
+        self.processor.process(item)
+    }
+
+    fn process_or_skip(&mut self, item: I) -> Result<O, String> {
+        self.processor.process_or_skip(item)
+    }
+
+    fn close(&mut self) {
+        self.processor.close();
+    }
+}
+
+/// A builder struct for constructing chained item processors.
+pub struct ChainedItemProcessorBuilder<I, O> {
+    /// The processor being constructed.
+    processor: ChainedItemProcessor<I, O>,
+}
+
+impl<I: 'static, O: 'static> ChainedItemProcessorBuilder<I, O> {
+    /// Initializes a new builder starting the chain with the given processor.
+    ///
+    /// # Arguments
+    ///
+    /// * `processor` - The first processor.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(processor: Box<dyn ItemProcessor<I, O> + Send>) -> Self {
+        ChainedItemProcessorBuilder {
+            processor: ChainedItemProcessor {
+                processor,
+            }
+        }
+    }
+
+    /// Appends a processor transforming the outputs of the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - The next processor, which may change the output type of the chain.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn then<P: 'static>(self, next: Box<dyn ItemProcessor<O, P> + Send>) -> ChainedItemProcessorBuilder<I, P> {
+        ChainedItemProcessorBuilder {
+            processor: ChainedItemProcessor {
+                processor: Box::new(Chain {
+                    first: self.processor.processor,
+                    second: next,
+                }),
+            }
+        }
+    }
+
+    /// Builds and returns the configured processor.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured processor.
+    pub fn build(self) -> ChainedItemProcessor<I, O> {
+        self.processor
+    }
+}
+
+/// Passes valid items through unchanged and fails on invalid ones, or skips them.
+///
+/// By default, an invalid item panics with the reason given by the validator, so the step handles
+/// it like any other processing error. With `skip_invalid`, the step skips it instead and counts
+/// it as skipped.
+pub struct ValidatingItemProcessor<T> {
+    /// Checks an item.
+    validator: Box<dyn Validator<T> + Send>,
+    /// Whether an invalid item is skipped rather than failing the step.
+    skip_invalid: bool,
+}
+
+impl<T> ValidatingItemProcessor<T> {
+    /// Creates a processor checking the items with the given validator.
+    ///
+    /// # Arguments
+    ///
+    /// * `validator` - The validator.
+    ///
+    /// # Returns
+    ///
+    /// Returns the processor.
+    pub fn new(validator: Box<dyn Validator<T> + Send>) -> Self {
+        ValidatingItemProcessor { validator, skip_invalid: false }
+    }
+
+    /// Skips the invalid items instead of failing on them.
+    ///
+    /// # Returns
+    ///
+    /// Returns the modified processor.
+    pub fn skip_invalid(self) -> Self {
+        ValidatingItemProcessor {
+            skip_invalid: true,
+            ..self
+        }
+    }
+}
+
+impl<T> ItemProcessor<T, T> for ValidatingItemProcessor<T> {
+    fn process(&mut self, item: T) -> T {
+        if let Err(reason) = self.validator.validate(&item) {
+            panic!("Invalid item: {}", reason);
+        }
+
+        item
+    }
+
+    fn process_or_skip(&mut self, item: T) -> Result<T, String> {
+        match self.validator.validate(&item) {
+            Ok(()) => Ok(item),
+            Err(reason) if self.skip_invalid => Err(format!("Invalid item: {}", reason)),
+            Err(reason) => panic!("Invalid item: {}", reason),
+        }
+    }
+}
+
+/// Expands each item into any number of outputs, e.g. an order into its lines.
+///
+/// The outputs of an item are processed as one `Vec`, so the writer of the step is a
+/// `FlattenItemWriter`. The chunk size counts the items read, not the outputs; a weigher returning
+/// the length of the outputs bounds the chunks by outputs instead. The items written are counted
+/// by outputs.
+pub struct FlatMapItemProcessor<I, O> {
+    /// Expands an item.
+    expander: Expander<I, O>,
+}
+
+impl<I, O> FlatMapItemProcessor<I, O> {
+    /// Creates a processor expanding the items with the given function.
+    ///
+    /// # Arguments
+    ///
+    /// * `expander` - The function returning the outputs of an item.
+    ///
+    /// # Returns
+    ///
+    /// Returns the processor.
+    pub fn new(expander: Expander<I, O>) -> Self {
+        FlatMapItemProcessor { expander }
+    }
+}
+
+impl<I, O> ItemProcessor<I, Vec<O>> for FlatMapItemProcessor<I, O> {
+    fn process(&mut self, item: I) -> Vec<O> {
+        (self.expander)(item)
+    }
+}
+
+/// Writes the outputs of a flat-map processor as a single chunk with the given writer.
+///
+/// A chunk whose items all expanded into nothing is not written. The step counts the outputs of a
+/// chunk as its items written.
+pub struct FlattenItemWriter<O> {
+    /// The writer of the outputs.
+    writer: Box<dyn ItemWriter<O> + Send>,
+}
+
+impl<O> FlattenItemWriter<O> {
+    /// Creates a writer flattening the chunks for the given writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer of the outputs.
+    ///
+    /// # Returns
+    ///
+    /// Returns the writer.
+    pub fn new(writer: Box<dyn ItemWriter<O> + Send>) -> Self {
+        FlattenItemWriter { writer }
+    }
+}
+
+impl<O> ItemWriter<Vec<O>> for FlattenItemWriter<O> {
+    fn open(&mut self) {
+        self.writer.open();
+    }
+
+    fn write(&mut self, items: Vec<Vec<O>>) {
+        let outputs: Vec<O> = items.into_iter().flatten().collect();
+        if !outputs.is_empty() {
+            self.writer.write(outputs);
+        }
+    }
+
+    fn item_count(&self, items: &[Vec<O>]) -> usize {
+        items.iter().map(Vec::len).sum()
+    }
+
+    fn close(&mut self) {
+        self.writer.close();
+    }
+}
@@ -0,0 +1,377 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+
+use rusqlite::types::Value;
+use rusqlite::Connection;
+
+use crate::core::sqlite::{insert_statement, open_connection, stream_rows, write_rows, RowMapper, SharedRowMapper, ROWS_AHEAD};
+use crate::sync::item::{ItemReader, ItemWriter};
+
+/// Reads the rows of a SQLite query, mapping each of them to `T`.
+///
+/// The query runs on its own thread when the step opens the reader, and maps a bounded number of
+/// rows ahead of the step, so that a large result is streamed rather than loaded. A query or a row
+/// that cannot be read or mapped panics, which fails the step.
+pub struct SqliteItemReader<T> {
+    /// The path of the database.
+    path: PathBuf,
+    /// The query selecting the rows.
+    query: String,
+    /// The values bound to the parameters of the query.
+    parameters: Vec<Value>,
+    /// Maps a row to an item.
+    mapper: Option<SharedRowMapper<T>>,
+    /// Whether the database is switched to write-ahead logging.
+    wal: bool,
+    /// Receives the items mapped by the query thread.
+    receiver: Option<Receiver<rusqlite::Result<T>>>,
+}
+
+impl<T: Send + 'static> ItemReader<T> for SqliteItemReader<T> {
+    fn open(&mut self) {
+        let (sender, receiver) = mpsc::sync_channel(ROWS_AHEAD);
+        let path = self.path.clone();
+        let wal = self.wal;
+        let query = self.query.clone();
+        let parameters = self.parameters.clone();
+        let mapper = Arc::clone(self.mapper.as_ref().unwrap());
+
+        thread::spawn(move || {
+            stream_rows(&path, wal, &query, &parameters, mapper.as_ref(), |item| sender.send(item).is_ok());
+        });
+        self.receiver = Some(receiver);
+    }
+
+    fn read(&mut self) -> Option<T> {
+        let receiver = self.receiver.as_ref().expect("SQLite reader is not open");
+
+        match receiver.recv() {
+            Ok(Ok(item)) => Some(item),
+            Ok(Err(err)) => panic!("Error reading SQLite database {}: {}", self.path.display(), err),
+            Err(_) => None,
+        }
+    }
+
+    fn close(&mut self) {
+        self.receiver = None;
+    }
+}
+
+/// A builder struct for constructing SQLite item readers.
+pub struct SqliteItemReaderBuilder<T> {
+    /// The reader being constructed.
+    reader: SqliteItemReader<T>,
+}
+
+impl<T: Send + 'static> SqliteItemReaderBuilder<T> {
+    /// Initializes a new builder reading the given database.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the database.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        SqliteItemReaderBuilder {
+            reader: SqliteItemReader {
+                path: path.into(),
+                query: String::new(),
+                parameters: Vec::new(),
+                mapper: None,
+                wal: false,
+                receiver: None,
+            }
+        }
+    }
+
+    /// Sets the query selecting the rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query, e.g. `SELECT id, make FROM cars WHERE year >= ?1`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn query(self, query: impl Into<String>) -> Self {
+        SqliteItemReaderBuilder {
+            reader: SqliteItemReader {
+                query: query.into(),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the values bound to the parameters of the query, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - The values of the parameters.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn parameters(self, parameters: Vec<Value>) -> Self {
+        SqliteItemReaderBuilder {
+            reader: SqliteItemReader {
+                parameters,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the function mapping a row to an item.
+    ///
+    /// # Arguments
+    ///
+    /// * `mapper` - The mapping function.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn mapper(self, mapper: RowMapper<T>) -> Self {
+        SqliteItemReaderBuilder {
+            reader: SqliteItemReader {
+                mapper: Some(Arc::from(mapper)),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Switches the database to write-ahead logging when the reader opens it, so that a writer of
+    /// the same step can commit its chunks while the query is running. The journal mode is stored
+    /// in the database file and stays in effect for every later connection, until it is changed
+    /// back.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn wal(self) -> Self {
+        SqliteItemReaderBuilder {
+            reader: SqliteItemReader {
+                wal: true,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.reader.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.reader.query.is_empty() {
+            panic!("Query is required");
+        }
+
+        if self.reader.mapper.is_none() {
+            panic!("Mapper is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured reader.
+    pub fn build(self) -> SqliteItemReader<T> {
+        self.validate().reader
+    }
+}
+
+/// Binds an item to the parameters of the statement writing it, in order.
+pub type ParameterBinder<O> = Box<dyn Fn(&O) -> Vec<Value> + Send>;
+
+/// Writes the items of each chunk to a SQLite database, executing a statement per item.
+///
+/// The database is opened when the step opens the writer. Each chunk is written in a single
+/// transaction, so a chunk whose write fails leaves no row behind and can be written again. A step
+/// reading and writing the same database needs write-ahead logging, see `wal` on the builders.
+pub struct SqliteItemWriter<O> {
+    /// The path of the database.
+    path: PathBuf,
+    /// The statement writing an item.
+    statement: Option<String>,
+    /// Binds an item to the parameters of the statement.
+    binder: Option<ParameterBinder<O>>,
+    /// Whether the database is switched to write-ahead logging.
+    wal: bool,
+    /// The open database.
+    connection: Option<Connection>,
+}
+
+impl<O> ItemWriter<O> for SqliteItemWriter<O> {
+    fn open(&mut self) {
+        let connection = open_connection(&self.path, self.wal)
+            .unwrap_or_else(|err| panic!("Error opening SQLite database {}: {}", self.path.display(), err));
+        self.connection = Some(connection);
+    }
+
+    fn write(&mut self, items: Vec<O>) {
+        let connection = self.connection.as_mut().expect("SQLite writer is not open");
+        let binder = self.binder.as_ref().unwrap();
+        let rows = items.iter().map(binder).collect();
+
+        write_rows(connection, self.statement.as_ref().unwrap(), rows)
+            .unwrap_or_else(|err| panic!("Error writing SQLite database {}: {}", self.path.display(), err));
+    }
+
+    fn close(&mut self) {
+        self.connection = None;
+    }
+}
+
+/// A builder struct for constructing SQLite item writers.
+pub struct SqliteItemWriterBuilder<O> {
+    /// The writer being constructed.
+    writer: SqliteItemWriter<O>,
+}
+
+impl<O> SqliteItemWriterBuilder<O> {
+    /// Initializes a new builder writing the given database.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the database.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        SqliteItemWriterBuilder {
+            writer: SqliteItemWriter {
+                path: path.into(),
+                statement: None,
+                binder: None,
+                wal: false,
+                connection: None,
+            }
+        }
+    }
+
+    /// Sets the statement writing an item.
+    ///
+    /// # Arguments
+    ///
+    /// * `statement` - The statement, e.g. `INSERT INTO cars (id, make) VALUES (?1, ?2)`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn sql(self, statement: impl Into<String>) -> Self {
+        SqliteItemWriterBuilder {
+            writer: SqliteItemWriter {
+                statement: Some(statement.into()),
+                ..self.writer
+            }
+        }
+    }
+
+    /// Inserts each item as a row of a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to write to.
+    /// * `columns` - The columns written, in the order of the values of the binder.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn insert(self, table: &str, columns: &[&str]) -> Self {
+        self.sql(insert_statement(table, columns, &[]))
+    }
+
+    /// Inserts each item as a row of a table, or updates the row with the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table to write to.
+    /// * `columns` - The columns written, in the order of the values of the binder.
+    /// * `keys` - The columns of a unique index identifying a row, e.g. the primary key.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn upsert(self, table: &str, columns: &[&str], keys: &[&str]) -> Self {
+        if keys.is_empty() {
+            panic!("At least one key column is required");
+        }
+
+        self.sql(insert_statement(table, columns, keys))
+    }
+
+    /// Sets the function binding an item to the parameters of the statement.
+    ///
+    /// # Arguments
+    ///
+    /// * `binder` - The binding function.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn binder(self, binder: ParameterBinder<O>) -> Self {
+        SqliteItemWriterBuilder {
+            writer: SqliteItemWriter {
+                binder: Some(binder),
+                ..self.writer
+            }
+        }
+    }
+
+    /// Switches the database to write-ahead logging when the writer opens it, as needed when the
+    /// reader of the step queries the same database. The journal mode is stored in the database
+    /// file and stays in effect for every later connection, until it is changed back.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    pub fn wal(self) -> Self {
+        SqliteItemWriterBuilder {
+            writer: SqliteItemWriter {
+                wal: true,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    pub fn validate(self) -> Self {
+        if self.writer.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.writer.statement.is_none() {
+            panic!("Statement is required");
+        }
+
+        if self.writer.binder.is_none() {
+            panic!("Binder is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured writer.
+    pub fn build(self) -> SqliteItemWriter<O> {
+        self.validate().writer
+    }
+}
@@ -1,4 +1,9 @@
+use std::sync::Arc;
+use crate::core::dag::DagScheduler;
+use crate::core::flow::Flow;
+use crate::core::progress::ProgressEvents;
 use crate::sync::job::Job;
+use crate::sync::listener::JobExecutionListener;
 use crate::sync::step::SyncStep;
 
 /// A trait for building synchronous jobs.
@@ -32,6 +37,42 @@ pub trait JobBuilderTrait {
     /// Returns a modified builder instance.
     fn multi_threaded(self, max_threads: usize) -> Self;
 
+    /// Sets the transitions between the steps of the job.
+    ///
+    /// The steps then run sequentially, starting with the first step of the flow and following the
+    /// transition matching the exit status of each step.
+    ///
+    /// # Arguments
+    ///
+    /// * `flow` - The flow of the job.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn flow(self, flow: Flow) -> Self;
+
+    /// Registers a listener notified before and after the job runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn listener(self, listener: Arc<dyn JobExecutionListener>) -> Self;
+
+    /// Sets the broadcaster the progress events of the job are emitted to.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - The broadcaster, subscribed to before the job runs.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn progress(self, progress: ProgressEvents) -> Self;
+
     /// Initializes a new builder instance with the given name.
     ///
     /// # Arguments
@@ -60,10 +101,36 @@ pub struct JobBuilder {
 impl JobBuilderTrait for JobBuilder {
     /// Validates the builder configuration by ensuring at least one step is added to the job.
     ///
+    /// # Panics
+    ///
+    /// Panics if the flow or the step dependencies reference unknown steps, or if the dependencies
+    /// form a cycle.
+    ///
     /// # Returns `Self`
     ///
     /// Returns a modified builder instance if validation succeeds.
     fn validate(self) -> Self {
+        let has_dependencies = self.job.steps.iter().any(|step| !step.depends_on.is_empty());
+
+        if let Some(flow) = &self.job.flow {
+            if has_dependencies {
+                panic!("A job cannot combine a flow with step dependencies");
+            }
+            let step_names: Vec<&str> = self.job.steps.iter().map(|step| step.name.as_str()).collect();
+            flow.validate(&step_names);
+        }
+
+        if has_dependencies {
+            let dependencies: Vec<(&str, &[String])> = self.job.steps.iter()
+                .map(|step| (step.name.as_str(), step.depends_on.as_slice()))
+                .collect();
+            DagScheduler::new(&dependencies);
+        }
+
+        if self.job.max_threads == Some(0) {
+            panic!("The maximum number of threads must be greater than zero");
+        }
+
         self
     }
 
@@ -100,6 +167,56 @@ impl JobBuilderTrait for JobBuilder {
         }
     }
 
+    /// Sets the transitions between the steps of the job.
+    ///
+    /// # Arguments
+    ///
+    /// * `flow` - The flow of the job.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn flow(self, flow: Flow) -> Self {
+        JobBuilder {
+            job: Job {
+                flow: Some(flow),
+                ..self.job
+            }
+        }
+    }
+
+    /// Registers a listener notified before and after the job runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn listener(mut self, listener: Arc<dyn JobExecutionListener>) -> Self {
+        self.job.listeners.push(listener);
+        self
+    }
+
+    /// Sets the broadcaster the progress events of the job are emitted to.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - The broadcaster, subscribed to before the job runs.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn progress(self, progress: ProgressEvents) -> Self {
+        JobBuilder {
+            job: Job {
+                progress,
+                ..self.job
+            }
+        }
+    }
+
     /// Initializes a new builder instance with the given name.
     ///
     /// # Arguments
@@ -119,6 +236,9 @@ impl JobBuilderTrait for JobBuilder {
                 steps: Vec::new(),
                 multi_threaded: None,
                 max_threads: None,
+                flow: None,
+                listeners: Vec::new(),
+                progress: ProgressEvents::new(),
             }
         }
     }
@@ -1,10 +1,18 @@
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, mpsc};
 use std::thread::{JoinHandle, spawn};
+use std::time::Instant;
 
 use log::{error, info};
 
-use crate::core::job::{now_time, JobStatus};
-use crate::core::step::StepStatus;
+use crate::core::dag::DagScheduler;
+use crate::core::flow::{Flow, FlowOutcome};
+use crate::core::progress::{ProgressEvent, ProgressEvents};
+use crate::core::recorder;
+use crate::core::job::{now_time, JobContext, JobStatus};
+use crate::core::step::{SKIPPED, StepStatus};
+use crate::core::trace::TraceSpan;
+use crate::sync::listener::JobExecutionListener;
 use crate::sync::step::{Decider, Runner, SyncStep};
 
 pub mod job_builder;
@@ -23,17 +31,72 @@ pub struct Job {
     pub multi_threaded: Option<bool>,
     /// The maximum number of threads allowed for multithreaded execution.
     pub max_threads: Option<usize>,
+    /// The transitions between steps, run sequentially in place of the list of steps.
+    pub flow: Option<Flow>,
+    /// The listeners notified before and after the job runs.
+    pub listeners: Vec<Arc<dyn JobExecutionListener>>,
+    /// The broadcaster the progress events of the job are emitted to.
+    pub progress: ProgressEvents,
 }
 
 impl Runner for Job {
     /// The output type of the job execution.
     type Output = JobStatus;
 
-    /// Executes the synchronous job and returns its status.
-    fn run(self) -> Self::Output {
+    /// Executes the synchronous job, notifying its listeners, and returns its status.
+    fn run(mut self) -> Self::Output {
+        let started = Instant::now();
+        let span = TraceSpan::job(&self.name);
+        let name = self.name.clone();
+        let listeners = std::mem::take(&mut self.listeners);
+        let progress = self.progress.clone();
+        for step in &self.steps {
+            step.execution.join_job(name.clone(), progress.clone());
+        }
+        progress.emit(ProgressEvent::JobStarted { job_name: name.clone(), step_count: self.steps.len() });
+        let job_status = span.in_scope(|| {
+            if !listeners.is_empty() {
+                let context = JobContext::new(name.clone(), self.steps.iter().map(|step| step.name.clone()).collect());
+                for listener in &listeners {
+                    listener.before_job(&context);
+                }
+            }
+            let mut job_status = self.execute();
+            for listener in listeners.iter().rev() {
+                listener.after_job(&mut job_status);
+            }
+            job_status
+        });
+        span.finish(job_status.status.is_ok());
+        recorder::job_finished(&name, job_status.status.is_ok(), started.elapsed());
+        progress.emit(ProgressEvent::JobFinished {
+            job_name: name,
+            succeeded: job_status.status.is_ok(),
+            duration: started.elapsed(),
+        });
+        job_status
+    }
+}
+
+impl Job {
+    /// Executes the steps of the job and returns its status.
+    fn execute(self) -> JobStatus {
         let start_time = now_time();
         let multi_threaded = self.multi_threaded.unwrap_or(false);
         let steps = self.steps;
+        let progress = self.progress;
+
+        if let Some(flow) = self.flow {
+            info!("Running job {} with flow", self.name);
+            return run_flow(self.name, flow, steps, &progress, start_time);
+        }
+
+        if steps.iter().any(|step| !step.depends_on.is_empty()) {
+            let max_threads = if multi_threaded { self.max_threads.unwrap_or(1) } else { 1 };
+            info!("Running job {} following step dependencies with {} threads", self.name, max_threads);
+            return run_dag(self.name, steps, max_threads, &progress, start_time);
+        }
+
         if multi_threaded {
             info!("Running job {} with multi-threaded mode", self.name)
         } else {
@@ -54,8 +117,9 @@ impl Runner for Job {
         return if !multi_threaded {
             let mut steps_status_vec: Vec<StepStatus> = Vec::new();
             for step in steps {
-                if step.is_run().clone() {
+                if !step.is_run() {
                     info!("Step {} is skipped", &step.name);
+                    progress.emit(ProgressEvent::StepSkipped { step_name: step.name.clone() });
                     continue;
                 }
 
@@ -72,11 +136,17 @@ impl Runner for Job {
                         info!("{}", success_message);
                     }
                     Err(error_message) => {
-                        if throw_tolerant {
-                            error!("Error occurred in step {} but it is throw tolerant", &step_name);
-                            panic!("{}", error_message);
-                        } else {
+                        if !throw_tolerant {
                             error!("{}", error_message);
+                            return JobStatus {
+                                name: self.name.clone(),
+                                status: Err(format!("Job {} failed", self.name)),
+                                end_time: Some(now_time()),
+                                start_time: Some(start_time),
+                                steps_status: steps_status_vec,
+                            };
+                        } else {
+                            error!("Error occurred in step {} but it is throw tolerant: {}", &step_name, error_message);
                         }
                     }
                 }
@@ -99,10 +169,11 @@ impl Runner for Job {
 
             for step in steps {
                 let threads = Arc::clone(&threads);
+                let span = TraceSpan::current();
                 {
                     let mut threads = threads.lock().unwrap();
                     threads.push(spawn(move || {
-                        step.run()
+                        span.in_scope(|| step.run())
                     }));
                 }
                 {
@@ -145,5 +216,115 @@ impl Runner for Job {
     }
 }
 
+/// Runs the steps of a job following the transitions of its flow.
+fn run_flow(name: String, flow: Flow, steps: Vec<SyncStep>, progress: &ProgressEvents, start_time: u128) -> JobStatus {
+    let mut steps: HashMap<String, SyncStep> = steps.into_iter()
+        .map(|step| (step.name.clone(), step))
+        .collect();
+    let mut steps_status_vec: Vec<StepStatus> = Vec::new();
+    let mut current = flow.start().to_string();
+
+    let status = loop {
+        let Some(step) = steps.remove(&current) else {
+            let message = format!("Job {} failed, step {} has already been executed", name, current);
+            error!("{}", message);
+            break Err(message);
+        };
+        let throw_tolerant = step.throw_tolerant.unwrap_or(false);
+
+        let exit_status = if !step.is_run() {
+            info!("Step {} is skipped", &step.name);
+            progress.emit(ProgressEvent::StepSkipped { step_name: step.name.clone() });
+            String::from(SKIPPED)
+        } else {
+            info!("Running step {}", &step.name);
+            let step_result = step.run();
+            match &step_result.status {
+                Ok(success_message) => info!("{}", success_message),
+                Err(error_message) => error!("{}", error_message),
+            }
+            let exit_status = step_result.exit_status.clone();
+            steps_status_vec.push(step_result);
+            exit_status
+        };
+
+        match flow.resolve(&name, &current, &exit_status, throw_tolerant) {
+            FlowOutcome::Next(next) => current = next,
+            FlowOutcome::Finished(status) => break status,
+        }
+    };
+
+    JobStatus {
+        name,
+        start_time: Some(start_time),
+        end_time: Some(now_time()),
+        status,
+        steps_status: steps_status_vec,
+    }
+}
+
+/// Runs the steps of a job as soon as the steps they depend on complete, up to `max_threads` at once.
+fn run_dag(name: String, steps: Vec<SyncStep>, max_threads: usize, progress: &ProgressEvents, start_time: u128) -> JobStatus {
+    let names: Vec<String> = steps.iter().map(|step| step.name.clone()).collect();
+    let dependencies: Vec<(&str, &[String])> = steps.iter()
+        .map(|step| (step.name.as_str(), step.depends_on.as_slice()))
+        .collect();
+    let mut scheduler = DagScheduler::new(&dependencies);
+    let mut steps: Vec<Option<SyncStep>> = steps.into_iter().map(Some).collect();
+    let (sender, receiver) = mpsc::channel::<(usize, bool, StepStatus)>();
+    let mut steps_status_vec: Vec<StepStatus> = Vec::new();
+    let mut running = 0;
+    let mut failed = false;
+
+    loop {
+        while running < max_threads {
+            let Some(index) = scheduler.next_ready() else {
+                break;
+            };
+            let step = steps[index].take().unwrap();
+            if !step.is_run() {
+                info!("Step {} is skipped", &step.name);
+                progress.emit(ProgressEvent::StepSkipped { step_name: step.name.clone() });
+                scheduler.complete(index, true);
+                continue;
+            }
+            let throw_tolerant = step.throw_tolerant.unwrap_or(false);
+            let sender = sender.clone();
+            let span = TraceSpan::current();
+            running += 1;
+            spawn(move || {
+                let step_result = span.in_scope(|| step.run());
+                sender.send((index, throw_tolerant, step_result)).unwrap();
+            });
+        }
+
+        if running == 0 {
+            break;
+        }
+
+        let (index, throw_tolerant, step_result) = receiver.recv().unwrap();
+        running -= 1;
+        match &step_result.status {
+            Ok(success_message) => info!("{}", success_message),
+            Err(error_message) => error!("{}", error_message),
+        }
+        let succeeded = step_result.status.is_ok() || throw_tolerant;
+        failed |= !succeeded;
+        for pruned in scheduler.complete(index, succeeded) {
+            info!("Step {} is skipped, a step it depends on failed", names[pruned]);
+            progress.emit(ProgressEvent::StepSkipped { step_name: names[pruned].clone() });
+        }
+        steps_status_vec.push(step_result);
+    }
+
+    JobStatus {
+        status: if failed { Err(format!("Job {} failed", name)) } else { Ok(format!("Job {} completed", name)) },
+        name,
+        start_time: Some(start_time),
+        end_time: Some(now_time()),
+        steps_status: steps_status_vec,
+    }
+}
+
 // Allows `Job` to be sent between threads safely.
 unsafe impl Send for Job {}
\ No newline at end of file
@@ -0,0 +1,44 @@
+use crate::core::job::{JobContext, JobStatus};
+use crate::core::step::{StepExecution, StepStatus};
+
+/// A trait for listening to the execution of a synchronous job.
+///
+/// Before-hooks run in registration order and after-hooks in reverse order, so that a listener
+/// registered first wraps the others.
+pub trait JobExecutionListener: Send + Sync {
+    /// Called before the first step of the job runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The name, steps and start time of the job.
+    fn before_job(&self, _context: &JobContext) {}
+
+    /// Called once the job has finished, with a status it may modify.
+    ///
+    /// # Arguments
+    ///
+    /// * `job_status` - The status of the job.
+    fn after_job(&self, _job_status: &mut JobStatus) {}
+}
+
+/// A trait for listening to the execution of a synchronous step.
+///
+/// Before-hooks run in registration order and after-hooks in reverse order. A step skipped by
+/// its decider is not reported.
+pub trait StepExecutionListener: Send + Sync {
+    /// Called before the step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step.
+    /// * `execution` - The data collected while the step is running.
+    fn before_step(&self, _step_name: &str, _execution: &StepExecution) {}
+
+    /// Called once the step has finished, with a status it may modify, such as its exit status.
+    ///
+    /// # Arguments
+    ///
+    /// * `execution` - The data collected while the step was running.
+    /// * `step_status` - The status of the step.
+    fn after_step(&self, _execution: &StepExecution, _step_status: &mut StepStatus) {}
+}
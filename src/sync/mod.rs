@@ -1,2 +1,4 @@
 pub mod job;
-pub mod step;
\ No newline at end of file
+pub mod step;
+pub mod listener;
+pub mod item;
\ No newline at end of file
@@ -1,5 +1,24 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use log::error;
+use crate::core::chunk::{AdaptiveChunkSize, ChunkSizer};
+use crate::core::listener::{panic_message, ChunkListener, ChunkListeners, ItemListener};
+use crate::core::recorder::StepMetrics;
+use crate::core::trace::TraceSpan;
 use crate::sync::step::{DeciderCallback, SyncStep};
 use crate::sync::step::step_builder::StepBuilderTrait;
+use crate::sync::item::{ItemProcessor, ItemReader, ItemWriter};
+use crate::sync::listener::StepExecutionListener;
+
+/// Alias for a function that measures the weight of an output item.
+type WeigherCallback<O> = Box<dyn Fn(&O) -> usize + Send>;
+/// Alias for a function creating the reader on the thread running the step.
+type ReaderFactory<I> = Box<dyn FnOnce() -> Box<dyn ItemReader<I>> + Send>;
+/// Alias for a function creating the processor on the thread running the step.
+type ProcessorFactory<I, O> = Box<dyn FnOnce() -> Box<dyn ItemProcessor<I, O>> + Send>;
+/// Alias for a function creating the writer on the thread running the step.
+type WriterFactory<O> = Box<dyn FnOnce() -> Box<dyn ItemWriter<O>> + Send>;
 
 /// A trait for building complex synchronous steps.
 pub trait ComplexStepBuilderTrait<I: Sized, O: Sized> {
@@ -36,6 +55,42 @@ pub trait ComplexStepBuilderTrait<I: Sized, O: Sized> {
     /// Returns a modified builder instance.
     fn writer(self, writer: Box<dyn Fn() -> Box<dyn Fn(&Vec<O>) -> ()> + Send>) -> Self;
 
+    /// Sets the reader of the step, opened before the first item is read and closed once the
+    /// step stops.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The item reader.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn item_reader(self, reader: Box<dyn ItemReader<I> + Send>) -> Self;
+
+    /// Sets the processor of the step, opened before the first item is processed and closed once
+    /// the step stops.
+    ///
+    /// # Arguments
+    ///
+    /// * `processor` - The item processor.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn item_processor(self, processor: Box<dyn ItemProcessor<I, O> + Send>) -> Self;
+
+    /// Sets the writer of the step, opened before the first chunk is written and closed once the
+    /// step stops.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The item writer.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn item_writer(self, writer: Box<dyn ItemWriter<O> + Send>) -> Self;
+
     /// Sets the chunk size for processing data in chunks.
     ///
     /// # Arguments
@@ -46,6 +101,83 @@ pub trait ComplexStepBuilderTrait<I: Sized, O: Sized> {
     ///
     /// Returns a modified builder instance.
     fn chunk_size(self, chunk_size: usize) -> Self;
+
+    /// Sets the maximum time a partial chunk may wait before being written.
+    ///
+    /// The reader is a blocking call, so the elapsed time is only checked once an item has been
+    /// read and processed. A partial chunk waiting on a slow or blocked reader is not written when
+    /// the interval expires, but with the next item, or once the reader is exhausted.
+    ///
+    /// # Arguments
+    ///
+    /// * `flush_interval` - The maximum time between the first item of a chunk and its write.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn flush_interval(self, flush_interval: Duration) -> Self;
+
+    /// Sets a weigher that closes a chunk once the accumulated weight of its items reaches a limit.
+    ///
+    /// The chunk size still applies, so a chunk is written as soon as either limit is reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `weigher` - The function returning the weight of an output item (e.g. its size in bytes).
+    /// * `max_chunk_weight` - The accumulated weight at which a chunk is written.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn weigher(self, weigher: WeigherCallback<O>, max_chunk_weight: usize) -> Self;
+
+    /// Lets the chunk size adapt between the configured bounds to the observed writer latency.
+    ///
+    /// The step starts from the configured chunk size (clamped to the bounds) and the sizes it
+    /// adopts are reported in `StepStatus::chunk_sizes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `adaptive_chunk_size` - The bounds and target latency of the chunk size.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn adaptive_chunk_size(self, adaptive_chunk_size: AdaptiveChunkSize) -> Self;
+
+    /// Registers a listener notified of the start and end of each chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The chunk listener.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn chunk_listener(self, listener: Arc<dyn ChunkListener>) -> Self;
+
+    /// Registers a listener notified around the reading, processing and writing of the items.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The item listener.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn item_listener(self, listener: Arc<dyn ItemListener<I, O>>) -> Self;
+
+    /// Sets the number of items the reader is expected to yield, reported in the progress events
+    /// so that subscribers can compute a percentage and an ETA.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_total` - The expected number of items.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn expected_total(self, expected_total: usize) -> Self;
 }
 
 /// The default chunk size for processing data in chunks.
@@ -54,21 +186,45 @@ const DEFAULT_CHUNK_SIZE: usize = 1000;
 impl<I: Sized + 'static, O: Sized + 'static> ComplexStepBuilderTrait<I, O> for ComplexStepBuilder<I, O> {
     fn reader(self, reader: Box<dyn Fn() -> Box<dyn Iterator<Item=I>> + Send>) -> Self {
         ComplexStepBuilder {
-            reader: Some(reader),
+            reader: Some(Box::new(move || Box::new(reader()))),
             ..self
         }
     }
 
     fn processor(self, processor: Box<dyn Fn() -> Box<dyn Fn(I) -> O> + Send>) -> Self {
         ComplexStepBuilder {
-            processor: Some(processor),
+            processor: Some(Box::new(move || Box::new(processor()))),
             ..self
         }
     }
 
     fn writer(self, writer: Box<dyn Fn() -> Box<dyn Fn(&Vec<O>) -> ()> + Send>) -> Self {
         ComplexStepBuilder {
-            writer: Some(writer),
+            writer: Some(Box::new(move || {
+                let writer = writer();
+                Box::new(move |items: Vec<O>| writer(&items))
+            })),
+            ..self
+        }
+    }
+
+    fn item_reader(self, reader: Box<dyn ItemReader<I> + Send>) -> Self {
+        ComplexStepBuilder {
+            reader: Some(Box::new(move || reader)),
+            ..self
+        }
+    }
+
+    fn item_processor(self, processor: Box<dyn ItemProcessor<I, O> + Send>) -> Self {
+        ComplexStepBuilder {
+            processor: Some(Box::new(move || processor)),
+            ..self
+        }
+    }
+
+    fn item_writer(self, writer: Box<dyn ItemWriter<O> + Send>) -> Self {
+        ComplexStepBuilder {
+            writer: Some(Box::new(move || writer)),
             ..self
         }
     }
@@ -79,18 +235,64 @@ impl<I: Sized + 'static, O: Sized + 'static> ComplexStepBuilderTrait<I, O> for C
             ..self
         }
     }
+
+    fn flush_interval(self, flush_interval: Duration) -> Self {
+        ComplexStepBuilder {
+            flush_interval: Some(flush_interval),
+            ..self
+        }
+    }
+
+    fn weigher(self, weigher: WeigherCallback<O>, max_chunk_weight: usize) -> Self {
+        ComplexStepBuilder {
+            weigher: Some((weigher, max_chunk_weight)),
+            ..self
+        }
+    }
+
+    fn adaptive_chunk_size(self, adaptive_chunk_size: AdaptiveChunkSize) -> Self {
+        ComplexStepBuilder {
+            adaptive_chunk_size: Some(adaptive_chunk_size),
+            ..self
+        }
+    }
+
+    fn chunk_listener(mut self, listener: Arc<dyn ChunkListener>) -> Self {
+        self.chunk_listeners.push(listener);
+        self
+    }
+
+    fn item_listener(mut self, listener: Arc<dyn ItemListener<I, O>>) -> Self {
+        self.item_listeners.push(listener);
+        self
+    }
+
+    fn expected_total(self, expected_total: usize) -> Self {
+        self.step.execution.set_expected_total(expected_total);
+        self
+    }
 }
 
 /// A builder struct for constructing complex synchronous steps.
 pub struct ComplexStepBuilder<I: Sized, O: Sized> {
-    /// The reader function for the step.
-    reader: Option<Box<dyn Fn() -> Box<dyn Iterator<Item=I>> + Send>>,
-    /// The processor function for the step.
-    processor: Option<Box<dyn Fn() -> Box<dyn Fn(I) -> O> + Send>>,
-    /// The writer function for the step.
-    writer: Option<Box<dyn Fn() -> Box<dyn Fn(&Vec<O>) -> ()> + Send>>,
+    /// The function creating the reader of the step.
+    reader: Option<ReaderFactory<I>>,
+    /// The function creating the processor of the step.
+    processor: Option<ProcessorFactory<I, O>>,
+    /// The function creating the writer of the step.
+    writer: Option<WriterFactory<O>>,
     /// The chunk size for processing data in chunks.
     chunk_size: Option<usize>,
+    /// The maximum time a partial chunk waits before being written.
+    flush_interval: Option<Duration>,
+    /// The weigher function and the maximum accumulated weight of a chunk.
+    weigher: Option<(WeigherCallback<O>, usize)>,
+    /// The bounds and target latency of an adaptive chunk size.
+    adaptive_chunk_size: Option<AdaptiveChunkSize>,
+    /// The listeners notified of the start and end of each chunk.
+    chunk_listeners: Vec<Arc<dyn ChunkListener>>,
+    /// The listeners notified of each phase of the items.
+    item_listeners: Vec<Arc<dyn ItemListener<I, O>>>,
     /// The synchronous step being constructed.
     step: SyncStep,
 }
@@ -117,6 +319,10 @@ impl<I: Sized + 'static, O: Sized + 'static> StepBuilderTrait for ComplexStepBui
 
     /// Configures the step to be tolerant to thrown exceptions.
     ///
+    /// Like an asynchronous step, a tolerant step skips a chunk that fails to be written, counts
+    /// its items as skipped and carries on with the next chunks. The restart position of the
+    /// reader is no longer advanced past the skipped chunk.
+    ///
     /// # Returns `Self`
     ///
     /// Returns a modified builder instance.
@@ -130,6 +336,34 @@ impl<I: Sized + 'static, O: Sized + 'static> StepBuilderTrait for ComplexStepBui
         }
     }
 
+    /// Declares a step that must complete before this step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step this step depends on.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn depends_on(mut self, step_name: String) -> Self {
+        self.step.depends_on.push(step_name);
+        self
+    }
+
+    /// Registers a listener notified before and after the step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn listener(mut self, listener: Arc<dyn StepExecutionListener>) -> Self {
+        self.step.listeners.push(listener);
+        self
+    }
+
     /// Initializes a new builder instance with the given name.
     ///
     /// # Arguments
@@ -146,6 +380,11 @@ impl<I: Sized + 'static, O: Sized + 'static> StepBuilderTrait for ComplexStepBui
             processor: None,
             writer: None,
             chunk_size: None,
+            flush_interval: None,
+            weigher: None,
+            adaptive_chunk_size: None,
+            chunk_listeners: Vec::new(),
+            item_listeners: Vec::new(),
             step: SyncStep {
                 name,
                 callback: None,
@@ -153,6 +392,9 @@ impl<I: Sized + 'static, O: Sized + 'static> StepBuilderTrait for ComplexStepBui
                 end_time: None,
                 start_time: None,
                 throw_tolerant: None,
+                execution: Arc::default(),
+                depends_on: Vec::new(),
+                listeners: Vec::new(),
             },
         }
     }
@@ -179,6 +421,10 @@ impl<I: Sized + 'static, O: Sized + 'static> StepBuilderTrait for ComplexStepBui
             panic!("Writer is required");
         }
 
+        if let Some(adaptive_chunk_size) = &self.adaptive_chunk_size {
+            adaptive_chunk_size.validate();
+        }
+
         return self;
     }
 
@@ -189,25 +435,142 @@ impl<I: Sized + 'static, O: Sized + 'static> StepBuilderTrait for ComplexStepBui
     /// Returns the configured synchronous step.
     fn build(self) -> SyncStep {
         let mut current_self = self.validate();
+        let throw_tolerant = current_self.step.throw_tolerant.unwrap_or(false);
+        let step_name = current_self.step.name.clone();
+        let execution = Arc::clone(&current_self.step.execution);
 
         current_self.step.callback = Some(Box::new(move || {
-            let reader = current_self.reader.unwrap();
-            let processor = current_self.processor.unwrap().as_mut()();
-            let writer = current_self.writer.unwrap().as_mut()();
+            let mut reader = current_self.reader.unwrap()();
+            let mut processor = current_self.processor.unwrap()();
+            let mut writer = current_self.writer.unwrap()();
             let chunk_size = current_self.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
-            let mut vec = Vec::with_capacity(chunk_size);
+            let flush_interval = current_self.flush_interval;
+            let weigher = current_self.weigher;
+            let metrics = StepMetrics::new(execution.job_name(), &step_name);
+            let sizer = current_self.adaptive_chunk_size
+                .map(|config| ChunkSizer::new(config, chunk_size, Arc::clone(&execution)));
+            let listeners = ChunkListeners::new(current_self.chunk_listeners, current_self.item_listeners);
+
+            reader.open();
+            processor.open();
+            writer.open();
 
-            for chunk in reader() {
-                vec.push(processor(chunk));
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut vec = Vec::with_capacity(chunk_size);
+                let mut chunk_start: Option<Instant> = None;
+                let mut chunk_weight: usize = 0;
+                let mut chunk: Option<(usize, TraceSpan)> = None;
+                // Every item read is skipped or in the chunk being filled, so a written chunk holds
+                // all of them that are not skipped.
+                let mut read_count: u64 = 0;
+                // Whether a chunk of a tolerant step failed to be written, after which the reader
+                // is no longer checkpointed.
+                let mut failed_write = false;
+                // A failed write is observed by the chunk sizer and the listeners, then fails the
+                // step like any other panic, unless the step is throw tolerant and skips the chunk.
+                let mut write = |vec: Vec<O>, (chunk_index, span): (usize, TraceSpan)| -> bool {
+                    let count = writer.item_count(&vec);
+                    span.record_items(count);
+                    let write_result = span.in_scope(|| {
+                        listeners.before_write(&vec);
+                        let write_start = Instant::now();
+                        let write_result = panic::catch_unwind(AssertUnwindSafe(|| writer.write(vec)));
+                        if let Some(sizer) = &sizer {
+                            sizer.observe(write_start.elapsed(), write_result.is_err());
+                        }
+                        metrics.chunk_written(write_start.elapsed());
+                        if let Err(cause) = &write_result {
+                            listeners.on_write_error(chunk_index, &panic_message(cause.as_ref()));
+                        }
+                        write_result
+                    });
+                    span.finish(write_result.is_ok());
+                    if let Err(cause) = write_result {
+                        if !throw_tolerant {
+                            panic::resume_unwind(cause);
+                        }
+                        error!("step {}: Error to writing data", step_name);
+                        metrics.items_skipped(count);
+                        execution.items_skipped(&step_name, count, &panic_message(cause.as_ref()));
+                        return false;
+                    }
+                    listeners.after_write(chunk_index, count);
+                    metrics.items_written(count);
+                    execution.chunk_written(&step_name, chunk_index, count);
+                    true
+                };
 
-                if vec.len() == chunk_size {
-                    writer(&vec);
-                    vec.clear();
+                loop {
+                    listeners.before_read();
+                    let item = match panic::catch_unwind(AssertUnwindSafe(|| reader.read())) {
+                        Ok(Some(item)) => item,
+                        Ok(None) => break,
+                        Err(cause) => {
+                            listeners.on_read_error(&panic_message(cause.as_ref()));
+                            panic::resume_unwind(cause);
+                        }
+                    };
+                    listeners.after_read(&item);
+                    metrics.items_read(1);
+                    read_count += 1;
+                    let (current_chunk, chunk_span) = chunk.get_or_insert_with(|| {
+                        let chunk_index = listeners.open_chunk();
+                        (chunk_index, TraceSpan::chunk(&step_name, chunk_index))
+                    }).clone();
+                    listeners.before_process(&item);
+                    let output = match chunk_span.in_scope(|| panic::catch_unwind(AssertUnwindSafe(|| processor.process_or_skip(item)))) {
+                        Ok(Ok(output)) => output,
+                        Ok(Err(reason)) => {
+                            listeners.on_skip(&reason);
+                            metrics.items_skipped(1);
+                            execution.items_skipped(&step_name, 1, &reason);
+                            continue;
+                        }
+                        Err(cause) => {
+                            listeners.on_process_error(current_chunk, &panic_message(cause.as_ref()));
+                            panic::resume_unwind(cause);
+                        }
+                    };
+                    listeners.after_process(&output);
+                    if let Some((weigher, _)) = &weigher {
+                        chunk_weight += weigher(&output);
+                    }
+                    vec.push(output);
+                    let started = *chunk_start.get_or_insert_with(Instant::now);
+                    let expired = flush_interval.is_some_and(|interval| started.elapsed() >= interval);
+                    let is_heavy = weigher.as_ref().is_some_and(|(_, max_chunk_weight)| chunk_weight >= *max_chunk_weight);
+                    let chunk_size = sizer.as_ref().map_or(chunk_size, ChunkSizer::current);
+
+                    if vec.len() >= chunk_size || expired || is_heavy {
+                        let full_chunk = std::mem::replace(&mut vec, Vec::with_capacity(chunk_size));
+                        failed_write |= !write(full_chunk, chunk.take().unwrap());
+                        if !failed_write {
+                            reader.checkpoint(read_count);
+                        }
+                        chunk_start = None;
+                        chunk_weight = 0;
+                    }
                 }
-            }
 
-            if !vec.is_empty() {
-                writer(&vec);
+                if let Some((chunk_index, chunk_span)) = chunk.take() {
+                    if vec.is_empty() {
+                        listeners.after_chunk(chunk_index);
+                        chunk_span.finish(true);
+                    } else {
+                        failed_write |= !write(vec, (chunk_index, chunk_span));
+                    }
+                }
+                if !failed_write {
+                    reader.checkpoint(read_count);
+                }
+            }));
+
+            reader.close();
+            processor.close();
+            writer.close();
+
+            if let Err(cause) = outcome {
+                panic::resume_unwind(cause);
             }
         }));
 
@@ -0,0 +1,190 @@
+use std::sync::Arc;
+use crate::sync::job::Job;
+use crate::sync::step::{DeciderCallback, Runner, SyncStep};
+use crate::sync::step::step_builder::StepBuilderTrait;
+use crate::sync::listener::StepExecutionListener;
+
+/// A trait for building synchronous steps that run a whole job.
+pub trait JobStepBuilderTrait {
+    /// Sets the job run by the step.
+    ///
+    /// The status of the job is reported in `StepStatus::job_status`, and the step fails when the
+    /// job fails, so the failure is handled by the error policy of the parent job.
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The job to run.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn job(self, job: Job) -> Self;
+}
+
+/// A builder struct for constructing synchronous steps that run a whole job.
+pub struct JobStepBuilder {
+    /// The job run by the step.
+    job: Option<Job>,
+    /// The step being constructed.
+    step: SyncStep,
+}
+
+impl StepBuilderTrait for JobStepBuilder {
+    /// Sets the decider callback for the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `decider` - The decider callback function.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn decider(self, decider: DeciderCallback) -> Self {
+        JobStepBuilder {
+            step: SyncStep {
+                decider: Some(decider),
+                ..self.step
+            },
+            ..self
+        }
+    }
+
+    /// Configures the step to be tolerant to thrown exceptions.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn throw_tolerant(self) -> Self {
+        JobStepBuilder {
+            step: SyncStep {
+                throw_tolerant: Some(true),
+                ..self.step
+            },
+            ..self
+        }
+    }
+
+    /// Declares a step that must complete before this step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step this step depends on.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn depends_on(mut self, step_name: String) -> Self {
+        self.step.depends_on.push(step_name);
+        self
+    }
+
+    /// Registers a listener notified before and after the step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn listener(mut self, listener: Arc<dyn StepExecutionListener>) -> Self {
+        self.step.listeners.push(listener);
+        self
+    }
+
+    /// Initializes a new builder instance with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the step.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    #[inline]
+    fn get(name: String) -> Self {
+        JobStepBuilder {
+            job: None,
+            step: SyncStep {
+                name,
+                callback: None,
+                decider: None,
+                end_time: None,
+                start_time: None,
+                throw_tolerant: None,
+                execution: Arc::default(),
+                depends_on: Vec::new(),
+                listeners: Vec::new(),
+            },
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    fn validate(self) -> Self {
+        if self.job.is_none() {
+            panic!("Job is required");
+        }
+
+        if self.step.name.is_empty() {
+            panic!("Name is required");
+        }
+
+        return self;
+    }
+
+    /// Builds and returns the configured synchronous step.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured synchronous step.
+    fn build(self) -> SyncStep {
+        let mut current_self = self.validate();
+        let job = current_self.job.unwrap();
+        let execution = Arc::clone(&current_self.step.execution);
+
+        current_self.step.callback = Some(Box::new(move || {
+            let job_status = job.run();
+            execution.set_job_status(job_status.clone());
+            if let Err(message) = job_status.status {
+                panic!("{}", message);
+            }
+        }));
+
+        return current_self.step;
+    }
+}
+
+impl JobStepBuilderTrait for JobStepBuilder {
+    /// Sets the job run by the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The job to run.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn job(self, job: Job) -> Self {
+        JobStepBuilder {
+            job: Some(job),
+            ..self
+        }
+    }
+}
+
+/// Initializes a new job step builder with the given name.
+///
+/// # Arguments
+///
+/// * `name` - The name of the step.
+///
+/// # Returns
+///
+/// Returns a new job step builder instance.
+pub fn get(name: String) -> JobStepBuilder {
+    JobStepBuilder::get(name)
+}
@@ -1,10 +1,18 @@
+use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
 use log::info;
 use crate::core::job::now_time;
-use crate::core::step::{mount_step_status, StepStatus, throw_tolerant_exception};
+use crate::core::progress::ProgressEvent;
+use crate::core::recorder::StepMetrics;
+use crate::core::step::{mount_step_status, StepExecution, StepStatus, throw_tolerant_exception};
+use crate::core::trace::TraceSpan;
+use crate::sync::listener::StepExecutionListener;
 
 pub mod complex_step;
 pub mod simple_step;
+pub mod job_step;
+pub mod partition_step;
 pub mod step_builder;
 
 /// A trait for objects that can be executed.
@@ -45,22 +53,58 @@ pub struct SyncStep {
     pub(crate) decider: Option<DeciderCallback>,
     /// The callback function to be executed as the step.
     pub(crate) callback: Option<Box<dyn FnOnce() -> () + Send>>,
+    /// The data collected while the step is running.
+    pub(crate) execution: Arc<StepExecution>,
+    /// The names of the steps that must complete before this step runs.
+    pub depends_on: Vec<String>,
+    /// The listeners notified before and after the step runs.
+    pub(crate) listeners: Vec<Arc<dyn StepExecutionListener>>,
 }
 
 impl Runner for SyncStep {
     /// The output type of the step execution.
     type Output = StepStatus;
 
-    /// Executes the step and returns its status.
-    fn run(self) -> Self::Output {
+    /// Executes the step, notifying its listeners, and returns its status.
+    fn run(mut self) -> Self::Output {
+        let started = Instant::now();
+        let metrics = StepMetrics::new(self.execution.job_name(), &self.name);
+        let span = TraceSpan::step(&self.name);
+        let listeners = std::mem::take(&mut self.listeners);
+        let execution = Arc::clone(&self.execution);
+        execution.emit(ProgressEvent::StepStarted {
+            step_name: self.name.clone(),
+            expected_total: execution.expected_total(),
+        });
+        let step_status = span.in_scope(|| {
+            for listener in &listeners {
+                listener.before_step(&self.name, &execution);
+            }
+            let mut step_status = self.execute();
+            for listener in listeners.iter().rev() {
+                listener.after_step(&execution, &mut step_status);
+            }
+            step_status
+        });
+        span.finish(step_status.status.is_ok());
+        metrics.step_finished(started.elapsed());
+        execution.emit(step_finished_event(&step_status, started.elapsed()));
+        step_status
+    }
+}
+
+impl SyncStep {
+    /// Executes the callback of the step and returns its status.
+    fn execute(self) -> StepStatus {
         return match self.callback {
             None => {
                 throw_tolerant_exception(self.throw_tolerant.unwrap_or(false), self.name)
             }
             Some(callback) => {
                 info!("Step {} is running", self.name);
+                let span = TraceSpan::current();
                 let task = thread::spawn(move || {
-                    callback();
+                    span.in_scope(callback);
                 });
                 let task_result = task.join();
 
@@ -69,12 +113,12 @@ impl Runner for SyncStep {
                     Ok(_) => {
                         let message = format!("Step {} executed successfully", self.name);
                         info!("{}", message);
-                        mount_step_status(self.name, Ok(message), start_time)
+                        self.execution.report(mount_step_status(self.name, Ok(message), start_time))
                     }
                     Err(_) => {
                         let message = format!("Step {} failed to execute", self.name);
                         info!("{}", message);
-                        mount_step_status(self.name, Err(message), start_time)
+                        self.execution.report(mount_step_status(self.name, Err(message), start_time))
                     }
                 };
             }
@@ -82,6 +126,14 @@ impl Runner for SyncStep {
     }
 }
 
+/// Returns the progress event reporting the outcome of a step.
+pub(crate) fn step_finished_event(step_status: &StepStatus, duration: Duration) -> ProgressEvent {
+    match &step_status.status {
+        Ok(_) => ProgressEvent::StepCompleted { step_name: step_status.name.clone(), duration },
+        Err(error) => ProgressEvent::StepFailed { step_name: step_status.name.clone(), error: error.clone() },
+    }
+}
+
 impl Decider for SyncStep {
     /// Checks if the step should be executed based on the decider callback.
     fn is_run(&self) -> bool {
@@ -0,0 +1,349 @@
+use std::sync::{Arc, mpsc};
+use std::thread::spawn;
+use log::{error, info};
+use crate::core::partition::{Partition, Partitioner};
+use crate::core::progress::ProgressEvent;
+use crate::core::partition::process::ProcessPartitionHandler;
+use crate::core::trace::TraceSpan;
+use crate::sync::step::{Decider, DeciderCallback, Runner, SyncStep};
+use crate::sync::step::step_builder::StepBuilderTrait;
+use crate::sync::listener::StepExecutionListener;
+
+/// Alias for a function that builds the worker step of a partition.
+type WorkerCallback = Box<dyn Fn(Partition) -> SyncStep + Send>;
+
+/// A trait for building synchronous steps that split their work into partitions.
+pub trait PartitionStepBuilderTrait {
+    /// Sets the partitioner that splits the work of the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `partitioner` - The partitioner.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn partitioner(self, partitioner: Box<dyn Partitioner>) -> Self;
+
+    /// Sets the function building the worker step of each partition.
+    ///
+    /// Every partition runs the step returned for it, so the same reader, processor and writer
+    /// can be built with the parameters of the partition.
+    ///
+    /// # Arguments
+    ///
+    /// * `worker` - The function building the worker step of a partition.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn worker(self, worker: WorkerCallback) -> Self;
+
+    /// Sets the number of partitions requested from the partitioner. Defaults to 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid_size` - The number of partitions.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn grid_size(self, grid_size: usize) -> Self;
+
+    /// Sets the maximum number of partitions running at once. Defaults to 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `workers` - The number of threads running partitions.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn workers(self, workers: usize) -> Self;
+
+    /// Runs each partition in a child process instead of a thread of the current process.
+    ///
+    /// The child serves the partition with `process::serve_stdio`, so the worker is only needed
+    /// in the child.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler spawning the worker processes.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn process_handler(self, handler: ProcessPartitionHandler) -> Self;
+}
+
+/// A builder struct for constructing synchronous partitioned steps.
+pub struct PartitionStepBuilder {
+    /// The partitioner that splits the work of the step.
+    partitioner: Option<Box<dyn Partitioner>>,
+    /// The function building the worker step of each partition.
+    worker: Option<WorkerCallback>,
+    /// The number of partitions requested from the partitioner.
+    grid_size: Option<usize>,
+    /// The maximum number of partitions running at once.
+    workers: Option<usize>,
+    /// The handler running the partitions in child processes.
+    process_handler: Option<ProcessPartitionHandler>,
+    /// The step being constructed.
+    step: SyncStep,
+}
+
+impl StepBuilderTrait for PartitionStepBuilder {
+    /// Sets the decider callback for the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `decider` - The decider callback function.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn decider(self, decider: DeciderCallback) -> Self {
+        PartitionStepBuilder {
+            step: SyncStep {
+                decider: Some(decider),
+                ..self.step
+            },
+            ..self
+        }
+    }
+
+    /// Configures the step to be tolerant to thrown exceptions.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn throw_tolerant(self) -> Self {
+        PartitionStepBuilder {
+            step: SyncStep {
+                throw_tolerant: Some(true),
+                ..self.step
+            },
+            ..self
+        }
+    }
+
+    /// Declares a step that must complete before this step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step this step depends on.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn depends_on(mut self, step_name: String) -> Self {
+        self.step.depends_on.push(step_name);
+        self
+    }
+
+    /// Registers a listener notified before and after the step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn listener(mut self, listener: Arc<dyn StepExecutionListener>) -> Self {
+        self.step.listeners.push(listener);
+        self
+    }
+
+    /// Initializes a new builder instance with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the step.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new builder instance.
+    #[inline]
+    fn get(name: String) -> Self {
+        PartitionStepBuilder {
+            partitioner: None,
+            worker: None,
+            grid_size: None,
+            workers: None,
+            process_handler: None,
+            step: SyncStep {
+                name,
+                callback: None,
+                decider: None,
+                end_time: None,
+                start_time: None,
+                throw_tolerant: None,
+                execution: Arc::default(),
+                depends_on: Vec::new(),
+                listeners: Vec::new(),
+            },
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    fn validate(self) -> Self {
+        if self.step.name.is_empty() {
+            panic!("Name is required");
+        }
+
+        if self.partitioner.is_none() {
+            panic!("Partitioner is required");
+        }
+
+        if self.worker.is_none() && self.process_handler.is_none() {
+            panic!("Worker or process handler is required");
+        }
+
+        if self.grid_size == Some(0) || self.workers == Some(0) {
+            panic!("Grid size and workers must be greater than zero");
+        }
+
+        return self;
+    }
+
+    /// Builds and returns the configured synchronous step.
+    ///
+    /// The status of each partition is reported in `StepStatus::partitions_status`, and the step
+    /// fails when any partition fails.
+    ///
+    /// # Returns
+    ///
+    /// Returns the configured synchronous step.
+    fn build(self) -> SyncStep {
+        let mut current_self = self.validate();
+        let partitioner = current_self.partitioner.unwrap();
+        let worker = current_self.worker;
+        let process_handler = current_self.process_handler;
+        let grid_size = current_self.grid_size.unwrap_or(1);
+        let workers = current_self.workers.unwrap_or(1);
+        let step_name = current_self.step.name.clone();
+        let execution = Arc::clone(&current_self.step.execution);
+
+        current_self.step.callback = Some(Box::new(move || {
+            let mut partitions = partitioner.partition(grid_size).into_iter();
+            let (sender, receiver) = mpsc::channel();
+            let mut running = 0;
+            let mut failed = 0;
+
+            info!("Step {} is running {} partitions", step_name, partitions.len());
+
+            loop {
+                while running < workers {
+                    let Some(partition) = partitions.next() else {
+                        break;
+                    };
+                    let sender = sender.clone();
+                    let span = TraceSpan::current();
+                    if let Some(handler) = &process_handler {
+                        let handler = handler.clone();
+                        let step_name = step_name.clone();
+                        let execution = Arc::clone(&execution);
+                        running += 1;
+                        spawn(move || {
+                            let partition_status = span.in_scope(|| {
+                                handler.run_with_restarts(&step_name, &partition, |attempt, error| {
+                                    execution.emit(ProgressEvent::Retried {
+                                        step_name: partition.name.clone(),
+                                        attempt,
+                                        error: error.to_string(),
+                                    });
+                                })
+                            });
+                            sender.send(partition_status).unwrap();
+                        });
+                        continue;
+                    }
+                    let step = worker.as_ref().unwrap()(partition);
+                    step.execution.inherit(&execution);
+                    if !step.is_run() {
+                        info!("Step {} is skipped", &step.name);
+                        execution.emit(ProgressEvent::StepSkipped { step_name: step.name.clone() });
+                        continue;
+                    }
+                    running += 1;
+                    spawn(move || {
+                        sender.send(span.in_scope(|| step.run())).unwrap();
+                    });
+                }
+
+                if running == 0 {
+                    break;
+                }
+
+                let partition_status = receiver.recv().unwrap();
+                running -= 1;
+                if let Err(error_message) = &partition_status.status {
+                    error!("{}", error_message);
+                    failed += 1;
+                }
+                execution.add_partition_status(partition_status);
+            }
+
+            if failed > 0 {
+                panic!("step {}: {} partitions failed", step_name, failed);
+            }
+        }));
+
+        return current_self.step;
+    }
+}
+
+impl PartitionStepBuilderTrait for PartitionStepBuilder {
+    fn partitioner(self, partitioner: Box<dyn Partitioner>) -> Self {
+        PartitionStepBuilder {
+            partitioner: Some(partitioner),
+            ..self
+        }
+    }
+
+    fn worker(self, worker: WorkerCallback) -> Self {
+        PartitionStepBuilder {
+            worker: Some(worker),
+            ..self
+        }
+    }
+
+    fn grid_size(self, grid_size: usize) -> Self {
+        PartitionStepBuilder {
+            grid_size: Some(grid_size),
+            ..self
+        }
+    }
+
+    fn workers(self, workers: usize) -> Self {
+        PartitionStepBuilder {
+            workers: Some(workers),
+            ..self
+        }
+    }
+
+    fn process_handler(self, handler: ProcessPartitionHandler) -> Self {
+        PartitionStepBuilder {
+            process_handler: Some(handler),
+            ..self
+        }
+    }
+}
+
+/// Initializes a new partitioned step builder with the given name.
+///
+/// # Arguments
+///
+/// * `name` - The name of the step.
+///
+/// # Returns
+///
+/// Returns a new partitioned step builder instance.
+pub fn get(name: String) -> PartitionStepBuilder {
+    PartitionStepBuilder::get(name)
+}
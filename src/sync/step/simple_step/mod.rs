@@ -1,5 +1,7 @@
+use std::sync::Arc;
 use crate::sync::step::{DeciderCallback, SyncStep, StepCallback};
 use crate::sync::step::step_builder::StepBuilderTrait;
+use crate::sync::listener::StepExecutionListener;
 
 /// A trait for building simple synchronous steps.
 pub trait SimpleStepBuilderTrait<I, O> {
@@ -13,6 +15,19 @@ pub trait SimpleStepBuilderTrait<I, O> {
     ///
     /// Returns a modified builder instance.
     fn tasklet(self, step_callback: StepCallback) -> Self;
+
+    /// Configures the step with a tasklet callback returning the exit status of the step.
+    ///
+    /// The returned exit status replaces `COMPLETED` and can be matched by the transitions of a flow.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_callback` - The tasklet callback function.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn tasklet_with_exit_status(self, step_callback: Box<dyn FnOnce() -> String + Send>) -> Self;
 }
 
 /// A builder struct for constructing simple synchronous steps.
@@ -54,6 +69,34 @@ impl StepBuilderTrait for SimpleStepBuilder {
         }
     }
 
+    /// Declares a step that must complete before this step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step this step depends on.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn depends_on(mut self, step_name: String) -> Self {
+        self.step.depends_on.push(step_name);
+        self
+    }
+
+    /// Registers a listener notified before and after the step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn listener(mut self, listener: Arc<dyn StepExecutionListener>) -> Self {
+        self.step.listeners.push(listener);
+        self
+    }
+
     /// Initializes a new builder instance with the given name.
     ///
     /// # Arguments
@@ -73,6 +116,9 @@ impl StepBuilderTrait for SimpleStepBuilder {
                 end_time: None,
                 start_time: None,
                 throw_tolerant: None,
+                execution: Arc::default(),
+                depends_on: Vec::new(),
+                listeners: Vec::new(),
             }
         }
     }
@@ -123,6 +169,22 @@ impl SimpleStepBuilderTrait<fn(), fn()> for SimpleStepBuilder {
             }
         };
     }
+
+    /// Configures the step with a tasklet callback returning the exit status of the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_callback` - The tasklet callback function.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn tasklet_with_exit_status(self, step_callback: Box<dyn FnOnce() -> String + Send>) -> Self {
+        let execution = Arc::clone(&self.step.execution);
+        self.tasklet(Box::new(move || {
+            execution.set_exit_status(step_callback());
+        }))
+    }
 }
 
 /// Initializes a new simple step builder with the given name.
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+use crate::sync::listener::StepExecutionListener;
 use crate::sync::step::{DeciderCallback, SyncStep};
 
 /// A trait for building synchronous steps.
@@ -20,6 +22,28 @@ pub trait StepBuilderTrait {
     /// Returns a modified builder instance.
     fn throw_tolerant(self) -> Self;
 
+    /// Declares a step that must complete before this step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step this step depends on.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn depends_on(self, step_name: String) -> Self;
+
+    /// Registers a listener notified before and after the step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified builder instance.
+    fn listener(self, listener: Arc<dyn StepExecutionListener>) -> Self;
+
     /// Initializes a new builder instance with the given name.
     ///
     /// # Arguments
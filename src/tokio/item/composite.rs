@@ -0,0 +1,373 @@
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use log::error;
+
+use crate::core::item::CompositeMode;
+use crate::core::listener::panic_message;
+use crate::tokio::item::AsyncItemWriter;
+
+/// Returns the class of an item, which selects the writer of a classifier writer it is routed to.
+pub type AsyncClassifier<O, C> = Box<dyn Fn(&O) -> C + Send + Sync>;
+
+/// Undoes the write of a chunk by a delegate of a composite writer, when a later delegate fails
+/// to write it in all-or-nothing mode.
+pub type AsyncCompensator<O> = Box<dyn Fn(Vec<O>) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Writes each chunk with several writers asynchronously, e.g. to a database and to an audit file.
+///
+/// Every delegate gets its own copy of the chunk and is opened and closed with the composite
+/// writer. The delegates write a chunk one after the other, and how a failing delegate is handled
+/// depends on the `CompositeMode`.
+pub struct AsyncCompositeItemWriter<O> {
+    /// The writers of a chunk, in order.
+    writers: Vec<Box<dyn AsyncItemWriter<O>>>,
+    /// The compensator of each writer, if any.
+    compensators: Vec<Option<AsyncCompensator<O>>>,
+    /// How a failing writer is handled.
+    mode: CompositeMode,
+}
+
+impl<O: Clone + Send + Sync + 'static> AsyncCompositeItemWriter<O> {
+    /// Undoes the write of a chunk by the writers before the failed one, in reverse order. A
+    /// failing compensator is logged, so that the others still run.
+    async fn compensate(&self, failed: usize, items: &[O]) {
+        for (index, compensator) in self.compensators[..failed].iter().enumerate().rev() {
+            let compensator = compensator.as_ref().unwrap();
+            if let Err(cause) = AssertUnwindSafe(compensator(items.to_vec())).catch_unwind().await {
+                error!("Compensation of composite writer {} failed: {}", index, panic_message(cause.as_ref()));
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<O: Clone + Send + Sync + 'static> AsyncItemWriter<O> for AsyncCompositeItemWriter<O> {
+    async fn open(&self) {
+        for writer in &self.writers {
+            writer.open().await;
+        }
+    }
+
+    async fn write(&self, items: Vec<O>) {
+        match self.mode {
+            CompositeMode::FailFast => {
+                for writer in &self.writers {
+                    writer.write(items.clone()).await;
+                }
+            }
+            CompositeMode::AllOrNothing => {
+                for (index, writer) in self.writers.iter().enumerate() {
+                    if let Err(cause) = AssertUnwindSafe(writer.write(items.clone())).catch_unwind().await {
+                        self.compensate(index, &items).await;
+                        panic::resume_unwind(cause);
+                    }
+                }
+            }
+            CompositeMode::BestEffort => {
+                let mut failure = None;
+                let mut failures = 0;
+                for (index, writer) in self.writers.iter().enumerate() {
+                    if let Err(cause) = AssertUnwindSafe(writer.write(items.clone())).catch_unwind().await {
+                        error!("Composite writer {} failed: {}", index, panic_message(cause.as_ref()));
+                        failures += 1;
+                        failure = Some(cause);
+                    }
+                }
+
+                if failures == self.writers.len() {
+                    if let Some(cause) = failure {
+                        panic::resume_unwind(cause);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Counts the items of a chunk as the first writer does, as every writer gets the same chunk.
+    fn item_count(&self, items: &[O]) -> usize {
+        self.writers[0].item_count(items)
+    }
+
+    async fn close(&self) {
+        for writer in &self.writers {
+            writer.close().await;
+        }
+    }
+}
+
+/// A builder struct for constructing asynchronous composite item writers.
+pub struct AsyncCompositeItemWriterBuilder<O> {
+    /// The writer being constructed.
+    writer: AsyncCompositeItemWriter<O>,
+}
+
+impl<O: Clone + Send + Sync + 'static> AsyncCompositeItemWriterBuilder<O> {
+    /// Initializes a new builder without writers, in fail-fast mode.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get() -> Self {
+        AsyncCompositeItemWriterBuilder {
+            writer: AsyncCompositeItemWriter {
+                writers: Vec::new(),
+                compensators: Vec::new(),
+                mode: CompositeMode::default(),
+            }
+        }
+    }
+
+    /// Adds a writer, called after the writers added before it.
+    ///
+    /// # Parameters
+    ///
+    /// - `writer`: The writer.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn writer(mut self, writer: Box<dyn AsyncItemWriter<O>>) -> Self {
+        self.writer.writers.push(writer);
+        self.writer.compensators.push(None);
+        self
+    }
+
+    /// Adds a writer with the function undoing its write of a chunk, called in all-or-nothing mode
+    /// when a writer added after it fails.
+    ///
+    /// # Parameters
+    ///
+    /// - `writer`: The writer.
+    /// - `compensator`: The function undoing the write of a chunk.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn compensated_writer(mut self, writer: Box<dyn AsyncItemWriter<O>>, compensator: AsyncCompensator<O>) -> Self {
+        self.writer.writers.push(writer);
+        self.writer.compensators.push(Some(compensator));
+        self
+    }
+
+    /// Sets how a failing writer is handled.
+    ///
+    /// # Parameters
+    ///
+    /// - `mode`: The composite mode.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn mode(self, mode: CompositeMode) -> Self {
+        AsyncCompositeItemWriterBuilder {
+            writer: AsyncCompositeItemWriter {
+                mode,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.writer.writers.is_empty() {
+            panic!("At least one writer is required");
+        }
+
+        let compensated = &self.writer.compensators[..self.writer.compensators.len() - 1];
+        if self.writer.mode == CompositeMode::AllOrNothing && compensated.iter().any(Option::is_none) {
+            panic!("Every writer but the last requires a compensator in all-or-nothing mode");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// The configured writer.
+    pub fn build(self) -> AsyncCompositeItemWriter<O> {
+        self.validate().writer
+    }
+}
+
+/// Splits each chunk by the class of its items and writes each part asynchronously with the writer
+/// of its class, e.g. to route the records of a file to a table per record type.
+///
+/// The items keep their order within a part. The parts are written one after the other, in the
+/// order the routes were added, then the part of the default writer. A chunk with an item of a
+/// class without a route fails before any part is written, unless a default writer is set.
+pub struct AsyncClassifierItemWriter<O, C> {
+    /// Returns the class of an item.
+    classifier: AsyncClassifier<O, C>,
+    /// The writer of each class.
+    routes: Vec<(C, Box<dyn AsyncItemWriter<O>>)>,
+    /// The writer of the items of a class without a route.
+    default: Option<Box<dyn AsyncItemWriter<O>>>,
+}
+
+impl<O, C: PartialEq> AsyncClassifierItemWriter<O, C> {
+    /// Returns the writer an item is routed to, or `None` when its class has no route.
+    fn writer_of(&self, item: &O) -> Option<&dyn AsyncItemWriter<O>> {
+        let class = (self.classifier)(item);
+        match self.routes.iter().find(|(route, _)| *route == class) {
+            Some((_, writer)) => Some(writer.as_ref()),
+            None => self.default.as_deref(),
+        }
+    }
+}
+
+#[async_trait]
+impl<O, C> AsyncItemWriter<O> for AsyncClassifierItemWriter<O, C>
+where
+    O: Send + 'static,
+    C: PartialEq + Debug + Send + Sync,
+{
+    async fn open(&self) {
+        for (_, writer) in &self.routes {
+            writer.open().await;
+        }
+        if let Some(writer) = &self.default {
+            writer.open().await;
+        }
+    }
+
+    async fn write(&self, items: Vec<O>) {
+        let mut parts: Vec<Vec<O>> = (0..=self.routes.len()).map(|_| Vec::new()).collect();
+        for item in items {
+            let class = (self.classifier)(&item);
+            let index = match self.routes.iter().position(|(route, _)| *route == class) {
+                Some(index) => index,
+                None if self.default.is_some() => self.routes.len(),
+                None => panic!("No writer for class {:?}", class),
+            };
+            parts[index].push(item);
+        }
+
+        let default = parts.pop().unwrap();
+        for ((_, writer), part) in self.routes.iter().zip(parts) {
+            if !part.is_empty() {
+                writer.write(part).await;
+            }
+        }
+        if let Some(writer) = &self.default {
+            if !default.is_empty() {
+                writer.write(default).await;
+            }
+        }
+    }
+
+    /// Counts each item as the writer it is routed to does.
+    fn item_count(&self, items: &[O]) -> usize {
+        items.iter()
+            .map(|item| self.writer_of(item).map_or(1, |writer| writer.item_count(std::slice::from_ref(item))))
+            .sum()
+    }
+
+    async fn close(&self) {
+        for (_, writer) in &self.routes {
+            writer.close().await;
+        }
+        if let Some(writer) = &self.default {
+            writer.close().await;
+        }
+    }
+}
+
+/// A builder struct for constructing asynchronous classifier item writers.
+pub struct AsyncClassifierItemWriterBuilder<O, C> {
+    /// The writer being constructed.
+    writer: AsyncClassifierItemWriter<O, C>,
+}
+
+impl<O, C> AsyncClassifierItemWriterBuilder<O, C>
+where
+    O: Send + 'static,
+    C: PartialEq + Debug + Send + Sync,
+{
+    /// Initializes a new builder classifying the items with the given function.
+    ///
+    /// # Parameters
+    ///
+    /// - `classifier`: The function returning the class of an item.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(classifier: AsyncClassifier<O, C>) -> Self {
+        AsyncClassifierItemWriterBuilder {
+            writer: AsyncClassifierItemWriter {
+                classifier,
+                routes: Vec::new(),
+                default: None,
+            }
+        }
+    }
+
+    /// Routes the items of a class to a writer.
+    ///
+    /// # Parameters
+    ///
+    /// - `class`: The class.
+    /// - `writer`: The writer of the items of the class.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn route(mut self, class: C, writer: Box<dyn AsyncItemWriter<O>>) -> Self {
+        if self.writer.routes.iter().any(|(route, _)| *route == class) {
+            panic!("Class {:?} is already routed", class);
+        }
+
+        self.writer.routes.push((class, writer));
+        self
+    }
+
+    /// Sets the writer of the items of a class without a route.
+    ///
+    /// # Parameters
+    ///
+    /// - `writer`: The default writer.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn default_writer(self, writer: Box<dyn AsyncItemWriter<O>>) -> Self {
+        AsyncClassifierItemWriterBuilder {
+            writer: AsyncClassifierItemWriter {
+                default: Some(writer),
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.writer.routes.is_empty() && self.writer.default.is_none() {
+            panic!("At least one writer is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// The configured writer.
+    pub fn build(self) -> AsyncClassifierItemWriter<O, C> {
+        self.validate().writer
+    }
+}
@@ -0,0 +1,485 @@
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use csv_async::{AsyncReader, AsyncReaderBuilder, AsyncSerializer, AsyncWriterBuilder, StringRecord};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::core::compression::{open_async_input, AsyncOutputFile, Compression};
+use crate::core::item::Positioned;
+use crate::tokio::item::{AsyncItemReader, AsyncItemWriter};
+
+/// Reads the rows of a CSV file asynchronously, deserializing each of them into `T`.
+///
+/// The file is opened when the step opens the reader. With headers, the columns are mapped to the
+/// fields of `T` by name, otherwise by position. A row that cannot be read or deserialized panics
+/// with its line number, which fails the step. The reader also accepts the gzip and zstd files
+/// described by [`Compression`].
+///
+/// Besides `T`, the reader yields `Positioned<T>` items carrying the line of each row.
+pub struct AsyncCsvItemReader<T> {
+    /// The path of the file to read.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The entry to read from a zip archive, or `None` for its first file.
+    zip_entry: Option<String>,
+    /// The field delimiter.
+    delimiter: u8,
+    /// The quote character.
+    quote: u8,
+    /// Whether the first row holds the column names.
+    has_headers: bool,
+    /// The number of lines skipped before the first row, e.g. a title or a comment.
+    skip_lines: usize,
+    /// The open reader.
+    reader: Option<AsyncReader<Box<dyn AsyncBufRead + Send + Unpin>>>,
+    /// The column names, when the file has headers.
+    headers: Option<StringRecord>,
+    /// The last row read.
+    record: StringRecord,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned + Send> AsyncCsvItemReader<T> {
+    /// Returns the line the last row read starts at, or zero before the first row.
+    pub fn line(&self) -> u64 {
+        match self.record.position() {
+            Some(position) => position.line() + self.skip_lines as u64,
+            None => 0,
+        }
+    }
+
+    /// Opens the file and reads the headers.
+    async fn open_file(&mut self) {
+        let mut file = open_async_input(&self.path, self.compression, self.zip_entry.as_deref()).await
+            .unwrap_or_else(|err| panic!("Error opening CSV file {}: {}", self.path.display(), err));
+
+        let mut line = String::new();
+        for _ in 0..self.skip_lines {
+            line.clear();
+            file.read_line(&mut line).await
+                .unwrap_or_else(|err| panic!("Error reading CSV file {}: {}", self.path.display(), err));
+        }
+
+        let mut reader = AsyncReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .has_headers(self.has_headers)
+            .create_reader(file);
+
+        if self.has_headers {
+            let headers = reader.headers().await
+                .unwrap_or_else(|err| panic!("Error reading the headers of CSV file {}: {}", self.path.display(), err));
+            self.headers = Some(headers.clone());
+        }
+
+        self.reader = Some(reader);
+    }
+
+    /// Reads and deserializes the next row.
+    async fn read_positioned(&mut self) -> Option<Positioned<T>> {
+        let reader = self.reader.as_mut().expect("CSV reader is not open");
+
+        match reader.read_record(&mut self.record).await {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(err) => panic!("Error reading CSV file {} after line {}: {}", self.path.display(), self.line(), err),
+        }
+
+        let line = self.line();
+
+        match self.record.deserialize(self.headers.as_ref()) {
+            Ok(item) => Some(Positioned { line, item }),
+            Err(err) => panic!("Error deserializing line {} of CSV file {}: {}", line, self.path.display(), err),
+        }
+    }
+}
+
+#[async_trait]
+impl<T: DeserializeOwned + Send> AsyncItemReader<Positioned<T>> for AsyncCsvItemReader<T> {
+    async fn open(&mut self) {
+        self.open_file().await;
+    }
+
+    async fn read(&mut self) -> Option<Positioned<T>> {
+        self.read_positioned().await
+    }
+
+    async fn close(&mut self) {
+        self.reader = None;
+    }
+}
+
+#[async_trait]
+impl<T: DeserializeOwned + Send> AsyncItemReader<T> for AsyncCsvItemReader<T> {
+    async fn open(&mut self) {
+        self.open_file().await;
+    }
+
+    async fn read(&mut self) -> Option<T> {
+        self.read_positioned().await.map(|positioned| positioned.item)
+    }
+
+    async fn close(&mut self) {
+        self.reader = None;
+    }
+}
+
+/// A builder struct for constructing asynchronous CSV item readers.
+pub struct AsyncCsvItemReaderBuilder<T> {
+    /// The reader being constructed.
+    reader: AsyncCsvItemReader<T>,
+}
+
+impl<T: DeserializeOwned + Send> AsyncCsvItemReaderBuilder<T> {
+    /// Initializes a new builder reading the given file, comma delimited, with headers and
+    /// the compression its extension implies.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the file to read.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        AsyncCsvItemReaderBuilder {
+            reader: AsyncCsvItemReader {
+                compression: Compression::from_path(&path),
+                zip_entry: None,
+                path,
+                delimiter: b',',
+                quote: b'"',
+                has_headers: true,
+                skip_lines: 0,
+                reader: None,
+                headers: None,
+                record: StringRecord::new(),
+                _item: PhantomData,
+            }
+        }
+    }
+
+    /// Sets the field delimiter.
+    ///
+    /// # Parameters
+    ///
+    /// - `delimiter`: The field delimiter, e.g. `b';'`.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn delimiter(self, delimiter: u8) -> Self {
+        AsyncCsvItemReaderBuilder {
+            reader: AsyncCsvItemReader {
+                delimiter,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the quote character.
+    ///
+    /// # Parameters
+    ///
+    /// - `quote`: The quote character, e.g. `b'\''`.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn quote(self, quote: u8) -> Self {
+        AsyncCsvItemReaderBuilder {
+            reader: AsyncCsvItemReader {
+                quote,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets whether the first row holds the column names. Without headers, the columns are mapped
+    /// to the fields of the item by position.
+    ///
+    /// # Parameters
+    ///
+    /// - `has_headers`: Whether the first row holds the column names.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn has_headers(self, has_headers: bool) -> Self {
+        AsyncCsvItemReaderBuilder {
+            reader: AsyncCsvItemReader {
+                has_headers,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the number of lines skipped before the headers or the first row.
+    ///
+    /// # Parameters
+    ///
+    /// - `skip_lines`: The number of lines to skip.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn skip_lines(self, skip_lines: usize) -> Self {
+        AsyncCsvItemReaderBuilder {
+            reader: AsyncCsvItemReader {
+                skip_lines,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Parameters
+    ///
+    /// - `compression`: The compression of the file.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        AsyncCsvItemReaderBuilder {
+            reader: AsyncCsvItemReader {
+                compression,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the entry to read when the file is a zip archive. By default, the first file of the
+    /// archive is read.
+    ///
+    /// # Parameters
+    ///
+    /// - `zip_entry`: The name of the entry, e.g. `data/records.csv`.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn zip_entry(self, zip_entry: impl Into<String>) -> Self {
+        AsyncCsvItemReaderBuilder {
+            reader: AsyncCsvItemReader {
+                zip_entry: Some(zip_entry.into()),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.reader.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// The configured reader.
+    pub fn build(self) -> AsyncCsvItemReader<T> {
+        self.validate().reader
+    }
+}
+
+/// Serializes the items of each chunk as rows of a CSV file, asynchronously.
+///
+/// The file is created, or truncated, when the step opens the writer and flushed when it closes
+/// it. The workers of the step write their chunks one at a time. With headers, the column names
+/// are taken from the fields of the first item written. The file may be compressed, as described
+/// by [`Compression`].
+pub struct AsyncCsvItemWriter<O> {
+    /// The path of the file to write.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The field delimiter.
+    delimiter: u8,
+    /// The quote character.
+    quote: u8,
+    /// Whether a header row is written before the first row.
+    has_headers: bool,
+    /// The open writer, shared by the workers of the step.
+    writer: Mutex<Option<AsyncSerializer<AsyncOutputFile>>>,
+    _item: PhantomData<fn(O)>,
+}
+
+#[async_trait]
+impl<O: Serialize + Send + 'static> AsyncItemWriter<O> for AsyncCsvItemWriter<O> {
+    async fn open(&self) {
+        let file = AsyncOutputFile::create(&self.path, self.compression).await
+            .unwrap_or_else(|err| panic!("Error creating CSV file {}: {}", self.path.display(), err));
+        let writer = AsyncWriterBuilder::new()
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .has_headers(self.has_headers)
+            .create_serializer(file);
+        *self.writer.lock().await = Some(writer);
+    }
+
+    async fn write(&self, items: Vec<O>) {
+        let mut writer = self.writer.lock().await;
+        let writer = writer.as_mut().expect("CSV writer is not open");
+
+        for item in items {
+            writer.serialize(item).await
+                .unwrap_or_else(|err| panic!("Error writing CSV file {}: {}", self.path.display(), err));
+        }
+    }
+
+    async fn close(&self) {
+        if let Some(writer) = self.writer.lock().await.take() {
+            let mut file = writer.into_inner().await
+                .unwrap_or_else(|err| panic!("Error flushing CSV file {}: {}", self.path.display(), err.error()));
+            file.shutdown().await
+                .unwrap_or_else(|err| panic!("Error flushing CSV file {}: {}", self.path.display(), err));
+        }
+    }
+}
+
+/// A builder struct for constructing asynchronous CSV item writers.
+pub struct AsyncCsvItemWriterBuilder<O> {
+    /// The writer being constructed.
+    writer: AsyncCsvItemWriter<O>,
+}
+
+impl<O: Serialize + Send + 'static> AsyncCsvItemWriterBuilder<O> {
+    /// Initializes a new builder writing the given file, comma delimited, with headers and
+    /// the compression its extension implies.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the file to write.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        AsyncCsvItemWriterBuilder {
+            writer: AsyncCsvItemWriter {
+                compression: Compression::from_path(&path),
+                path,
+                delimiter: b',',
+                quote: b'"',
+                has_headers: true,
+                writer: Mutex::new(None),
+                _item: PhantomData,
+            }
+        }
+    }
+
+    /// Sets the field delimiter.
+    ///
+    /// # Parameters
+    ///
+    /// - `delimiter`: The field delimiter, e.g. `b';'`.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn delimiter(self, delimiter: u8) -> Self {
+        AsyncCsvItemWriterBuilder {
+            writer: AsyncCsvItemWriter {
+                delimiter,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets the quote character.
+    ///
+    /// # Parameters
+    ///
+    /// - `quote`: The quote character, e.g. `b'\''`.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn quote(self, quote: u8) -> Self {
+        AsyncCsvItemWriterBuilder {
+            writer: AsyncCsvItemWriter {
+                quote,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets whether a header row is written before the first row.
+    ///
+    /// # Parameters
+    ///
+    /// - `has_headers`: Whether to write a header row.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn has_headers(self, has_headers: bool) -> Self {
+        AsyncCsvItemWriterBuilder {
+            writer: AsyncCsvItemWriter {
+                has_headers,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Parameters
+    ///
+    /// - `compression`: The compression of the file; zip archives cannot be written.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        AsyncCsvItemWriterBuilder {
+            writer: AsyncCsvItemWriter {
+                compression,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.writer.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.writer.compression == Compression::Zip {
+            panic!("Zip archives cannot be written, use gzip or zstd compression");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// The configured writer.
+    pub fn build(self) -> AsyncCsvItemWriter<O> {
+        self.validate().writer
+    }
+}
@@ -0,0 +1,380 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::core::compression::{open_async_input, AsyncOutputFile, Compression};
+use crate::core::flat_file::{FieldValue, FlatRecord, RecordAssembler, RecordLayout};
+use crate::tokio::item::{AsyncItemReader, AsyncItemWriter};
+
+/// Reads the records of a fixed-width or delimited flat file asynchronously.
+///
+/// Each line is matched to a record layout by its prefix, and the fields of the layout are
+/// extracted and converted. A layout may span several lines, which are read as a single record.
+/// A line matching no layout, a delimited line with more columns than fields or a field that
+/// cannot be converted panics with its line number, which fails the step. Compressed files are
+/// decompressed while they are read, see [`Compression`].
+pub struct AsyncFlatFileItemReader {
+    /// The path of the file to read.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The entry to read from a zip archive, or `None` for its first file.
+    zip_entry: Option<String>,
+    /// The layouts of the records of the file.
+    layouts: Vec<RecordLayout>,
+    /// The number of lines skipped at the start of the file.
+    skip_lines: usize,
+    /// The open file.
+    reader: Option<Box<dyn AsyncBufRead + Send + Unpin>>,
+    /// Turns the lines read into records.
+    assembler: RecordAssembler,
+    /// The last line read.
+    buffer: String,
+}
+
+#[async_trait]
+impl AsyncItemReader<FlatRecord> for AsyncFlatFileItemReader {
+    async fn open(&mut self) {
+        let file = open_async_input(&self.path, self.compression, self.zip_entry.as_deref()).await
+            .unwrap_or_else(|err| panic!("Error opening flat file {}: {}", self.path.display(), err));
+        self.reader = Some(file);
+        self.assembler = RecordAssembler::new(self.layouts.clone(), self.skip_lines);
+    }
+
+    async fn read(&mut self) -> Option<FlatRecord> {
+        let reader = self.reader.as_mut().expect("Flat file reader is not open");
+
+        loop {
+            self.buffer.clear();
+            let read = reader.read_line(&mut self.buffer).await
+                .unwrap_or_else(|err| panic!("Error reading flat file {}: {}", self.path.display(), err));
+            if read == 0 {
+                self.assembler.finish()
+                    .unwrap_or_else(|err| panic!("Error reading flat file {}: {}", self.path.display(), err));
+                return None;
+            }
+
+            let record = self.assembler.push(&self.buffer)
+                .unwrap_or_else(|err| panic!("Error reading flat file {}: {}", self.path.display(), err));
+            if record.is_some() {
+                return record;
+            }
+        }
+    }
+
+    async fn close(&mut self) {
+        self.reader = None;
+    }
+}
+
+/// A builder struct for constructing asynchronous flat file item readers.
+pub struct AsyncFlatFileItemReaderBuilder {
+    /// The reader being constructed.
+    reader: AsyncFlatFileItemReader,
+}
+
+impl AsyncFlatFileItemReaderBuilder {
+    /// Initializes a new builder reading the given file, with the compression its extension
+    /// implies.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the file to read.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        AsyncFlatFileItemReaderBuilder {
+            reader: AsyncFlatFileItemReader {
+                compression: Compression::from_path(&path),
+                zip_entry: None,
+                path,
+                layouts: Vec::new(),
+                skip_lines: 0,
+                reader: None,
+                assembler: RecordAssembler::new(Vec::new(), 0),
+                buffer: String::new(),
+            }
+        }
+    }
+
+    /// Adds the layout of a kind of record. A file with a single kind of record has a single
+    /// layout, without prefix.
+    ///
+    /// # Parameters
+    ///
+    /// - `layout`: The record layout.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn layout(mut self, layout: RecordLayout) -> Self {
+        self.reader.layouts.push(layout);
+        self
+    }
+
+    /// Sets the number of lines skipped at the start of the file, e.g. a title.
+    ///
+    /// # Parameters
+    ///
+    /// - `skip_lines`: The number of lines to skip.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn skip_lines(self, skip_lines: usize) -> Self {
+        AsyncFlatFileItemReaderBuilder {
+            reader: AsyncFlatFileItemReader {
+                skip_lines,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Parameters
+    ///
+    /// - `compression`: The compression of the file.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        AsyncFlatFileItemReaderBuilder {
+            reader: AsyncFlatFileItemReader {
+                compression,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the entry to read when the file is a zip archive. By default, the first file of the
+    /// archive is read.
+    ///
+    /// # Parameters
+    ///
+    /// - `zip_entry`: The name of the entry, e.g. `data/records.txt`.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn zip_entry(self, zip_entry: impl Into<String>) -> Self {
+        AsyncFlatFileItemReaderBuilder {
+            reader: AsyncFlatFileItemReader {
+                zip_entry: Some(zip_entry.into()),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.reader.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.reader.layouts.is_empty() {
+            panic!("At least one record layout is required");
+        }
+
+        self.reader.layouts.iter().for_each(RecordLayout::validate);
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// The configured reader.
+    pub fn build(self) -> AsyncFlatFileItemReader {
+        self.validate().reader
+    }
+}
+
+/// Formats an item into the values of the fields of a record, in declaration order.
+pub type AsyncFieldFormatter<O> = Box<dyn Fn(&O) -> Vec<FieldValue> + Send + Sync>;
+
+/// Writes the items of each chunk as the records of a fixed-width or delimited flat file,
+/// asynchronously.
+///
+/// The file is created, or truncated, when the step opens the writer. The workers of the step
+/// write their chunks one at a time. Fixed-width values are padded to the width of their field
+/// and delimited values are written unquoted; a value too long for its field, or holding the
+/// delimiter or a line break, panics, which fails the chunk. For compressed output, see
+/// [`Compression`].
+pub struct AsyncFlatFileItemWriter<O> {
+    /// The path of the file to write.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The layout of the records.
+    layout: Option<RecordLayout>,
+    /// Formats an item into the values of its fields.
+    formatter: Option<AsyncFieldFormatter<O>>,
+    /// The open file, shared by the workers of the step.
+    writer: Mutex<Option<AsyncOutputFile>>,
+}
+
+#[async_trait]
+impl<O: Send + 'static> AsyncItemWriter<O> for AsyncFlatFileItemWriter<O> {
+    async fn open(&self) {
+        let file = AsyncOutputFile::create(&self.path, self.compression).await
+            .unwrap_or_else(|err| panic!("Error creating flat file {}: {}", self.path.display(), err));
+        *self.writer.lock().await = Some(file);
+    }
+
+    async fn write(&self, items: Vec<O>) {
+        let layout = self.layout.as_ref().unwrap();
+        let formatter = self.formatter.as_ref().unwrap();
+
+        let mut output = String::new();
+        for item in items {
+            let record = layout.format(formatter(&item))
+                .unwrap_or_else(|err| panic!("Error formatting a record of flat file {}: {}", self.path.display(), err));
+            output.push_str(&record);
+            output.push('\n');
+        }
+
+        let mut writer = self.writer.lock().await;
+        let writer = writer.as_mut().expect("Flat file writer is not open");
+        writer.write_all(output.as_bytes()).await
+            .unwrap_or_else(|err| panic!("Error writing flat file {}: {}", self.path.display(), err));
+    }
+
+    async fn close(&self) {
+        if let Some(mut writer) = self.writer.lock().await.take() {
+            writer.shutdown().await
+                .unwrap_or_else(|err| panic!("Error flushing flat file {}: {}", self.path.display(), err));
+        }
+    }
+}
+
+/// A builder struct for constructing asynchronous flat file item writers.
+pub struct AsyncFlatFileItemWriterBuilder<O> {
+    /// The writer being constructed.
+    writer: AsyncFlatFileItemWriter<O>,
+}
+
+impl<O: Send + 'static> AsyncFlatFileItemWriterBuilder<O> {
+    /// Initializes a new builder writing the given file, with the compression its extension
+    /// implies.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the file to write.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        AsyncFlatFileItemWriterBuilder {
+            writer: AsyncFlatFileItemWriter {
+                compression: Compression::from_path(&path),
+                path,
+                layout: None,
+                formatter: None,
+                writer: Mutex::new(None),
+            }
+        }
+    }
+
+    /// Sets the layout of the records.
+    ///
+    /// # Parameters
+    ///
+    /// - `layout`: The record layout.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn layout(self, layout: RecordLayout) -> Self {
+        AsyncFlatFileItemWriterBuilder {
+            writer: AsyncFlatFileItemWriter {
+                layout: Some(layout),
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets the function formatting an item into the values of the fields of its record.
+    ///
+    /// # Parameters
+    ///
+    /// - `formatter`: The formatting function.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn formatter(self, formatter: AsyncFieldFormatter<O>) -> Self {
+        AsyncFlatFileItemWriterBuilder {
+            writer: AsyncFlatFileItemWriter {
+                formatter: Some(formatter),
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Parameters
+    ///
+    /// - `compression`: The compression of the file; zip archives cannot be written.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        AsyncFlatFileItemWriterBuilder {
+            writer: AsyncFlatFileItemWriter {
+                compression,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.writer.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.writer.compression == Compression::Zip {
+            panic!("Zip archives cannot be written, use gzip or zstd compression");
+        }
+
+        match &self.writer.layout {
+            Some(layout) => layout.validate(),
+            None => panic!("Record layout is required"),
+        }
+
+        if self.writer.formatter.is_none() {
+            panic!("Formatter is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// The configured writer.
+    pub fn build(self) -> AsyncFlatFileItemWriter<O> {
+        self.validate().writer
+    }
+}
@@ -0,0 +1,408 @@
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::core::compression::{open_async_input, AsyncOutputFile, Compression};
+use crate::core::item::Positioned;
+use crate::core::json::{JsonArrayScanner, JsonFormat, JsonFraming};
+use crate::tokio::item::{AsyncItemReader, AsyncItemWriter};
+
+/// Reads the values of a JSON file asynchronously, deserializing each of them into `T`.
+///
+/// Depending on its format, the file holds one value per line or a single top-level array, which
+/// is streamed element by element so that only one item is held in memory. A value that cannot be
+/// deserialized panics with its line number, which fails the step. Compressed files are streamed
+/// through their decoder, see [`Compression`].
+///
+/// Besides `T`, the reader yields `Positioned<T>` items carrying the line of each value.
+pub struct AsyncJsonItemReader<T> {
+    /// The path of the file to read.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The entry to read from a zip archive, or `None` for its first file.
+    zip_entry: Option<String>,
+    /// The layout of the file.
+    format: JsonFormat,
+    /// The open file.
+    reader: Option<Box<dyn AsyncBufRead + Send + Unpin>>,
+    /// Splits a top-level array into its elements.
+    scanner: JsonArrayScanner,
+    /// The line of the last value read.
+    line: u64,
+    /// The last line read, for the lines format.
+    buffer: String,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned + Send> AsyncJsonItemReader<T> {
+    /// Returns the line the last value read starts at, or zero before the first value.
+    pub fn line(&self) -> u64 {
+        self.line
+    }
+
+    /// Opens the file.
+    async fn open_file(&mut self) {
+        let file = open_async_input(&self.path, self.compression, self.zip_entry.as_deref()).await
+            .unwrap_or_else(|err| panic!("Error opening JSON file {}: {}", self.path.display(), err));
+        self.reader = Some(file);
+        self.scanner = JsonArrayScanner::new();
+        self.line = 0;
+    }
+
+    /// Reads the bytes of the next value and the line it starts at.
+    async fn read_value(&mut self) -> Option<(u64, Vec<u8>)> {
+        let reader = self.reader.as_mut().expect("JSON reader is not open");
+
+        match self.format {
+            JsonFormat::Lines => loop {
+                self.buffer.clear();
+                let read = reader.read_line(&mut self.buffer).await
+                    .unwrap_or_else(|err| panic!("Error reading JSON file {}: {}", self.path.display(), err));
+                if read == 0 {
+                    return None;
+                }
+
+                self.line += 1;
+                if !self.buffer.trim().is_empty() {
+                    return Some((self.line, self.buffer.as_bytes().to_vec()));
+                }
+            },
+            JsonFormat::Array => loop {
+                let bytes = reader.fill_buf().await
+                    .unwrap_or_else(|err| panic!("Error reading JSON file {}: {}", self.path.display(), err));
+                if bytes.is_empty() {
+                    if self.scanner.is_done() {
+                        return None;
+                    }
+                    panic!("Unexpected end of JSON file {} at line {}", self.path.display(), self.scanner.line());
+                }
+
+                let (consumed, value) = self.scanner.scan(bytes)
+                    .unwrap_or_else(|err| panic!("Error reading JSON file {}: {}", self.path.display(), err));
+                reader.consume(consumed);
+
+                if value.is_some() {
+                    return value;
+                }
+            },
+        }
+    }
+
+    /// Reads and deserializes the next value.
+    async fn read_positioned(&mut self) -> Option<Positioned<T>> {
+        let (line, value) = self.read_value().await?;
+        self.line = line;
+
+        match serde_json::from_slice(&value) {
+            Ok(item) => Some(Positioned { line, item }),
+            Err(err) => panic!("Error deserializing line {} of JSON file {}: {}", line, self.path.display(), err),
+        }
+    }
+}
+
+#[async_trait]
+impl<T: DeserializeOwned + Send> AsyncItemReader<Positioned<T>> for AsyncJsonItemReader<T> {
+    async fn open(&mut self) {
+        self.open_file().await;
+    }
+
+    async fn read(&mut self) -> Option<Positioned<T>> {
+        self.read_positioned().await
+    }
+
+    async fn close(&mut self) {
+        self.reader = None;
+    }
+}
+
+#[async_trait]
+impl<T: DeserializeOwned + Send> AsyncItemReader<T> for AsyncJsonItemReader<T> {
+    async fn open(&mut self) {
+        self.open_file().await;
+    }
+
+    async fn read(&mut self) -> Option<T> {
+        self.read_positioned().await.map(|positioned| positioned.item)
+    }
+
+    async fn close(&mut self) {
+        self.reader = None;
+    }
+}
+
+/// A builder struct for constructing asynchronous JSON item readers.
+pub struct AsyncJsonItemReaderBuilder<T> {
+    /// The reader being constructed.
+    reader: AsyncJsonItemReader<T>,
+}
+
+impl<T: DeserializeOwned + Send> AsyncJsonItemReaderBuilder<T> {
+    /// Initializes a new builder reading the given file, with one value per line and the
+    /// compression its extension implies.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the file to read.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        AsyncJsonItemReaderBuilder {
+            reader: AsyncJsonItemReader {
+                compression: Compression::from_path(&path),
+                zip_entry: None,
+                path,
+                format: JsonFormat::Lines,
+                reader: None,
+                scanner: JsonArrayScanner::new(),
+                line: 0,
+                buffer: String::new(),
+                _item: PhantomData,
+            }
+        }
+    }
+
+    /// Sets the layout of the file.
+    ///
+    /// # Parameters
+    ///
+    /// - `format`: The layout of the file.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn format(self, format: JsonFormat) -> Self {
+        AsyncJsonItemReaderBuilder {
+            reader: AsyncJsonItemReader {
+                format,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Parameters
+    ///
+    /// - `compression`: The compression of the file.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        AsyncJsonItemReaderBuilder {
+            reader: AsyncJsonItemReader {
+                compression,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the entry to read when the file is a zip archive. By default, the first file of the
+    /// archive is read.
+    ///
+    /// # Parameters
+    ///
+    /// - `zip_entry`: The name of the entry, e.g. `data/records.json`.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn zip_entry(self, zip_entry: impl Into<String>) -> Self {
+        AsyncJsonItemReaderBuilder {
+            reader: AsyncJsonItemReader {
+                zip_entry: Some(zip_entry.into()),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.reader.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// The configured reader.
+    pub fn build(self) -> AsyncJsonItemReader<T> {
+        self.validate().reader
+    }
+}
+
+/// The open file of an asynchronous JSON item writer.
+struct JsonOutput {
+    writer: AsyncOutputFile,
+    framing: JsonFraming,
+}
+
+/// Serializes the items of each chunk to a JSON file, asynchronously.
+///
+/// The file is created, or truncated, when the step opens the writer. The workers of the step
+/// write their chunks one at a time. With the array format, the closing bracket is written when
+/// the step closes the writer, so the file is a well-formed array even when no item has been
+/// written. Compression of the output is set up as described by [`Compression`].
+pub struct AsyncJsonItemWriter<O> {
+    /// The path of the file to write.
+    path: PathBuf,
+    /// The compression of the file.
+    compression: Compression,
+    /// The layout of the file.
+    format: JsonFormat,
+    /// The open file, shared by the workers of the step.
+    output: Mutex<Option<JsonOutput>>,
+    _item: PhantomData<fn(O)>,
+}
+
+impl<O> AsyncJsonItemWriter<O> {
+    async fn write_bytes(&self, writer: &mut AsyncOutputFile, bytes: &[u8]) {
+        writer.write_all(bytes).await
+            .unwrap_or_else(|err| panic!("Error writing JSON file {}: {}", self.path.display(), err));
+    }
+}
+
+#[async_trait]
+impl<O: Serialize + Send + 'static> AsyncItemWriter<O> for AsyncJsonItemWriter<O> {
+    async fn open(&self) {
+        let file = AsyncOutputFile::create(&self.path, self.compression).await
+            .unwrap_or_else(|err| panic!("Error creating JSON file {}: {}", self.path.display(), err));
+        let mut output = JsonOutput {
+            writer: file,
+            framing: JsonFraming::new(self.format),
+        };
+        self.write_bytes(&mut output.writer, output.framing.header()).await;
+        *self.output.lock().await = Some(output);
+    }
+
+    async fn write(&self, items: Vec<O>) {
+        let mut output = self.output.lock().await;
+        let output = output.as_mut().expect("JSON writer is not open");
+
+        for item in items {
+            let value = serde_json::to_vec(&item)
+                .unwrap_or_else(|err| panic!("Error serializing an item of JSON file {}: {}", self.path.display(), err));
+            self.write_bytes(&mut output.writer, output.framing.separator()).await;
+            self.write_bytes(&mut output.writer, &value).await;
+            self.write_bytes(&mut output.writer, output.framing.terminator()).await;
+        }
+    }
+
+    async fn close(&self) {
+        if let Some(mut output) = self.output.lock().await.take() {
+            self.write_bytes(&mut output.writer, output.framing.footer()).await;
+            output.writer.shutdown().await
+                .unwrap_or_else(|err| panic!("Error flushing JSON file {}: {}", self.path.display(), err));
+        }
+    }
+}
+
+/// A builder struct for constructing asynchronous JSON item writers.
+pub struct AsyncJsonItemWriterBuilder<O> {
+    /// The writer being constructed.
+    writer: AsyncJsonItemWriter<O>,
+}
+
+impl<O: Serialize + Send + 'static> AsyncJsonItemWriterBuilder<O> {
+    /// Initializes a new builder writing the given file, with one value per line and the
+    /// compression its extension implies.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the file to write.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        AsyncJsonItemWriterBuilder {
+            writer: AsyncJsonItemWriter {
+                compression: Compression::from_path(&path),
+                path,
+                format: JsonFormat::Lines,
+                output: Mutex::new(None),
+                _item: PhantomData,
+            }
+        }
+    }
+
+    /// Sets the layout of the file.
+    ///
+    /// # Parameters
+    ///
+    /// - `format`: The layout of the file.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn format(self, format: JsonFormat) -> Self {
+        AsyncJsonItemWriterBuilder {
+            writer: AsyncJsonItemWriter {
+                format,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Sets the compression of the file, for a file whose extension does not tell it.
+    ///
+    /// # Parameters
+    ///
+    /// - `compression`: The compression of the file; zip archives cannot be written.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn compression(self, compression: Compression) -> Self {
+        AsyncJsonItemWriterBuilder {
+            writer: AsyncJsonItemWriter {
+                compression,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.writer.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.writer.compression == Compression::Zip {
+            panic!("Zip archives cannot be written, use gzip or zstd compression");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// The configured writer.
+    pub fn build(self) -> AsyncJsonItemWriter<O> {
+        self.validate().writer
+    }
+}
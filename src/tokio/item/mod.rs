@@ -0,0 +1,153 @@
+pub mod composite;
+pub mod flat_file;
+pub mod paging;
+pub mod processor;
+#[cfg(feature = "glob")]
+pub mod multi_resource;
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::{Stream, StreamExt};
+
+/// A trait for reading the items of an asynchronous complex step.
+///
+/// The reader is opened before the first item is read and closed once the last item has been
+/// dispatched to the workers or the reader has failed. Any `Stream` is a reader.
+#[async_trait]
+pub trait AsyncItemReader<I>: Send {
+    /// Called before the first item is read, e.g. to open a file or a connection.
+    async fn open(&mut self) {}
+
+    /// Reads the next item.
+    ///
+    /// # Returns `Option<I>`
+    ///
+    /// Returns the item, or `None` once the input is exhausted.
+    async fn read(&mut self) -> Option<I>;
+
+    /// Called between reads once the chunks holding the first items read have been written, e.g.
+    /// to record a restart position. The workers write their chunks in any order, so only the
+    /// items up to the first one not written yet are counted. Once every worker has stopped, even
+    /// when the step fails, it is called with the final count, which is the number of items read
+    /// when every chunk has been written and every failed item skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `written` - The number of items, counted from the first one read since the reader was
+    ///   opened, that have been written.
+    async fn checkpoint(&mut self, _written: u64) {}
+
+    /// Called once the step has stopped reading, e.g. to release a file or a connection.
+    async fn close(&mut self) {}
+}
+
+/// A trait for transforming the items of an asynchronous complex step.
+///
+/// The processor is shared by the workers of the step, so it only gets `&self`. Any
+/// `Fn(I) -> BoxFuture<'static, O>` closure is a processor.
+#[async_trait]
+pub trait AsyncItemProcessor<I, O>: Send + Sync {
+    /// Called before the first item is processed.
+    async fn open(&self) {}
+
+    /// Transforms an item.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item read.
+    ///
+    /// # Returns `O`
+    ///
+    /// Returns the item to write.
+    async fn process(&self, item: I) -> O;
+
+    /// Transforms an item, or skips it, e.g. when it is invalid. The step counts a skipped item
+    /// as skipped and writes nothing for it. Defaults to `process`.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item read.
+    ///
+    /// # Returns `Result<O, String>`
+    ///
+    /// Returns the item to write, or the reason the item is skipped.
+    async fn process_or_skip(&self, item: I) -> Result<O, String>
+    where
+        I: Send + 'static,
+    {
+        Ok(self.process(item).await)
+    }
+
+    /// Called once every worker has stopped processing.
+    async fn close(&self) {}
+}
+
+/// A trait for writing the chunks of an asynchronous complex step.
+///
+/// The writer is shared by the workers of the step, so it only gets `&self`. Any
+/// `Fn(Vec<O>) -> BoxFuture<'static, ()>` closure is a writer.
+#[async_trait]
+pub trait AsyncItemWriter<O>: Send + Sync {
+    /// Called before the first chunk is written.
+    async fn open(&self) {}
+
+    /// Writes a chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items of the chunk.
+    async fn write(&self, items: Vec<O>);
+
+    /// Counts the items of a chunk, as reported to the listeners, the metrics and the execution
+    /// of the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items of the chunk.
+    ///
+    /// # Returns `usize`
+    ///
+    /// Returns the number of items written, the length of the chunk unless the writer expands it.
+    fn item_count(&self, items: &[O]) -> usize {
+        items.len()
+    }
+
+    /// Called once every worker has stopped writing, e.g. to flush buffered output.
+    async fn close(&self) {}
+}
+
+#[async_trait]
+impl<I, S> AsyncItemReader<I> for S
+where
+    S: Stream<Item=I> + Send + Unpin,
+{
+    async fn read(&mut self) -> Option<I> {
+        self.next().await
+    }
+}
+
+#[async_trait]
+impl<I: Send + 'static, O, F> AsyncItemProcessor<I, O> for F
+where
+    F: Send + Sync + Fn(I) -> BoxFuture<'static, O>,
+{
+    async fn process(&self, item: I) -> O {
+        self(item).await
+    }
+}
+
+#[async_trait]
+impl<O: Send + 'static, F> AsyncItemWriter<O> for F
+where
+    F: Send + Sync + Fn(Vec<O>) -> BoxFuture<'static, ()>,
+{
+    async fn write(&self, items: Vec<O>) {
+        self(items).await
+    }
+}
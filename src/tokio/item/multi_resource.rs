@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::core::item::ResourceItem;
+use crate::core::resource::{matching_resources, ResourceCheckpoint};
+use crate::tokio::item::AsyncItemReader;
+
+/// Creates the reader of a resource, e.g. `|path| Box::new(AsyncCsvItemReaderBuilder::get(path).build())`.
+pub type AsyncResourceReaderFactory<I> = Box<dyn FnMut(&Path) -> Box<dyn AsyncItemReader<I>> + Send>;
+
+/// Reads the items of every file matching a glob pattern asynchronously, one file after the other
+/// in path order.
+///
+/// Each file is read by its own reader, opened when the previous file has been read completely and
+/// closed once it is exhausted. The items are wrapped with the file they come from.
+///
+/// With a restart file, the file holding the first item not written yet is recorded each time the
+/// step checkpoints the reader, and a new run after a failure skips the files before it. That file
+/// is read again from its start. The restart file is removed once every item has been written.
+pub struct AsyncMultiResourceItemReader<I> {
+    /// The glob pattern of the files to read.
+    pattern: String,
+    /// Creates the reader of each file.
+    factory: Option<AsyncResourceReaderFactory<I>>,
+    /// Records the file holding the first item not written yet.
+    checkpoint: Option<ResourceCheckpoint>,
+    /// The files to read, resolved when the reader is opened.
+    resources: Vec<PathBuf>,
+    /// The index of the next file to open.
+    next: usize,
+    /// The file being read and its reader.
+    current: Option<(Arc<Path>, Box<dyn AsyncItemReader<I>>)>,
+    /// The number of items read from the current file.
+    index: u64,
+    /// The number of items read since the reader was opened.
+    read: u64,
+    /// Whether every file has been read.
+    exhausted: bool,
+}
+
+#[async_trait]
+impl<I: Send> AsyncItemReader<ResourceItem<I>> for AsyncMultiResourceItemReader<I> {
+    async fn open(&mut self) {
+        let resources = matching_resources(&self.pattern);
+        self.resources = match &mut self.checkpoint {
+            Some(checkpoint) => checkpoint.resume(resources),
+            None => resources,
+        };
+        self.next = 0;
+        self.read = 0;
+        self.exhausted = false;
+    }
+
+    async fn read(&mut self) -> Option<ResourceItem<I>> {
+        loop {
+            if self.current.is_none() {
+                let Some(resource) = self.resources.get(self.next) else {
+                    self.exhausted = true;
+                    return None;
+                };
+                self.next += 1;
+
+                if let Some(checkpoint) = &mut self.checkpoint {
+                    checkpoint.start(resource, self.read);
+                }
+
+                let factory = self.factory.as_mut().unwrap();
+                let mut reader = factory(resource);
+                reader.open().await;
+                self.current = Some((Arc::from(resource.as_path()), reader));
+                self.index = 0;
+            }
+
+            let (resource, reader) = self.current.as_mut().unwrap();
+            match reader.read().await {
+                Some(item) => {
+                    self.index += 1;
+                    self.read += 1;
+                    return Some(ResourceItem {
+                        resource: Arc::clone(resource),
+                        index: self.index,
+                        item,
+                    });
+                }
+                None => {
+                    reader.close().await;
+                    self.current = None;
+                }
+            }
+        }
+    }
+
+    async fn checkpoint(&mut self, written: u64) {
+        if let Some(checkpoint) = &mut self.checkpoint {
+            checkpoint.written(written, self.read, self.exhausted);
+        }
+    }
+
+    async fn close(&mut self) {
+        if let Some((_, mut reader)) = self.current.take() {
+            reader.close().await;
+        }
+    }
+}
+
+/// A builder struct for constructing multi-resource item readers.
+pub struct AsyncMultiResourceItemReaderBuilder<I> {
+    /// The reader being constructed.
+    reader: AsyncMultiResourceItemReader<I>,
+}
+
+impl<I: Send> AsyncMultiResourceItemReaderBuilder<I> {
+    /// Initializes a new builder reading the files matching the given glob pattern.
+    ///
+    /// # Parameters
+    ///
+    /// - `pattern`: The glob pattern, e.g. `input/*.csv`.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(pattern: impl Into<String>) -> Self {
+        AsyncMultiResourceItemReaderBuilder {
+            reader: AsyncMultiResourceItemReader {
+                pattern: pattern.into(),
+                factory: None,
+                checkpoint: None,
+                resources: Vec::new(),
+                next: 0,
+                current: None,
+                index: 0,
+                read: 0,
+                exhausted: false,
+            }
+        }
+    }
+
+    /// Sets the function creating the reader of each file.
+    ///
+    /// # Parameters
+    ///
+    /// - `factory`: The function creating the reader of a file.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn reader(self, factory: AsyncResourceReaderFactory<I>) -> Self {
+        AsyncMultiResourceItemReaderBuilder {
+            reader: AsyncMultiResourceItemReader {
+                factory: Some(factory),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the file recording the file holding the first item not written yet, so that a new run
+    /// resumes at that file.
+    ///
+    /// # Parameters
+    ///
+    /// - `restart_file`: The path of the restart file.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn restart_file(self, restart_file: impl Into<PathBuf>) -> Self {
+        AsyncMultiResourceItemReaderBuilder {
+            reader: AsyncMultiResourceItemReader {
+                checkpoint: Some(ResourceCheckpoint::new(restart_file.into())),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.reader.pattern.is_empty() {
+            panic!("Pattern is required");
+        }
+
+        if self.reader.factory.is_none() {
+            panic!("Reader is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// The configured reader.
+    pub fn build(self) -> AsyncMultiResourceItemReader<I> {
+        self.validate().reader
+    }
+}
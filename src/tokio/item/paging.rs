@@ -0,0 +1,476 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::panic;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::vec;
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use tokio::task::JoinHandle;
+
+use crate::core::restart::RestartFile;
+use crate::tokio::item::AsyncItemReader;
+
+/// The number of items of a page, unless configured otherwise.
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Fetches the page of at most `limit` items starting at `offset`, e.g. with a
+/// `SELECT ... ORDER BY id LIMIT ? OFFSET ?` query. A page shorter than `limit` is the last one.
+pub type AsyncPageFetcher<I> = Box<dyn FnMut(u64, usize) -> BoxFuture<'static, Vec<I>> + Send>;
+
+/// Fetches the page of at most `limit` items following the key `after`, or the first page when
+/// `after` is `None`, e.g. with a `SELECT ... WHERE id > ? ORDER BY id LIMIT ?` query. A page
+/// shorter than `limit` is the last one.
+pub type AsyncKeysetPageFetcher<I, K> = Box<dyn FnMut(Option<K>, usize) -> BoxFuture<'static, Vec<I>> + Send>;
+
+/// Returns the key of an item, by which the pages of a keyset reader are ordered.
+pub type AsyncKeyExtractor<I, K> = Box<dyn Fn(&I) -> K + Send>;
+
+/// Waits for a page fetched by a task. A panic fetching it is resumed in the reader.
+async fn receive<I>(fetch: JoinHandle<Vec<I>>) -> Vec<I> {
+    match fetch.await {
+        Ok(page) => page,
+        Err(err) if err.is_panic() => panic::resume_unwind(err.into_panic()),
+        Err(err) => panic!("Page fetching task failed: {}", err),
+    }
+}
+
+/// Reads the items of a source one page at a time asynchronously, by offset and limit.
+///
+/// The next page is fetched by a task of the runtime while the items of the current page are
+/// processed. With a restart file, the offset of the first item not written yet is recorded each
+/// time the step checkpoints the reader, and a new run after a failure resumes at that offset. The
+/// restart file is removed once every item has been written.
+pub struct AsyncPagingItemReader<I> {
+    /// Fetches a page.
+    fetcher: AsyncPageFetcher<I>,
+    /// The number of items of a page.
+    page_size: usize,
+    /// Records the offset of the first item not written yet.
+    restart: Option<RestartFile>,
+    /// The task fetching the next page.
+    fetch: Option<JoinHandle<Vec<I>>>,
+    /// The items of the current page not read yet.
+    page: vec::IntoIter<I>,
+    /// The offset of the next page.
+    next_offset: u64,
+    /// The offset the reader was opened at.
+    start_offset: u64,
+    /// The number of items read since the reader was opened.
+    read: u64,
+    /// Whether every page has been read.
+    exhausted: bool,
+}
+
+impl<I: Send + 'static> AsyncPagingItemReader<I> {
+    /// Starts fetching the next page.
+    fn request(&mut self) {
+        let fetch = (self.fetcher)(self.next_offset, self.page_size);
+        self.fetch = Some(tokio::spawn(fetch));
+    }
+}
+
+#[async_trait]
+impl<I: Send + 'static> AsyncItemReader<I> for AsyncPagingItemReader<I> {
+    async fn open(&mut self) {
+        self.next_offset = match self.restart.as_ref().and_then(RestartFile::load) {
+            Some(offset) => offset.trim().parse()
+                .unwrap_or_else(|_| panic!("Invalid offset {} in restart file", offset)),
+            None => 0,
+        };
+
+        self.start_offset = self.next_offset;
+        self.read = 0;
+        self.page = Vec::new().into_iter();
+        self.exhausted = false;
+        self.request();
+    }
+
+    async fn read(&mut self) -> Option<I> {
+        loop {
+            if let Some(item) = self.page.next() {
+                self.read += 1;
+                return Some(item);
+            }
+
+            let Some(fetch) = self.fetch.take() else {
+                self.exhausted = true;
+                return None;
+            };
+
+            let page = receive(fetch).await;
+            self.next_offset += page.len() as u64;
+            if page.len() >= self.page_size {
+                self.request();
+            }
+            self.page = page.into_iter();
+        }
+    }
+
+    async fn checkpoint(&mut self, written: u64) {
+        let Some(restart) = &self.restart else {
+            return;
+        };
+
+        if self.exhausted && written == self.read {
+            restart.clear();
+        } else {
+            restart.save(&(self.start_offset + written).to_string());
+        }
+    }
+
+    async fn close(&mut self) {
+        if let Some(fetch) = self.fetch.take() {
+            fetch.abort();
+            let _ = fetch.await;
+        }
+        self.page = Vec::new().into_iter();
+    }
+}
+
+/// A builder struct for constructing asynchronous paging item readers.
+pub struct AsyncPagingItemReaderBuilder<I> {
+    /// The reader being constructed.
+    reader: AsyncPagingItemReader<I>,
+}
+
+impl<I: Send + 'static> AsyncPagingItemReaderBuilder<I> {
+    /// Initializes a new builder reading the pages fetched by the given function, 100 items at a
+    /// time.
+    ///
+    /// # Parameters
+    ///
+    /// - `fetcher`: The function fetching a page.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(fetcher: AsyncPageFetcher<I>) -> Self {
+        AsyncPagingItemReaderBuilder {
+            reader: AsyncPagingItemReader {
+                fetcher,
+                page_size: DEFAULT_PAGE_SIZE,
+                restart: None,
+                fetch: None,
+                page: Vec::new().into_iter(),
+                next_offset: 0,
+                start_offset: 0,
+                read: 0,
+                exhausted: false,
+            }
+        }
+    }
+
+    /// Sets the number of items of a page.
+    ///
+    /// # Parameters
+    ///
+    /// - `page_size`: The number of items of a page.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn page_size(self, page_size: usize) -> Self {
+        AsyncPagingItemReaderBuilder {
+            reader: AsyncPagingItemReader {
+                page_size,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the file recording the offset of the first item not written yet, so that a new run
+    /// resumes at that item.
+    ///
+    /// # Parameters
+    ///
+    /// - `restart_file`: The path of the restart file.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn restart_file(self, restart_file: impl Into<PathBuf>) -> Self {
+        AsyncPagingItemReaderBuilder {
+            reader: AsyncPagingItemReader {
+                restart: Some(RestartFile::new(restart_file.into())),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.reader.page_size == 0 {
+            panic!("Page size must be greater than zero");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// The configured reader.
+    pub fn build(self) -> AsyncPagingItemReader<I> {
+        self.validate().reader
+    }
+}
+
+/// Reads the items of a source one page at a time asynchronously, each page following the key of
+/// the last item of the previous one.
+///
+/// Unlike an offset, a key keeps its cost constant on large tables and is not shifted by rows
+/// inserted or deleted during the reading. The next page is fetched by a task of the runtime while
+/// the items of the current page are processed. With a restart file, the key of the last item
+/// written is recorded each time the step checkpoints the reader, and a new run after a failure
+/// resumes after that key. The restart file is removed once every item has been written.
+pub struct AsyncKeysetItemReader<I, K> {
+    /// Fetches a page.
+    fetcher: AsyncKeysetPageFetcher<I, K>,
+    /// Returns the key of an item.
+    key: Option<AsyncKeyExtractor<I, K>>,
+    /// The number of items of a page.
+    page_size: usize,
+    /// Records the key of the last item written.
+    restart: Option<RestartFile>,
+    /// The task fetching the next page.
+    fetch: Option<JoinHandle<Vec<I>>>,
+    /// The items of the current page not read yet.
+    page: vec::IntoIter<I>,
+    /// The key the next page follows.
+    next_key: Option<K>,
+    /// The key of the last item read.
+    last_key: Option<K>,
+    /// The keys of the items read but not written yet, when there is a restart file.
+    unwritten: VecDeque<K>,
+    /// The number of items read since the reader was opened.
+    read: u64,
+    /// The number of items written when the reader was last checkpointed.
+    written: u64,
+    /// Whether every page has been read.
+    exhausted: bool,
+}
+
+impl<I, K> AsyncKeysetItemReader<I, K>
+where
+    I: Send + 'static,
+    K: Clone,
+{
+    /// Returns the key of the last item read, or `None` before the first item.
+    pub fn last_key(&self) -> Option<&K> {
+        self.last_key.as_ref()
+    }
+
+    /// Starts fetching the next page.
+    fn request(&mut self) {
+        let fetch = (self.fetcher)(self.next_key.clone(), self.page_size);
+        self.fetch = Some(tokio::spawn(fetch));
+    }
+}
+
+#[async_trait]
+impl<I, K> AsyncItemReader<I> for AsyncKeysetItemReader<I, K>
+where
+    I: Send + 'static,
+    K: Clone + Display + FromStr + Send + 'static,
+{
+    async fn open(&mut self) {
+        // Only a final line break is ignored, as the spaces around a key may be part of it.
+        self.next_key = self.restart.as_ref().and_then(RestartFile::load).map(|key| {
+            key.strip_suffix('\n').unwrap_or(&key).parse()
+                .unwrap_or_else(|_| panic!("Invalid key {:?} in restart file", key))
+        });
+
+        self.page = Vec::new().into_iter();
+        self.last_key = None;
+        self.unwritten.clear();
+        self.read = 0;
+        self.written = 0;
+        self.exhausted = false;
+        self.request();
+    }
+
+    async fn read(&mut self) -> Option<I> {
+        loop {
+            if let Some(item) = self.page.next() {
+                let key = self.key.as_ref().unwrap();
+                let item_key = key(&item);
+                if self.restart.is_some() {
+                    self.unwritten.push_back(item_key.clone());
+                }
+                self.last_key = Some(item_key);
+                self.read += 1;
+                return Some(item);
+            }
+
+            let Some(fetch) = self.fetch.take() else {
+                self.exhausted = true;
+                return None;
+            };
+
+            let page = receive(fetch).await;
+            let after = self.next_key.take();
+            self.next_key = page.last().map(self.key.as_ref().unwrap()).or(after);
+            if page.len() >= self.page_size {
+                self.request();
+            }
+            self.page = page.into_iter();
+        }
+    }
+
+    async fn checkpoint(&mut self, written: u64) {
+        let Some(restart) = &self.restart else {
+            return;
+        };
+
+        if self.exhausted && written == self.read {
+            restart.clear();
+            return;
+        }
+
+        let mut last_written = None;
+        while self.written < written {
+            last_written = self.unwritten.pop_front();
+            self.written += 1;
+        }
+        if let Some(key) = last_written {
+            restart.save(&key.to_string());
+        }
+    }
+
+    async fn close(&mut self) {
+        if let Some(fetch) = self.fetch.take() {
+            fetch.abort();
+            let _ = fetch.await;
+        }
+        self.page = Vec::new().into_iter();
+    }
+}
+
+/// A builder struct for constructing asynchronous keyset item readers.
+pub struct AsyncKeysetItemReaderBuilder<I, K> {
+    /// The reader being constructed.
+    reader: AsyncKeysetItemReader<I, K>,
+}
+
+impl<I, K> AsyncKeysetItemReaderBuilder<I, K>
+where
+    I: Send + 'static,
+    K: Clone + Display + FromStr + Send + 'static,
+{
+    /// Initializes a new builder reading the pages fetched by the given function, 100 items at a
+    /// time.
+    ///
+    /// # Parameters
+    ///
+    /// - `fetcher`: The function fetching a page.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(fetcher: AsyncKeysetPageFetcher<I, K>) -> Self {
+        AsyncKeysetItemReaderBuilder {
+            reader: AsyncKeysetItemReader {
+                fetcher,
+                key: None,
+                page_size: DEFAULT_PAGE_SIZE,
+                restart: None,
+                fetch: None,
+                page: Vec::new().into_iter(),
+                next_key: None,
+                last_key: None,
+                unwritten: VecDeque::new(),
+                read: 0,
+                written: 0,
+                exhausted: false,
+            }
+        }
+    }
+
+    /// Sets the function returning the key of an item.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: The key function.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn key(self, key: AsyncKeyExtractor<I, K>) -> Self {
+        AsyncKeysetItemReaderBuilder {
+            reader: AsyncKeysetItemReader {
+                key: Some(key),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the number of items of a page.
+    ///
+    /// # Parameters
+    ///
+    /// - `page_size`: The number of items of a page.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn page_size(self, page_size: usize) -> Self {
+        AsyncKeysetItemReaderBuilder {
+            reader: AsyncKeysetItemReader {
+                page_size,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the file recording the key of the last item written, so that a new run resumes after
+    /// that key.
+    ///
+    /// # Parameters
+    ///
+    /// - `restart_file`: The path of the restart file.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn restart_file(self, restart_file: impl Into<PathBuf>) -> Self {
+        AsyncKeysetItemReaderBuilder {
+            reader: AsyncKeysetItemReader {
+                restart: Some(RestartFile::new(restart_file.into())),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.reader.key.is_none() {
+            panic!("Key is required");
+        }
+
+        if self.reader.page_size == 0 {
+            panic!("Page size must be greater than zero");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// The configured reader.
+    pub fn build(self) -> AsyncKeysetItemReader<I, K> {
+        self.validate().reader
+    }
+}
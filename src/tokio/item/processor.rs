@@ -0,0 +1,261 @@
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+
+use crate::core::item::Validator;
+use crate::tokio::item::{AsyncItemProcessor, AsyncItemWriter};
+
+/// Expands an item into any number of outputs.
+pub type AsyncExpander<I, O> = Box<dyn Fn(I) -> BoxFuture<'static, Vec<O>> + Send + Sync>;
+
+/// Transforms an item with a processor, then its output with the next one.
+struct AsyncChain<I, M, O> {
+    /// The first processor.
+    first: Box<dyn AsyncItemProcessor<I, M>>,
+    /// The processor of the outputs of the first one.
+    second: Box<dyn AsyncItemProcessor<M, O>>,
+}
+
+#[async_trait]
+impl<I: Send + 'static, M: Send + 'static, O: Send + 'static> AsyncItemProcessor<I, O> for AsyncChain<I, M, O> {
+    async fn open(&self) {
+        self.first.open().await;
+        self.second.open().await;
+    }
+
+    async fn process(&self, item: I) -> O {
+        let intermediate = self.first.process(item).await;
+        self.second.process(intermediate).await
+    }
+
+    async fn process_or_skip(&self, item: I) -> Result<O, String> {
+        let intermediate = self.first.process_or_skip(item).await?;
+        self.second.process_or_skip(intermediate).await
+    }
+
+    async fn close(&self) {
+        self.first.close().await;
+        self.second.close().await;
+    }
+}
+
+/// Runs an item through a pipeline of asynchronous processors, e.g. parse, validate, enrich and
+/// map, each one transforming the output of the previous one.
+///
+/// Every processor is opened and closed with the chain. A processor failing fails the whole chain
+/// for that item, so the step handles the failure, notifies its listeners and counts it as if a
+/// single processor had failed. Likewise, a processor skipping an item skips it for the chain.
+pub struct AsyncChainedItemProcessor<I, O> {
+    /// The processors, chained into one.
+    processor: Box<dyn AsyncItemProcessor<I, O>>,
+}
+
+#[async_trait]
+impl<I: Send + 'static, O: Send + 'static> AsyncItemProcessor<I, O> for AsyncChainedItemProcessor<I, O> {
+    async fn open(&self) {
+        self.processor.open().await;
+    }
+
+    async fn process(&self, item: I) -> O {
+        self.processor.process(item).await
+    }
+
+    async fn process_or_skip(&self, item: I) -> Result<O, String> {
+        self.processor.process_or_skip(item).await
+    }
+
+    async fn close(&self) {
+        self.processor.close().await;
+    }
+}
+
+/// A builder struct for constructing asynchronous chained item processors.
+pub struct AsyncChainedItemProcessorBuilder<I, O> {
+    /// The processor being constructed.
+    processor: AsyncChainedItemProcessor<I, O>,
+}
+
+impl<I: Send + 'static, O: Send + 'static> AsyncChainedItemProcessorBuilder<I, O> {
+    /// Initializes a new builder starting the chain with the given processor.
+    ///
+    /// # Parameters
+    ///
+    /// - `processor`: The first processor.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(processor: Box<dyn AsyncItemProcessor<I, O>>) -> Self {
+        AsyncChainedItemProcessorBuilder {
+            processor: AsyncChainedItemProcessor {
+                processor,
+            }
+        }
+    }
+
+    /// Appends a processor transforming the outputs of the chain.
+    ///
+    /// # Parameters
+    ///
+    /// - `next`: The next processor, which may change the output type of the chain.
+    ///
+    /// # Returns `AsyncChainedItemProcessorBuilder<I, P>`
+    ///
+    /// The modified builder instance.
+    pub fn then<P: Send + 'static>(self, next: Box<dyn AsyncItemProcessor<O, P>>) -> AsyncChainedItemProcessorBuilder<I, P> {
+        AsyncChainedItemProcessorBuilder {
+            processor: AsyncChainedItemProcessor {
+                processor: Box::new(AsyncChain {
+                    first: self.processor.processor,
+                    second: next,
+                }),
+            }
+        }
+    }
+
+    /// Builds and returns the configured processor.
+    ///
+    /// # Returns
+    ///
+    /// The configured processor.
+    pub fn build(self) -> AsyncChainedItemProcessor<I, O> {
+        self.processor
+    }
+}
+
+/// Passes valid items through unchanged and fails on invalid ones, or skips them.
+///
+/// By default, an invalid item panics with the reason given by the validator, so the step handles
+/// it like any other processing error, e.g. skips it when the step is throw tolerant, which still
+/// fails the step. With `skip_invalid`, the step skips it instead, counts it as skipped and
+/// carries on.
+pub struct AsyncValidatingItemProcessor<T> {
+    /// Checks an item.
+    validator: Box<dyn Validator<T> + Send + Sync>,
+    /// Whether an invalid item is skipped rather than failing the step.
+    skip_invalid: bool,
+}
+
+impl<T> AsyncValidatingItemProcessor<T> {
+    /// Creates a processor checking the items with the given validator.
+    ///
+    /// # Parameters
+    ///
+    /// - `validator`: The validator, shared by the workers of the step.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The processor.
+    pub fn new(validator: Box<dyn Validator<T> + Send + Sync>) -> Self {
+        AsyncValidatingItemProcessor { validator, skip_invalid: false }
+    }
+
+    /// Skips the invalid items instead of failing on them.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified processor.
+    pub fn skip_invalid(self) -> Self {
+        AsyncValidatingItemProcessor {
+            skip_invalid: true,
+            ..self
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Send + 'static> AsyncItemProcessor<T, T> for AsyncValidatingItemProcessor<T> {
+    async fn process(&self, item: T) -> T {
+        if let Err(reason) = self.validator.validate(&item) {
+            panic!("Invalid item: {}", reason);
+        }
+
+        item
+    }
+
+    async fn process_or_skip(&self, item: T) -> Result<T, String> {
+        match self.validator.validate(&item) {
+            Ok(()) => Ok(item),
+            Err(reason) if self.skip_invalid => Err(format!("Invalid item: {}", reason)),
+            Err(reason) => panic!("Invalid item: {}", reason),
+        }
+    }
+}
+
+/// Expands each item into any number of outputs asynchronously, e.g. an order into its lines.
+///
+/// The outputs of an item are processed as one `Vec`, so the writer of the step is an
+/// `AsyncFlattenItemWriter`. The chunk size counts the items read, not the outputs; a weigher
+/// returning the length of the outputs bounds the chunks by outputs instead. The items written
+/// are counted by outputs.
+pub struct AsyncFlatMapItemProcessor<I, O> {
+    /// Expands an item.
+    expander: AsyncExpander<I, O>,
+}
+
+impl<I, O> AsyncFlatMapItemProcessor<I, O> {
+    /// Creates a processor expanding the items with the given function.
+    ///
+    /// # Parameters
+    ///
+    /// - `expander`: The function returning the outputs of an item.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The processor.
+    pub fn new(expander: AsyncExpander<I, O>) -> Self {
+        AsyncFlatMapItemProcessor { expander }
+    }
+}
+
+#[async_trait]
+impl<I: Send + 'static, O: Send + 'static> AsyncItemProcessor<I, Vec<O>> for AsyncFlatMapItemProcessor<I, O> {
+    async fn process(&self, item: I) -> Vec<O> {
+        (self.expander)(item).await
+    }
+}
+
+/// Writes the outputs of a flat-map processor as a single chunk with the given writer.
+///
+/// A chunk whose items all expanded into nothing is not written. The step counts the outputs of a
+/// chunk as its items written.
+pub struct AsyncFlattenItemWriter<O> {
+    /// The writer of the outputs.
+    writer: Box<dyn AsyncItemWriter<O>>,
+}
+
+impl<O> AsyncFlattenItemWriter<O> {
+    /// Creates a writer flattening the chunks for the given writer.
+    ///
+    /// # Parameters
+    ///
+    /// - `writer`: The writer of the outputs.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The writer.
+    pub fn new(writer: Box<dyn AsyncItemWriter<O>>) -> Self {
+        AsyncFlattenItemWriter { writer }
+    }
+}
+
+#[async_trait]
+impl<O: Send + 'static> AsyncItemWriter<Vec<O>> for AsyncFlattenItemWriter<O> {
+    async fn open(&self) {
+        self.writer.open().await;
+    }
+
+    async fn write(&self, items: Vec<Vec<O>>) {
+        let outputs: Vec<O> = items.into_iter().flatten().collect();
+        if !outputs.is_empty() {
+            self.writer.write(outputs).await;
+        }
+    }
+
+    fn item_count(&self, items: &[Vec<O>]) -> usize {
+        items.iter().map(Vec::len).sum()
+    }
+
+    async fn close(&self) {
+        self.writer.close().await;
+    }
+}
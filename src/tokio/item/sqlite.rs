@@ -0,0 +1,388 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use async_trait::async_trait;
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use tokio::sync::mpsc::{self, Receiver};
+
+use crate::core::sqlite::{insert_statement, open_connection, stream_rows, write_rows, RowMapper, SharedRowMapper, ROWS_AHEAD};
+use crate::tokio::item::{AsyncItemReader, AsyncItemWriter};
+
+/// Reads the rows of a SQLite query asynchronously, mapping each of them to `T`.
+///
+/// The query runs on its own thread when the step opens the reader, and maps a bounded number of
+/// rows ahead of the step, so that a large result is streamed rather than loaded. A query or a row
+/// that cannot be read or mapped panics, which fails the step.
+pub struct AsyncSqliteItemReader<T> {
+    /// The path of the database.
+    path: PathBuf,
+    /// The query selecting the rows.
+    query: String,
+    /// The values bound to the parameters of the query.
+    parameters: Vec<Value>,
+    /// Maps a row to an item.
+    mapper: Option<SharedRowMapper<T>>,
+    /// Whether the database is switched to write-ahead logging.
+    wal: bool,
+    /// Receives the items mapped by the query thread.
+    receiver: Option<Receiver<rusqlite::Result<T>>>,
+}
+
+#[async_trait]
+impl<T: Send + 'static> AsyncItemReader<T> for AsyncSqliteItemReader<T> {
+    async fn open(&mut self) {
+        let (sender, receiver) = mpsc::channel(ROWS_AHEAD);
+        let path = self.path.clone();
+        let wal = self.wal;
+        let query = self.query.clone();
+        let parameters = self.parameters.clone();
+        let mapper = Arc::clone(self.mapper.as_ref().unwrap());
+
+        thread::spawn(move || {
+            stream_rows(&path, wal, &query, &parameters, mapper.as_ref(), |item| sender.blocking_send(item).is_ok());
+        });
+        self.receiver = Some(receiver);
+    }
+
+    async fn read(&mut self) -> Option<T> {
+        let receiver = self.receiver.as_mut().expect("SQLite reader is not open");
+
+        match receiver.recv().await {
+            Some(Ok(item)) => Some(item),
+            Some(Err(err)) => panic!("Error reading SQLite database {}: {}", self.path.display(), err),
+            None => None,
+        }
+    }
+
+    async fn close(&mut self) {
+        self.receiver = None;
+    }
+}
+
+/// A builder struct for constructing asynchronous SQLite item readers.
+pub struct AsyncSqliteItemReaderBuilder<T> {
+    /// The reader being constructed.
+    reader: AsyncSqliteItemReader<T>,
+}
+
+impl<T: Send + 'static> AsyncSqliteItemReaderBuilder<T> {
+    /// Initializes a new builder reading the given database.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the database.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        AsyncSqliteItemReaderBuilder {
+            reader: AsyncSqliteItemReader {
+                path: path.into(),
+                query: String::new(),
+                parameters: Vec::new(),
+                mapper: None,
+                wal: false,
+                receiver: None,
+            }
+        }
+    }
+
+    /// Sets the query selecting the rows.
+    ///
+    /// # Parameters
+    ///
+    /// - `query`: The query, e.g. `SELECT id, make FROM cars WHERE year >= ?1`.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn query(self, query: impl Into<String>) -> Self {
+        AsyncSqliteItemReaderBuilder {
+            reader: AsyncSqliteItemReader {
+                query: query.into(),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the values bound to the parameters of the query, in order.
+    ///
+    /// # Parameters
+    ///
+    /// - `parameters`: The values of the parameters.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn parameters(self, parameters: Vec<Value>) -> Self {
+        AsyncSqliteItemReaderBuilder {
+            reader: AsyncSqliteItemReader {
+                parameters,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Sets the function mapping a row to an item.
+    ///
+    /// # Parameters
+    ///
+    /// - `mapper`: The mapping function.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn mapper(self, mapper: RowMapper<T>) -> Self {
+        AsyncSqliteItemReaderBuilder {
+            reader: AsyncSqliteItemReader {
+                mapper: Some(Arc::from(mapper)),
+                ..self.reader
+            }
+        }
+    }
+
+    /// Switches the database to write-ahead logging when the reader opens it, so that a writer of
+    /// the same step can commit its chunks while the query is running. The journal mode is stored
+    /// in the database file and stays in effect for every later connection, until it is changed
+    /// back.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn wal(self) -> Self {
+        AsyncSqliteItemReaderBuilder {
+            reader: AsyncSqliteItemReader {
+                wal: true,
+                ..self.reader
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.reader.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.reader.query.is_empty() {
+            panic!("Query is required");
+        }
+
+        if self.reader.mapper.is_none() {
+            panic!("Mapper is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured reader.
+    ///
+    /// # Returns
+    ///
+    /// The configured reader.
+    pub fn build(self) -> AsyncSqliteItemReader<T> {
+        self.validate().reader
+    }
+}
+
+/// Binds an item to the parameters of the statement writing it, in order.
+pub type AsyncParameterBinder<O> = Box<dyn Fn(&O) -> Vec<Value> + Send + Sync>;
+
+/// Writes the items of each chunk to a SQLite database asynchronously, executing a statement per
+/// item.
+///
+/// The database is opened when the step opens the writer. The workers of the step write their
+/// chunks one at a time, on the blocking threads of the runtime. Each chunk is written in a single
+/// transaction, so a chunk whose write fails leaves no row behind and can be written again. A step
+/// reading and writing the same database needs write-ahead logging, see `wal` on the builders.
+pub struct AsyncSqliteItemWriter<O> {
+    /// The path of the database.
+    path: PathBuf,
+    /// The statement writing an item.
+    statement: Option<String>,
+    /// Binds an item to the parameters of the statement.
+    binder: Option<AsyncParameterBinder<O>>,
+    /// Whether the database is switched to write-ahead logging.
+    wal: bool,
+    /// The open database, shared by the workers of the step.
+    connection: Arc<Mutex<Option<Connection>>>,
+}
+
+#[async_trait]
+impl<O: Send + 'static> AsyncItemWriter<O> for AsyncSqliteItemWriter<O> {
+    async fn open(&self) {
+        let connection = open_connection(&self.path, self.wal)
+            .unwrap_or_else(|err| panic!("Error opening SQLite database {}: {}", self.path.display(), err));
+        *self.connection.lock().unwrap() = Some(connection);
+    }
+
+    async fn write(&self, items: Vec<O>) {
+        let binder = self.binder.as_ref().unwrap();
+        let rows: Vec<Vec<Value>> = items.iter().map(binder).collect();
+        let statement = self.statement.clone().unwrap();
+        let connection = Arc::clone(&self.connection);
+
+        let written = tokio::task::spawn_blocking(move || {
+            let mut connection = connection.lock().unwrap();
+            let connection = connection.as_mut().expect("SQLite writer is not open");
+            write_rows(connection, &statement, rows)
+        }).await.expect("SQLite write task failed");
+
+        written.unwrap_or_else(|err| panic!("Error writing SQLite database {}: {}", self.path.display(), err));
+    }
+
+    async fn close(&self) {
+        *self.connection.lock().unwrap() = None;
+    }
+}
+
+/// A builder struct for constructing asynchronous SQLite item writers.
+pub struct AsyncSqliteItemWriterBuilder<O> {
+    /// The writer being constructed.
+    writer: AsyncSqliteItemWriter<O>,
+}
+
+impl<O: Send + 'static> AsyncSqliteItemWriterBuilder<O> {
+    /// Initializes a new builder writing the given database.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the database.
+    ///
+    /// # Returns `Self`
+    ///
+    /// A new builder instance.
+    pub fn get(path: impl Into<PathBuf>) -> Self {
+        AsyncSqliteItemWriterBuilder {
+            writer: AsyncSqliteItemWriter {
+                path: path.into(),
+                statement: None,
+                binder: None,
+                wal: false,
+                connection: Arc::new(Mutex::new(None)),
+            }
+        }
+    }
+
+    /// Sets the statement writing an item.
+    ///
+    /// # Parameters
+    ///
+    /// - `statement`: The statement, e.g. `INSERT INTO cars (id, make) VALUES (?1, ?2)`.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn sql(self, statement: impl Into<String>) -> Self {
+        AsyncSqliteItemWriterBuilder {
+            writer: AsyncSqliteItemWriter {
+                statement: Some(statement.into()),
+                ..self.writer
+            }
+        }
+    }
+
+    /// Inserts each item as a row of a table.
+    ///
+    /// # Parameters
+    ///
+    /// - `table`: The table to write to.
+    /// - `columns`: The columns written, in the order of the values of the binder.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn insert(self, table: &str, columns: &[&str]) -> Self {
+        self.sql(insert_statement(table, columns, &[]))
+    }
+
+    /// Inserts each item as a row of a table, or updates the row with the same key.
+    ///
+    /// # Parameters
+    ///
+    /// - `table`: The table to write to.
+    /// - `columns`: The columns written, in the order of the values of the binder.
+    /// - `keys`: The columns of a unique index identifying a row, e.g. the primary key.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn upsert(self, table: &str, columns: &[&str], keys: &[&str]) -> Self {
+        if keys.is_empty() {
+            panic!("At least one key column is required");
+        }
+
+        self.sql(insert_statement(table, columns, keys))
+    }
+
+    /// Sets the function binding an item to the parameters of the statement.
+    ///
+    /// # Parameters
+    ///
+    /// - `binder`: The binding function.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn binder(self, binder: AsyncParameterBinder<O>) -> Self {
+        AsyncSqliteItemWriterBuilder {
+            writer: AsyncSqliteItemWriter {
+                binder: Some(binder),
+                ..self.writer
+            }
+        }
+    }
+
+    /// Switches the database to write-ahead logging when the writer opens it, as needed when the
+    /// reader of the step queries the same database. The journal mode is stored in the database
+    /// file and stays in effect for every later connection, until it is changed back.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    pub fn wal(self) -> Self {
+        AsyncSqliteItemWriterBuilder {
+            writer: AsyncSqliteItemWriter {
+                wal: true,
+                ..self.writer
+            }
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The validated builder instance.
+    pub fn validate(self) -> Self {
+        if self.writer.path.as_os_str().is_empty() {
+            panic!("Path is required");
+        }
+
+        if self.writer.statement.is_none() {
+            panic!("Statement is required");
+        }
+
+        if self.writer.binder.is_none() {
+            panic!("Binder is required");
+        }
+
+        self
+    }
+
+    /// Builds and returns the configured writer.
+    ///
+    /// # Returns
+    ///
+    /// The configured writer.
+    pub fn build(self) -> AsyncSqliteItemWriter<O> {
+        self.validate().writer
+    }
+}
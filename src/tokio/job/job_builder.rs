@@ -1,4 +1,10 @@
+use std::sync::Arc;
 use crate::tokio::job::AsyncJob;
+use crate::core::dag::DagScheduler;
+use crate::core::flow::Flow;
+use crate::core::progress::ProgressEvents;
+use crate::sync::step::SyncStep;
+use crate::tokio::listener::AsyncJobExecutionListener;
 use crate::tokio::step::AsyncStep;
 
 /// A trait for building asynchronous jobs.
@@ -21,6 +27,17 @@ pub trait AsyncJobBuilderTrait {
     /// Returns a modified builder instance.
     fn step(self, step: AsyncStep) -> Self;
 
+    /// Adds a synchronous step to the job, executed on the blocking thread pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - The synchronous step to add.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn sync_step(self, step: SyncStep) -> Self;
+
     /// Configures the job to run with multiple tasks.
     ///
     /// # Arguments
@@ -32,6 +49,42 @@ pub trait AsyncJobBuilderTrait {
     /// Returns a modified builder instance.
     fn multi_tasks(self, max_tasks: usize) -> Self;
 
+    /// Sets the transitions between the steps of the job.
+    ///
+    /// The steps then run sequentially, starting with the first step of the flow and following the
+    /// transition matching the exit status of each step.
+    ///
+    /// # Arguments
+    ///
+    /// * `flow` - The flow of the job.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn flow(self, flow: Flow) -> Self;
+
+    /// Registers a listener notified before and after the job runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn listener(self, listener: Arc<dyn AsyncJobExecutionListener>) -> Self;
+
+    /// Sets the broadcaster the progress events of the job are emitted to.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - The broadcaster, subscribed to before the job runs.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn progress(self, progress: ProgressEvents) -> Self;
+
     /// Initializes a new builder instance with the given name.
     ///
     /// # Arguments
@@ -60,10 +113,36 @@ pub struct AsyncJobBuilder {
 impl AsyncJobBuilderTrait for AsyncJobBuilder {
     /// Validates the builder configuration by ensuring at least one step is added to the job.
     ///
+    /// # Panics
+    ///
+    /// Panics if the flow or the step dependencies reference unknown steps, or if the dependencies
+    /// form a cycle.
+    ///
     /// # Returns `Self`
     ///
     /// Returns a modified builder instance if validation succeeds.
     fn validate(self) -> Self {
+        let has_dependencies = self.job.steps.iter().any(|step| !step.depends_on.is_empty());
+
+        if let Some(flow) = &self.job.flow {
+            if has_dependencies {
+                panic!("A job cannot combine a flow with step dependencies");
+            }
+            let step_names: Vec<&str> = self.job.steps.iter().map(|step| step.name.as_str()).collect();
+            flow.validate(&step_names);
+        }
+
+        if has_dependencies {
+            let dependencies: Vec<(&str, &[String])> = self.job.steps.iter()
+                .map(|step| (step.name.as_str(), step.depends_on.as_slice()))
+                .collect();
+            DagScheduler::new(&dependencies);
+        }
+
+        if self.job.max_tasks == Some(0) {
+            panic!("The maximum number of tasks must be greater than zero");
+        }
+
         self
     }
 
@@ -81,6 +160,20 @@ impl AsyncJobBuilderTrait for AsyncJobBuilder {
         self
     }
 
+    /// Adds a synchronous step to the job, executed on the blocking thread pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - The synchronous step to add.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn sync_step(mut self, step: SyncStep) -> Self {
+        self.job.steps.push(AsyncStep::from(step));
+        self
+    }
+
     /// Configures the job to run with multiple tasks.
     ///
     /// # Arguments
@@ -100,6 +193,56 @@ impl AsyncJobBuilderTrait for AsyncJobBuilder {
         }
     }
 
+    /// Sets the transitions between the steps of the job.
+    ///
+    /// # Arguments
+    ///
+    /// * `flow` - The flow of the job.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn flow(self, flow: Flow) -> Self {
+        AsyncJobBuilder {
+            job: AsyncJob {
+                flow: Some(flow),
+                ..self.job
+            }
+        }
+    }
+
+    /// Registers a listener notified before and after the job runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn listener(mut self, listener: Arc<dyn AsyncJobExecutionListener>) -> Self {
+        self.job.listeners.push(listener);
+        self
+    }
+
+    /// Sets the broadcaster the progress events of the job are emitted to.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - The broadcaster, subscribed to before the job runs.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn progress(self, progress: ProgressEvents) -> Self {
+        AsyncJobBuilder {
+            job: AsyncJob {
+                progress,
+                ..self.job
+            }
+        }
+    }
+
     /// Initializes a new builder instance with the given name.
     ///
     /// # Arguments
@@ -119,6 +262,9 @@ impl AsyncJobBuilderTrait for AsyncJobBuilder {
                 steps: Vec::new(),
                 multi_threaded: None,
                 max_tasks: None,
+                flow: None,
+                listeners: Vec::new(),
+                progress: ProgressEvents::new(),
             }
         }
     }
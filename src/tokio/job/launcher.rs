@@ -0,0 +1,188 @@
+use std::thread;
+
+use log::info;
+use tokio::runtime::{Builder, Handle, RuntimeFlavor};
+
+use crate::core::job::JobStatus;
+use crate::tokio::job::AsyncJob;
+use crate::tokio::step::AsyncStepRunner;
+
+/// The kind of runtime created by a `JobLauncher`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LauncherFlavor {
+    /// Runs the job on the thread that launches it.
+    CurrentThread,
+    /// Runs the job on a pool of worker threads.
+    MultiThread,
+}
+
+/// Runs asynchronous jobs to completion from synchronous code.
+///
+/// The launcher either creates a runtime from its configuration for each job or reuses the runtime
+/// of a given handle. It can also be called from within a runtime: the job then runs on a dedicated
+/// thread, so it still gets the configured runtime, and on a multi-threaded runtime the calling
+/// worker is turned into a blocking one while it waits.
+#[derive(Debug, Clone)]
+pub struct JobLauncher {
+    /// The kind of runtime to create.
+    flavor: LauncherFlavor,
+    /// The number of worker threads of a multi-threaded runtime.
+    worker_threads: Option<usize>,
+    /// The name given to the threads of the runtime.
+    thread_name: Option<String>,
+    /// The handle of an existing runtime used instead of creating one.
+    handle: Option<Handle>,
+}
+
+impl JobLauncher {
+    /// Creates a launcher running jobs on a current-thread runtime.
+    ///
+    /// # Returns `JobLauncher`
+    ///
+    /// Returns a new launcher instance.
+    pub fn current_thread() -> Self {
+        JobLauncher {
+            flavor: LauncherFlavor::CurrentThread,
+            worker_threads: None,
+            thread_name: None,
+            handle: None,
+        }
+    }
+
+    /// Creates a launcher running jobs on a multi-threaded runtime.
+    ///
+    /// # Returns `JobLauncher`
+    ///
+    /// Returns a new launcher instance.
+    pub fn multi_thread() -> Self {
+        JobLauncher {
+            flavor: LauncherFlavor::MultiThread,
+            ..JobLauncher::current_thread()
+        }
+    }
+
+    /// Creates a launcher running jobs on the multi-threaded runtime of the given handle.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the runtime to use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handle belongs to a current-thread runtime, which only drives its timers and
+    /// IO from its own `block_on` and would stall the jobs launched from another thread.
+    ///
+    /// # Returns `JobLauncher`
+    ///
+    /// Returns a new launcher instance.
+    pub fn from_handle(handle: Handle) -> Self {
+        if handle.runtime_flavor() == RuntimeFlavor::CurrentThread {
+            panic!("Launcher handle must belong to a multi-threaded runtime");
+        }
+
+        JobLauncher {
+            handle: Some(handle),
+            ..JobLauncher::multi_thread()
+        }
+    }
+
+    /// Sets the number of worker threads of a multi-threaded runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `worker_threads` - The number of worker threads.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified launcher instance.
+    pub fn worker_threads(self, worker_threads: usize) -> Self {
+        JobLauncher {
+            worker_threads: Some(worker_threads),
+            ..self
+        }
+    }
+
+    /// Sets the name given to the threads of the runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_name` - The thread name.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified launcher instance.
+    pub fn thread_name(self, thread_name: String) -> Self {
+        JobLauncher {
+            thread_name: Some(thread_name),
+            ..self
+        }
+    }
+
+    /// Returns the kind of runtime the jobs run on.
+    pub fn flavor(&self) -> LauncherFlavor {
+        self.flavor
+    }
+
+    /// Runs the job to completion, blocking the current thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The asynchronous job to run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime cannot be created.
+    ///
+    /// # Returns `JobStatus`
+    ///
+    /// Returns the status of the job.
+    pub fn run(&self, job: AsyncJob) -> JobStatus {
+        let current = match Handle::try_current() {
+            Ok(current) => current,
+            Err(_) => return self.block_on(job),
+        };
+
+        // A runtime cannot be created or blocked on from one of its own threads.
+        info!("Launching job {} on a dedicated thread", job.name);
+        let launch = move || thread::scope(|scope| {
+            scope.spawn(move || self.block_on(job)).join().unwrap()
+        });
+
+        if current.runtime_flavor() == RuntimeFlavor::MultiThread {
+            tokio::task::block_in_place(launch)
+        } else {
+            launch()
+        }
+    }
+
+    /// Runs the job on the configured runtime from a thread outside of any runtime.
+    fn block_on(&self, job: AsyncJob) -> JobStatus {
+        if let Some(handle) = &self.handle {
+            return handle.block_on(job.run());
+        }
+
+        let mut builder = match self.flavor {
+            LauncherFlavor::CurrentThread => Builder::new_current_thread(),
+            LauncherFlavor::MultiThread => Builder::new_multi_thread(),
+        };
+        builder.enable_all();
+
+        if let (LauncherFlavor::MultiThread, Some(worker_threads)) = (self.flavor, self.worker_threads) {
+            builder.worker_threads(worker_threads);
+        }
+
+        if let Some(thread_name) = &self.thread_name {
+            builder.thread_name(thread_name);
+        }
+
+        let runtime = builder.build().expect("Error building the job runtime");
+        runtime.block_on(job.run())
+    }
+}
+
+impl Default for JobLauncher {
+    /// Creates a launcher running jobs on a multi-threaded runtime.
+    fn default() -> Self {
+        JobLauncher::multi_thread()
+    }
+}
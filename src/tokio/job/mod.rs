@@ -1,15 +1,24 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_trait::async_trait;
 use futures::lock::Mutex;
 use log::{error, info};
 use tokio::task::JoinSet;
 
-use crate::core::job::{JobStatus, now_time};
-use crate::core::step::StepStatus;
+use crate::core::dag::DagScheduler;
+use crate::core::flow::{Flow, FlowOutcome};
+use crate::core::progress::{ProgressEvent, ProgressEvents};
+use crate::core::recorder;
+use crate::core::job::{JobContext, JobStatus, now_time};
+use crate::core::step::{SKIPPED, StepStatus};
+use crate::core::trace::TraceSpan;
+use crate::tokio::listener::AsyncJobExecutionListener;
 use crate::tokio::step::{AsyncStep, AsyncStepRunner, Decider};
 
 pub mod job_builder;
+pub mod launcher;
 mod utils;
 
 /// A struct representing an asynchronous job.
@@ -26,18 +35,72 @@ pub struct AsyncJob {
     pub multi_threaded: Option<bool>,
     /// The maximum number of tasks allowed for multithreaded execution.
     pub max_tasks: Option<usize>,
+    /// The transitions between steps, run sequentially in place of the list of steps.
+    pub flow: Option<Flow>,
+    /// The listeners notified before and after the job runs.
+    pub listeners: Vec<Arc<dyn AsyncJobExecutionListener>>,
+    /// The broadcaster the progress events of the job are emitted to.
+    pub progress: ProgressEvents,
 }
 
 #[async_trait]
 impl AsyncStepRunner<JobStatus> for AsyncJob {
-    /// Executes the asynchronous job and returns its result.
+    /// Executes the asynchronous job, notifying its listeners, and returns its result.
     async fn run(mut self) -> JobStatus {
+        let started = Instant::now();
+        let span = TraceSpan::job(&self.name);
+        let name = self.name.clone();
+        let listeners = std::mem::take(&mut self.listeners);
+        let progress = self.progress.clone();
+        for step in &self.steps {
+            step.join_job(name.clone(), progress.clone());
+        }
+        progress.emit(ProgressEvent::JobStarted { job_name: name.clone(), step_count: self.steps.len() });
+        let job_status = span.clone().instrument(async move {
+            if !listeners.is_empty() {
+                let context = JobContext::new(self.name.clone(), self.steps.iter().map(|step| step.name.clone()).collect());
+                for listener in &listeners {
+                    listener.before_job(&context).await;
+                }
+            }
+            let mut job_status = self.execute().await;
+            for listener in listeners.iter().rev() {
+                listener.after_job(&mut job_status).await;
+            }
+            job_status
+        }).await;
+        span.finish(job_status.status.is_ok());
+        recorder::job_finished(&name, job_status.status.is_ok(), started.elapsed());
+        progress.emit(ProgressEvent::JobFinished {
+            job_name: name,
+            succeeded: job_status.status.is_ok(),
+            duration: started.elapsed(),
+        });
+        job_status
+    }
+}
+
+impl AsyncJob {
+    /// Executes the steps of the job and returns its result.
+    async fn execute(self) -> JobStatus {
         let multi_threaded = self.multi_threaded.unwrap_or(false);
         let mut steps = self.steps;
         let name = self.name.clone();
         let steps_len = steps.len().clone();
         let start_time = now_time();
         let mut steps_status_vec: Vec<StepStatus> = Vec::new();
+        let progress = self.progress;
+
+        if let Some(flow) = self.flow {
+            info!("Running job {} with flow", self.name);
+            return run_flow(name, flow, steps, &progress, start_time).await;
+        }
+
+        if steps.iter().any(|step| !step.depends_on.is_empty()) {
+            let max_tasks = if multi_threaded { self.max_tasks.unwrap_or(1) } else { 1 };
+            info!("Running job {} following step dependencies with {} tasks", self.name, max_tasks);
+            return run_dag(name, steps, max_tasks, &progress, start_time).await;
+        }
 
         if multi_threaded {
             info!("Running job {} with multi-threaded mode", self.name)
@@ -49,6 +112,7 @@ impl AsyncStepRunner<JobStatus> for AsyncJob {
             for step in steps {
                 if !step.decide().await {
                     info!("Skipping step {}", step.name);
+                    progress.emit(ProgressEvent::StepSkipped { step_name: step.name.clone() });
                     continue;
                 }
 
@@ -90,6 +154,7 @@ impl AsyncStepRunner<JobStatus> for AsyncJob {
                 for step in steps {
                     if !step.decide().await {
                         info!("Skipping step {}", step.name);
+                        progress.emit(ProgressEvent::StepSkipped { step_name: step.name.clone() });
                         continue;
                     }
                     let throw_tolerant = step.throw_tolerant.unwrap_or(false).clone();
@@ -150,5 +215,111 @@ impl AsyncStepRunner<JobStatus> for AsyncJob {
     }
 }
 
+/// Runs the steps of a job following the transitions of its flow.
+async fn run_flow(name: String, flow: Flow, steps: Vec<AsyncStep>, progress: &ProgressEvents, start_time: u128) -> JobStatus {
+    let mut steps: HashMap<String, AsyncStep> = steps.into_iter()
+        .map(|step| (step.name.clone(), step))
+        .collect();
+    let mut steps_status_vec: Vec<StepStatus> = Vec::new();
+    let mut current = flow.start().to_string();
+
+    let status = loop {
+        let Some(step) = steps.remove(&current) else {
+            let message = format!("Job {} failed, step {} has already been executed", name, current);
+            error!("{}", message);
+            break Err(message);
+        };
+        let throw_tolerant = step.throw_tolerant.unwrap_or(false);
+
+        let exit_status = if !step.decide().await {
+            info!("Skipping step {}", step.name);
+            progress.emit(ProgressEvent::StepSkipped { step_name: step.name.clone() });
+            String::from(SKIPPED)
+        } else {
+            let step_result = step.run().await;
+            utils::log_step(step_result.status.clone());
+            let exit_status = step_result.exit_status.clone();
+            steps_status_vec.push(step_result);
+            exit_status
+        };
+
+        match flow.resolve(&name, &current, &exit_status, throw_tolerant) {
+            FlowOutcome::Next(next) => current = next,
+            FlowOutcome::Finished(status) => break status,
+        }
+    };
+
+    JobStatus {
+        name,
+        start_time: Some(start_time),
+        end_time: Some(now_time()),
+        status,
+        steps_status: steps_status_vec,
+    }
+}
+
+/// Runs the steps of a job as soon as the steps they depend on complete, up to `max_tasks` at once.
+async fn run_dag(name: String, steps: Vec<AsyncStep>, max_tasks: usize, progress: &ProgressEvents, start_time: u128) -> JobStatus {
+    let names: Vec<String> = steps.iter().map(|step| step.name.clone()).collect();
+    let dependencies: Vec<(&str, &[String])> = steps.iter()
+        .map(|step| (step.name.as_str(), step.depends_on.as_slice()))
+        .collect();
+    let mut scheduler = DagScheduler::new(&dependencies);
+    let mut steps: Vec<Option<AsyncStep>> = steps.into_iter().map(Some).collect();
+    let mut join_set: JoinSet<(usize, bool, StepStatus)> = JoinSet::new();
+    let mut steps_status_vec: Vec<StepStatus> = Vec::new();
+    let mut failed = false;
+
+    loop {
+        while join_set.len() < max_tasks {
+            let Some(index) = scheduler.next_ready() else {
+                break;
+            };
+            let step = steps[index].take().unwrap();
+            if !step.decide().await {
+                info!("Skipping step {}", step.name);
+                progress.emit(ProgressEvent::StepSkipped { step_name: step.name.clone() });
+                scheduler.complete(index, true);
+                continue;
+            }
+            let throw_tolerant = step.throw_tolerant.unwrap_or(false);
+            join_set.spawn(TraceSpan::current().instrument(async move {
+                (index, throw_tolerant, step.run().await)
+            }));
+        }
+
+        let Some(join_result) = join_set.join_next().await else {
+            break;
+        };
+
+        match join_result {
+            Ok((index, throw_tolerant, step_status)) => {
+                utils::log_step(step_status.status.clone());
+                let succeeded = step_status.status.is_ok() || throw_tolerant;
+                failed |= !succeeded;
+                for pruned in scheduler.complete(index, succeeded) {
+                    info!("Skipping step {}, a step it depends on failed", names[pruned]);
+                    progress.emit(ProgressEvent::StepSkipped { step_name: names[pruned].clone() });
+                }
+                steps_status_vec.push(step_status);
+            }
+            Err(join_error) => {
+                error!("Join error: {:?}", join_error);
+                failed = true;
+                join_set.abort_all();
+                break;
+            }
+        }
+    }
+
+    JobStatus {
+        status: if failed { Err(format!("Job {} failed", name)) } else { Ok(format!("Job {} completed", name)) },
+        name,
+        start_time: Some(start_time),
+        end_time: Some(now_time()),
+        steps_status: steps_status_vec,
+    }
+}
+
 // Allows `AsyncJob` to be sent between threads safely.
 unsafe impl Send for AsyncJob {}
\ No newline at end of file
@@ -4,6 +4,7 @@ use futures::lock::{Mutex, MutexGuard};
 use log::{error, info};
 use tokio::task::{AbortHandle, JoinSet};
 use crate::core::step::StepStatus;
+use crate::core::trace::TraceSpan;
 
 use crate::tokio::step::{AsyncStepRunner, AsyncStep};
 
@@ -56,7 +57,7 @@ pub async fn run_all_join_handles(join_set: Arc<Mutex<JoinSet<StepStatus>>>) ->
 }
 
 pub async fn mount_step_task(step: AsyncStep, throw_tolerant: bool, mut join_set: MutexGuard<'_, JoinSet<StepStatus>>) -> AbortHandle {
-    return join_set.spawn(async move {
+    return join_set.spawn(TraceSpan::current().instrument(async move {
         let step_result = step.run().await;
         match step_result.status.clone() {
             Ok(message) => {
@@ -72,5 +73,5 @@ pub async fn mount_step_task(step: AsyncStep, throw_tolerant: bool, mut join_set
         };
 
         return step_result;
-    });
+    }));
 }
\ No newline at end of file
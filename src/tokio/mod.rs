@@ -1,2 +1,4 @@
 pub mod step;
-pub mod job;
\ No newline at end of file
+pub mod job;
+pub mod listener;
+pub mod item;
\ No newline at end of file
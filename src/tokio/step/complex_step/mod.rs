@@ -1,14 +1,25 @@
+use std::collections::BTreeSet;
 use std::sync::Arc;
+use std::time::Duration;
 use async_trait::async_trait;
-use futures::{StreamExt};
+use std::panic::{self, AssertUnwindSafe};
+use futures::{FutureExt, StreamExt};
 use futures::future::BoxFuture;
 use futures::stream::BoxStream;
 use log::error;
 use tokio::sync::{mpsc, Mutex};
 use tokio::task::{JoinSet};
+use tokio::time::Instant;
+use crate::core::chunk::{AdaptiveChunkSize, ChunkSizer};
+use crate::core::listener::{panic_message, ChunkListener, ChunkListeners, ItemListener};
+use crate::core::recorder::StepMetrics;
+use crate::core::step::StepExecution;
+use crate::core::trace::TraceSpan;
 use crate::tokio::step::{AsyncStep, DeciderCallback, StepResult};
 use crate::tokio::step::parallel_step_builder::AsyncParallelStepBuilderTrait;
 use crate::tokio::step::step_builder::AsyncStepBuilderTrait;
+use crate::tokio::item::{AsyncItemProcessor, AsyncItemReader, AsyncItemWriter};
+use crate::tokio::listener::AsyncStepExecutionListener;
 
 /// Default chunk size used if not specified.
 const DEFAULT_CHUNK_SIZE: usize = 1000;
@@ -20,6 +31,8 @@ type DynParamAsyncCallback<I, O> = dyn Send + Sync + Fn(I) -> BoxFuture<'static,
 type ProcessorCallback<I, O> = Box<DynParamAsyncCallback<I, O>>;
 /// Alias for a callback function that processes input data asynchronously and produces output.
 type ReaderCallback<I> = Box<dyn Send + Sync + Fn() -> BoxFuture<'static, BoxStream<'static, I>>>;
+/// Alias for a function that measures the weight of an output item.
+type WeigherCallback<O> = Box<dyn Send + Sync + Fn(&O) -> usize>;
 
 #[async_trait]
 pub trait ComplexStepBuilderTrait<I: Sized, O: Sized> {
@@ -53,6 +66,39 @@ pub trait ComplexStepBuilderTrait<I: Sized, O: Sized> {
     ///
     /// The modified builder instance.
     fn writer(self, writer: Box<DynParamAsyncCallback<Vec<O>, ()>>) -> Self;
+    /// Sets the reader of the step, opened before the first item is read and closed once the
+    /// step stops.
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: The item reader.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    fn item_reader(self, reader: Box<dyn AsyncItemReader<I>>) -> Self;
+    /// Sets the processor of the step, shared by all the workers, opened before the first item
+    /// is processed and closed once the step stops.
+    ///
+    /// # Parameters
+    ///
+    /// - `processor`: The item processor.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    fn item_processor(self, processor: Box<dyn AsyncItemProcessor<I, O>>) -> Self;
+    /// Sets the writer of the step, shared by all the workers, opened before the first chunk is
+    /// written and closed once the step stops.
+    ///
+    /// # Parameters
+    ///
+    /// - `writer`: The item writer.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    fn item_writer(self, writer: Box<dyn AsyncItemWriter<O>>) -> Self;
     /// Sets the chunk size for processing data in chunks.
     ///
     /// # Parameters
@@ -63,28 +109,119 @@ pub trait ComplexStepBuilderTrait<I: Sized, O: Sized> {
     ///
     /// The modified builder instance.
     fn chunk_size(self, chunk_size: usize) -> Self;
+    /// Sets the maximum time a partial chunk may wait before being written.
+    ///
+    /// Without it, a chunk is only written once it reaches the chunk size or the reader ends,
+    /// so items coming from a slow or unbounded stream can stay buffered indefinitely.
+    ///
+    /// # Parameters
+    ///
+    /// - `flush_interval`: The maximum time between the first item of a chunk and its write.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    fn flush_interval(self, flush_interval: Duration) -> Self;
+    /// Sets a weigher that closes a chunk once the accumulated weight of its items reaches a limit.
+    ///
+    /// The chunk size still applies, so a chunk is written as soon as either limit is reached.
+    ///
+    /// # Parameters
+    ///
+    /// - `weigher`: A function returning the weight of an output item (e.g. its size in bytes).
+    /// - `max_chunk_weight`: The accumulated weight at which a chunk is written.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    fn weigher(self, weigher: WeigherCallback<O>, max_chunk_weight: usize) -> Self;
+    /// Lets the chunk size adapt between the configured bounds to the observed writer latency.
+    ///
+    /// The step starts from the configured chunk size (clamped to the bounds) and the sizes it
+    /// adopts are reported in `StepStatus::chunk_sizes`.
+    ///
+    /// # Parameters
+    ///
+    /// - `adaptive_chunk_size`: The bounds and target latency of the chunk size.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    fn adaptive_chunk_size(self, adaptive_chunk_size: AdaptiveChunkSize) -> Self;
+    /// Registers a listener notified of the start and end of each chunk.
+    ///
+    /// # Parameters
+    ///
+    /// - `listener`: The chunk listener, shared by all the workers.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    fn chunk_listener(self, listener: Arc<dyn ChunkListener>) -> Self;
+    /// Registers a listener notified around the reading, processing and writing of the items.
+    ///
+    /// # Parameters
+    ///
+    /// - `listener`: The item listener, shared by all the workers.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    fn item_listener(self, listener: Arc<dyn ItemListener<I, O>>) -> Self;
+    /// Sets the number of items the reader is expected to yield, reported in the progress events
+    /// so that subscribers can compute a percentage and an ETA.
+    ///
+    /// # Parameters
+    ///
+    /// - `expected_total`: The expected number of items.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    fn expected_total(self, expected_total: usize) -> Self;
 }
 
 /// Implementation of `ComplexStepBuilderTrait` for `AsyncComplexStepBuilder`.
 #[async_trait]
-impl<I: Sized + 'static, O: Sized + 'static> ComplexStepBuilderTrait<I, O> for AsyncComplexStepBuilder<I, O> {
+impl<I: Sized + Send + 'static, O: Sized + Send + 'static> ComplexStepBuilderTrait<I, O> for AsyncComplexStepBuilder<I, O> {
     fn reader(self, reader: ReaderCallback<I>) -> Self {
         AsyncComplexStepBuilder {
-            reader: Some(reader),
+            reader: Some(Box::new(StreamReader { factory: reader, stream: None })),
             ..self
         }
     }
 
     fn processor(self, processor: ProcessorCallback<I, O>) -> Self {
         AsyncComplexStepBuilder {
-            processor: Some(processor),
+            processor: Some(Arc::new(processor)),
             ..self
         }
     }
 
     fn writer(self, writer: Box<DynParamAsyncCallback<Vec<O>, ()>>) -> Self {
         AsyncComplexStepBuilder {
-            writer: Some(writer),
+            writer: Some(Arc::new(writer)),
+            ..self
+        }
+    }
+
+    fn item_reader(self, reader: Box<dyn AsyncItemReader<I>>) -> Self {
+        AsyncComplexStepBuilder {
+            reader: Some(reader),
+            ..self
+        }
+    }
+
+    fn item_processor(self, processor: Box<dyn AsyncItemProcessor<I, O>>) -> Self {
+        AsyncComplexStepBuilder {
+            processor: Some(Arc::from(processor)),
+            ..self
+        }
+    }
+
+    fn item_writer(self, writer: Box<dyn AsyncItemWriter<O>>) -> Self {
+        AsyncComplexStepBuilder {
+            writer: Some(Arc::from(writer)),
             ..self
         }
     }
@@ -95,14 +232,60 @@ impl<I: Sized + 'static, O: Sized + 'static> ComplexStepBuilderTrait<I, O> for A
             ..self
         }
     }
+
+    fn flush_interval(self, flush_interval: Duration) -> Self {
+        AsyncComplexStepBuilder {
+            flush_interval: Some(flush_interval),
+            ..self
+        }
+    }
+
+    fn weigher(self, weigher: WeigherCallback<O>, max_chunk_weight: usize) -> Self {
+        AsyncComplexStepBuilder {
+            weigher: Some((weigher, max_chunk_weight)),
+            ..self
+        }
+    }
+
+    fn adaptive_chunk_size(self, adaptive_chunk_size: AdaptiveChunkSize) -> Self {
+        AsyncComplexStepBuilder {
+            adaptive_chunk_size: Some(adaptive_chunk_size),
+            ..self
+        }
+    }
+
+    fn chunk_listener(mut self, listener: Arc<dyn ChunkListener>) -> Self {
+        self.chunk_listeners.push(listener);
+        self
+    }
+
+    fn item_listener(mut self, listener: Arc<dyn ItemListener<I, O>>) -> Self {
+        self.item_listeners.push(listener);
+        self
+    }
+
+    fn expected_total(self, expected_total: usize) -> Self {
+        self.step.execution.set_expected_total(expected_total);
+        self
+    }
 }
 
 /// An asynchronous complex step builder for processing data.
 pub struct AsyncComplexStepBuilder<I: Sized, O: Sized> {
-    reader: Option<ReaderCallback<I>>,
-    processor: Option<ProcessorCallback<I, O>>,
-    writer: Option<Box<DynParamAsyncCallback<Vec<O>, ()>>>,
+    reader: Option<Box<dyn AsyncItemReader<I>>>,
+    processor: Option<Arc<dyn AsyncItemProcessor<I, O>>>,
+    writer: Option<Arc<dyn AsyncItemWriter<O>>>,
     chunk_size: Option<usize>,
+    /// The maximum time a partial chunk waits before being written.
+    flush_interval: Option<Duration>,
+    /// The weigher and the maximum accumulated weight of a chunk.
+    weigher: Option<(WeigherCallback<O>, usize)>,
+    /// The bounds and target latency of an adaptive chunk size.
+    adaptive_chunk_size: Option<AdaptiveChunkSize>,
+    /// The listeners notified of the start and end of each chunk.
+    chunk_listeners: Vec<Arc<dyn ChunkListener>>,
+    /// The listeners notified of each phase of the items.
+    item_listeners: Vec<Arc<dyn ItemListener<I, O>>>,
     /// The size of each processing task.
     /// Defaults to 1.
     workers: usize,
@@ -147,6 +330,34 @@ where
         }
     }
 
+    /// Declares a step that must complete before this step runs.
+    ///
+    /// # Parameters
+    ///
+    /// - `step_name`: The name of the step this step depends on.
+    ///
+    /// # Returns `Self`
+    ///
+    /// The modified builder instance.
+    fn depends_on(mut self, step_name: String) -> Self {
+        self.step.depends_on.push(step_name);
+        self
+    }
+
+    /// Registers a listener notified before and after the step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn listener(mut self, listener: Arc<dyn AsyncStepExecutionListener>) -> Self {
+        self.step.listeners.push(listener);
+        self
+    }
+
     /// Retrieves a new step builder instance with a given name.
     ///
     /// # Parameters
@@ -163,12 +374,21 @@ where
             processor: None,
             writer: None,
             chunk_size: None,
+            flush_interval: None,
+            weigher: None,
+            adaptive_chunk_size: None,
+            chunk_listeners: Vec::new(),
+            item_listeners: Vec::new(),
             workers: DEFAULT_WORKERS_SIZE,
             step: AsyncStep {
                 name,
                 callback: None,
                 decider: None,
                 throw_tolerant: None,
+                execution: Arc::default(),
+                blocking: None,
+                depends_on: Vec::new(),
+                listeners: Vec::new(),
             },
         }
     }
@@ -195,6 +415,10 @@ where
             panic!("Writer is required");
         }
 
+        if let Some(adaptive_chunk_size) = &self.adaptive_chunk_size {
+            adaptive_chunk_size.validate();
+        }
+
         return self;
     }
 
@@ -205,19 +429,30 @@ where
     /// The built asynchronous step.
     fn build(self) -> AsyncStep {
         let mut current_self = self.validate();
-        let reader = Arc::new(current_self.reader.unwrap());
-        let processor = Arc::new(current_self.processor.unwrap());
-        let writer = Arc::new(current_self.writer.unwrap());
+        let reader = Arc::new(Mutex::new(current_self.reader.take().unwrap()));
+        let processor = current_self.processor.take().unwrap();
+        let writer = current_self.writer.take().unwrap();
+        let weigher = current_self.weigher.map(|(weigher, max_chunk_weight)| (Arc::new(weigher), max_chunk_weight));
         let throw_tolerant = current_self.step.throw_tolerant.unwrap_or(false);
         let step_name = Arc::new(current_self.step.name.clone());
+        let execution = Arc::clone(&current_self.step.execution);
+        let chunk_listeners = std::mem::take(&mut current_self.chunk_listeners);
+        let item_listeners = std::mem::take(&mut current_self.item_listeners);
 
         current_self.step.callback = Some(Box::new(move || {
-            let reader = Box::pin(reader.clone());
+            let reader = Arc::clone(&reader);
             let processor = processor.clone();
             let writer = writer.clone();
+            let weigher = weigher.clone();
             let chunk_size = current_self.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+            let flush_interval = current_self.flush_interval;
             let throw_tolerant = throw_tolerant.clone();
             let step_name = step_name.clone();
+            let sizer = current_self.adaptive_chunk_size
+                .map(|config| Arc::new(ChunkSizer::new(config, chunk_size, Arc::clone(&execution))));
+            let listeners = Arc::new(ChunkListeners::new(chunk_listeners.clone(), item_listeners.clone()));
+            let metrics = StepMetrics::new(execution.job_name(), &step_name);
+            let execution = Arc::clone(&execution);
             return Box::pin(async move {
                 let reader = Arc::clone(&reader);
                 let processor = Arc::clone(&processor);
@@ -225,98 +460,204 @@ where
                 let throw_tolerant = throw_tolerant.clone();
                 let step_name = Arc::clone(&step_name);
 
-                let mut join_workers = JoinSet::new();
-                let mut channels = Vec::new();
-                let step_result: Arc<Mutex<StepResult>> = Arc::new(Mutex::new(Ok(())));
-                for _ in 0..current_self.workers {
-                    let (sender, receiver) = mpsc::channel::<I>(16);
-                    let processor = Arc::clone(&processor);
-                    let writer = Arc::clone(&writer);
-                    let mut receiver = receiver;
-                    let throw_tolerant = throw_tolerant.clone();
-                    let step_result = Arc::clone(&step_result);
-                    let step_name = Arc::clone(&step_name);
-                    join_workers.spawn(async move {
+                let mut reader = reader.lock().await;
+                reader.open().await;
+                processor.open().await;
+                writer.open().await;
+
+                let written = Arc::new(std::sync::Mutex::new(WrittenItems::default()));
+                // The reader, processor and writer are closed even when the step fails.
+                let outcome = AssertUnwindSafe(async {
+                    let mut join_workers = JoinSet::new();
+                    let mut channels = Vec::new();
+                    let step_result: Arc<Mutex<StepResult>> = Arc::new(Mutex::new(Ok(())));
+                    for worker in 0..current_self.workers {
+                        let (sender, receiver) = mpsc::channel::<(u64, I)>(16);
+                        let processor = Arc::clone(&processor);
+                        let weigher = weigher.clone();
+                        let sizer = sizer.clone();
+                        let listeners = Arc::clone(&listeners);
+                        let mut receiver = receiver;
+                        let throw_tolerant = throw_tolerant.clone();
                         let step_result = Arc::clone(&step_result);
-                        let mut vec: Vec<O> = Vec::new();
                         let step_name = Arc::clone(&step_name);
-                        while let Some(data) = receiver.recv().await {
-                            let output = tokio::spawn(processor(data)).await;
-                            if let Err(err) = output {
-                                let mut step_result = step_result.lock().await;
-                                *step_result = Err(err);
-                                if !throw_tolerant {
-                                    panic!("step {}: Error to processing data", step_name);
-                                } else {
-                                    error!("step {}: Error to processing data", step_name);
-                                    continue;
-                                }
-                            }
-                            let output = output.unwrap();
-                            vec.push(output);
-
-                            if vec.len() >= chunk_size {
-                                let vec_to_write = std::mem::take(&mut vec);
-                                let writer_result = tokio::spawn(writer(vec_to_write)).await;
-                                vec.clear();
-                                if let Err(err) = writer_result {
+                        let chunk_writer = ChunkWriter {
+                            writer: Arc::clone(&writer),
+                            throw_tolerant,
+                            step_result: Arc::clone(&step_result),
+                            step_name: Arc::clone(&step_name),
+                            sizer: sizer.clone(),
+                            listeners: Arc::clone(&listeners),
+                            metrics: metrics.clone(),
+                            execution: Arc::clone(&execution),
+                            written: Arc::clone(&written),
+                        };
+                        let written = Arc::clone(&written);
+                        let metrics = metrics.clone();
+                        let execution = Arc::clone(&execution);
+                        let worker_span = TraceSpan::worker(&step_name, worker);
+                        join_workers.spawn(worker_span.instrument(async move {
+                            let step_result = Arc::clone(&step_result);
+                            let mut vec: Vec<O> = Vec::new();
+                            // The sequence numbers of the items in `vec`.
+                            let mut seqs: Vec<u64> = Vec::new();
+                            let step_name = Arc::clone(&step_name);
+                            let mut chunk_deadline: Option<Instant> = None;
+                            let mut chunk_weight: usize = 0;
+                            let mut chunk: Option<(usize, TraceSpan)> = None;
+                            loop {
+                                let data = match chunk_deadline {
+                                    Some(deadline) => match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                                        Ok(data) => data,
+                                        Err(_) => {
+                                            chunk_deadline = None;
+                                            chunk_weight = 0;
+                                            let vec_to_write = std::mem::take(&mut vec);
+                                            let seqs_to_write = std::mem::take(&mut seqs);
+                                            chunk_writer.write(vec_to_write, seqs_to_write, chunk.take().unwrap()).await;
+                                            continue;
+                                        }
+                                    },
+                                    None => receiver.recv().await,
+                                };
+                                let Some((seq, data)) = data else {
+                                    break;
+                                };
+                                let (current_chunk, chunk_span) = chunk.get_or_insert_with(|| {
+                                    let chunk_index = listeners.open_chunk();
+                                    (chunk_index, TraceSpan::chunk(&step_name, chunk_index))
+                                }).clone();
+                                listeners.before_process(&data);
+                                let processor = Arc::clone(&processor);
+                                let output = tokio::spawn(chunk_span.instrument(async move {
+                                    processor.process_or_skip(data).await
+                                })).await;
+                                if let Err(err) = output {
+                                    let message = err.to_string();
+                                    listeners.on_process_error(current_chunk, &message);
+                                    let mut step_result = step_result.lock().await;
+                                    *step_result = Err(err);
                                     if !throw_tolerant {
-                                        let mut error = step_result.lock().await;
-                                        *error = Err(err);
-                                        panic!("step {}: Error to writing data", step_name);
+                                        panic!("step {}: Error to processing data", step_name);
                                     } else {
-                                        error!("step {}: Error to writing data", step_name);
+                                        error!("step {}: Error to processing data", step_name);
+                                        metrics.items_skipped(1);
+                                        execution.items_skipped(&step_name, 1, &message);
+                                        written.lock().unwrap().complete([seq]);
+                                        continue;
+                                    }
+                                }
+                                let output = match output.unwrap() {
+                                    Ok(output) => output,
+                                    Err(reason) => {
+                                        listeners.on_skip(&reason);
+                                        metrics.items_skipped(1);
+                                        execution.items_skipped(&step_name, 1, &reason);
+                                        written.lock().unwrap().complete([seq]);
+                                        continue;
                                     }
+                                };
+                                listeners.after_process(&output);
+                                if let Some((weigher, _)) = &weigher {
+                                    chunk_weight += weigher(&output);
+                                }
+                                vec.push(output);
+                                seqs.push(seq);
+
+                                if chunk_deadline.is_none() {
+                                    chunk_deadline = flush_interval.map(|interval| Instant::now() + interval);
+                                }
+
+                                let is_heavy = weigher.as_ref()
+                                    .is_some_and(|(_, max_chunk_weight)| chunk_weight >= *max_chunk_weight);
+
+                                let chunk_size = sizer.as_ref().map_or(chunk_size, |sizer| sizer.current());
+
+                                if vec.len() >= chunk_size || is_heavy {
+                                    chunk_deadline = None;
+                                    chunk_weight = 0;
+                                    let vec_to_write = std::mem::take(&mut vec);
+                                    let seqs_to_write = std::mem::take(&mut seqs);
+                                    chunk_writer.write(vec_to_write, seqs_to_write, chunk.take().unwrap()).await;
                                 }
                             }
-                        }
-                        if !vec.is_empty() {
-                            let vec_to_write = std::mem::take(&mut vec);
-                            let writer_result = tokio::spawn(writer(vec_to_write)).await;
-                            if let Err(err) = writer_result {
-                                if !throw_tolerant {
-                                    let mut step_result = step_result.lock().await;
-                                    *step_result = Err(err);
-                                    panic!("step {}: Error to writing data", step_name);
+                            if let Some((chunk_index, chunk_span)) = chunk.take() {
+                                if vec.is_empty() {
+                                    listeners.after_chunk(chunk_index);
+                                    chunk_span.finish(true);
                                 } else {
-                                    error!("step {}: Error to writing data", step_name);
+                                    let vec_to_write = std::mem::take(&mut vec);
+                                    let seqs_to_write = std::mem::take(&mut seqs);
+                                    chunk_writer.write(vec_to_write, seqs_to_write, (chunk_index, chunk_span)).await;
                                 }
                             }
+                        }));
+                        channels.push(sender);
+                    }
+                    let mut current_channel: usize = 0;
+                    let mut read_count: u64 = 0;
+                    let mut checkpointed: u64 = 0;
+                    loop {
+                        let written_count = written.lock().unwrap().count;
+                        if written_count > checkpointed {
+                            reader.checkpoint(written_count).await;
+                            checkpointed = written_count;
                         }
-                    });
-                    channels.push(sender);
-                }
-                let mut iterator = reader().await;
-                let mut current_channel: usize = 0;
-                while let Some(data) = iterator.next().await {
-                    if !throw_tolerant {
-                        let step_result = Arc::clone(&step_result);
-                        let step_result = step_result.lock().await;
-                        if step_result.is_err() {
-                            join_workers.abort_all();
-                            panic!("step {}: Error to processing data", step_name);
+                        listeners.before_read();
+                        let data = match AssertUnwindSafe(reader.read()).catch_unwind().await {
+                            Ok(Some(data)) => data,
+                            Ok(None) => break,
+                            Err(cause) => {
+                                listeners.on_read_error(&panic_message(cause.as_ref()));
+                                panic::resume_unwind(cause);
+                            }
+                        };
+                        listeners.after_read(&data);
+                        metrics.items_read(1);
+                        if !throw_tolerant {
+                            let step_result = Arc::clone(&step_result);
+                            let step_result = step_result.lock().await;
+                            if step_result.is_err() {
+                                join_workers.abort_all();
+                                panic!("step {}: Error to processing data", step_name);
+                            }
+                        }
+                        let sender = &mut channels[current_channel];
+                        sender.send((read_count, data)).await.unwrap();
+                        read_count += 1;
+                        metrics.queue_depth(channels.iter().map(|sender| sender.max_capacity() - sender.capacity()).sum());
+                        if current_channel == current_self.workers - 1 {
+                            current_channel = 0;
+                        } else {
+                            current_channel += 1;
                         }
                     }
-                    let sender = &mut channels[current_channel];
-                    sender.send(data).await.unwrap();
-                    if current_channel == current_self.workers - 1 {
-                        current_channel = 0;
-                    } else {
-                        current_channel += 1;
-                    }
-                }
-                drop(channels);
-                while let Some(task_result) = join_workers.join_next().await {
-                    if let Err(err) = task_result {
-                        if !throw_tolerant {
-                            return Err(err);
+                    drop(channels);
+                    metrics.queue_depth(0);
+                    while let Some(task_result) = join_workers.join_next().await {
+                        if let Err(err) = task_result {
+                            if !throw_tolerant {
+                                return Err(err);
+                            }
+                            join_workers.abort_all();
                         }
-                        join_workers.abort_all();
                     }
+                    let step_result = Arc::try_unwrap(step_result).unwrap();
+                    let step_result = step_result.into_inner();
+                    return step_result;
+                }).catch_unwind().await;
+
+                // Even when the step fails, the chunks written so far are known.
+                let written_count = written.lock().unwrap().count;
+                reader.checkpoint(written_count).await;
+                reader.close().await;
+                processor.close().await;
+                writer.close().await;
+
+                match outcome {
+                    Ok(step_result) => step_result,
+                    Err(cause) => panic::resume_unwind(cause),
                 }
-                let step_result = Arc::try_unwrap(step_result).unwrap();
-                let step_result = step_result.into_inner();
-                return step_result;
             });
         }));
 
@@ -324,6 +665,104 @@ where
     }
 }
 
+/// Reads the stream created by a reader callback, which is called each time the step opens it.
+struct StreamReader<I> {
+    factory: ReaderCallback<I>,
+    stream: Option<BoxStream<'static, I>>,
+}
+
+#[async_trait]
+impl<I: Send + 'static> AsyncItemReader<I> for StreamReader<I> {
+    async fn open(&mut self) {
+        self.stream = Some((self.factory)().await);
+    }
+
+    async fn read(&mut self) -> Option<I> {
+        match &mut self.stream {
+            Some(stream) => stream.next().await,
+            None => None,
+        }
+    }
+
+    async fn close(&mut self) {
+        self.stream = None;
+    }
+}
+
+/// Counts the items written from the first one read, while the workers write their chunks in any
+/// order. Each item is numbered in the order it is read, starting at zero.
+#[derive(Default)]
+struct WrittenItems {
+    /// The number of items written, or skipped, before the first one that is not.
+    count: u64,
+    /// The items written or skipped after the first one that is not.
+    pending: BTreeSet<u64>,
+}
+
+impl WrittenItems {
+    fn complete(&mut self, seqs: impl IntoIterator<Item=u64>) {
+        self.pending.extend(seqs);
+        while self.pending.remove(&self.count) {
+            self.count += 1;
+        }
+    }
+}
+
+/// The state a worker needs to write its chunks.
+struct ChunkWriter<I, O> {
+    writer: Arc<dyn AsyncItemWriter<O>>,
+    throw_tolerant: bool,
+    step_result: Arc<Mutex<StepResult>>,
+    step_name: Arc<String>,
+    sizer: Option<Arc<ChunkSizer>>,
+    listeners: Arc<ChunkListeners<I, O>>,
+    metrics: StepMetrics,
+    execution: Arc<StepExecution>,
+    written: Arc<std::sync::Mutex<WrittenItems>>,
+}
+
+impl<I, O: Send + 'static> ChunkWriter<I, O> {
+    /// Writes a chunk, recording a failure in the shared step result.
+    ///
+    /// The latency and outcome of the write are reported to the adaptive chunk sizer, if any.
+    /// The items of a written chunk are counted as written, by their sequence numbers. Panics when
+    /// the write fails and the step is not throw tolerant, so the worker is aborted.
+    async fn write(&self, chunk: Vec<O>, seqs: Vec<u64>, (chunk_index, span): (usize, TraceSpan)) {
+        let count = self.writer.item_count(&chunk);
+        span.record_items(count);
+        self.listeners.before_write(&chunk);
+        let write_start = Instant::now();
+        let writer = Arc::clone(&self.writer);
+        let writer_result = tokio::spawn(span.clone().instrument(async move {
+            writer.write(chunk).await
+        })).await;
+        span.finish(writer_result.is_ok());
+        if let Some(sizer) = &self.sizer {
+            sizer.observe(write_start.elapsed(), writer_result.is_err());
+        }
+        self.metrics.chunk_written(write_start.elapsed());
+        if let Err(err) = writer_result {
+            self.listeners.on_write_error(chunk_index, &err.to_string());
+            if self.throw_tolerant {
+                self.metrics.items_skipped(count);
+                self.execution.items_skipped(&self.step_name, count, &err.to_string());
+            }
+            if !self.throw_tolerant {
+                let mut step_result = self.step_result.lock().await;
+                *step_result = Err(err);
+                panic!("step {}: Error to writing data", self.step_name);
+            } else {
+                error!("step {}: Error to writing data", self.step_name);
+            }
+            return;
+        }
+        self.written.lock().unwrap().complete(seqs);
+        self.listeners.after_write(chunk_index, count);
+        self.metrics.items_written(count);
+        self.execution.chunk_written(&self.step_name, chunk_index, count);
+    }
+}
+
 impl<I: Sized + Send + 'static + Sync, O: Sized + Send + 'static + Sync> AsyncParallelStepBuilderTrait for AsyncComplexStepBuilder<I, O>
 where
     Self: Sized,
@@ -0,0 +1,202 @@
+use std::sync::{Arc, Mutex};
+use crate::tokio::job::AsyncJob;
+use crate::tokio::step::{AsyncStep, AsyncStepRunner, DeciderCallback};
+use crate::tokio::step::step_builder::AsyncStepBuilderTrait;
+use crate::tokio::listener::AsyncStepExecutionListener;
+
+/// This trait defines methods for building asynchronous steps that run a whole job.
+pub trait AsyncJobStepBuilderTrait {
+    /// Sets the job run by the step.
+    ///
+    /// The status of the job is reported in `StepStatus::job_status`, and the step fails when the
+    /// job fails, so the failure is handled by the error policy of the parent job.
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The job to run.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn job(self, job: AsyncJob) -> Self;
+}
+
+/// A builder struct for constructing asynchronous steps that run a whole job.
+pub struct AsyncJobStepBuilder {
+    /// The job run by the step.
+    job: Option<AsyncJob>,
+    /// The step being constructed.
+    step: AsyncStep,
+}
+
+impl AsyncStepBuilderTrait for AsyncJobStepBuilder {
+    /// Sets the decider callback for the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `decider` - The decider callback function.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn decider(self, decider: DeciderCallback) -> Self {
+        AsyncJobStepBuilder {
+            step: AsyncStep {
+                decider: Some(decider),
+                ..self.step
+            },
+            ..self
+        }
+    }
+
+    /// Sets the step to be tolerant to thrown errors.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn throw_tolerant(self) -> Self {
+        AsyncJobStepBuilder {
+            step: AsyncStep {
+                throw_tolerant: Some(true),
+                ..self.step
+            },
+            ..self
+        }
+    }
+
+    /// Declares a step that must complete before this step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step this step depends on.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn depends_on(mut self, step_name: String) -> Self {
+        self.step.depends_on.push(step_name);
+        self
+    }
+
+    /// Registers a listener notified before and after the step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn listener(mut self, listener: Arc<dyn AsyncStepExecutionListener>) -> Self {
+        self.step.listeners.push(listener);
+        self
+    }
+
+    /// Initializes a new builder instance with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the step.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a new builder instance.
+    #[inline]
+    fn get(name: String) -> Self {
+        AsyncJobStepBuilder {
+            job: None,
+            step: AsyncStep {
+                name,
+                callback: None,
+                decider: None,
+                throw_tolerant: None,
+                execution: Arc::default(),
+                blocking: None,
+                depends_on: Vec::new(),
+                listeners: Vec::new(),
+            },
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if job or name is not provided.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    fn validate(self) -> Self {
+        if self.job.is_none() {
+            panic!("Job is required");
+        }
+
+        if self.step.name.is_empty() {
+            panic!("Name is required");
+        }
+
+        return self;
+    }
+
+    /// Builds and returns the configured asynchronous step.
+    ///
+    /// # Returns `AsyncStep`
+    ///
+    /// Returns the configured asynchronous step.
+    fn build(self) -> AsyncStep {
+        let current_self = self.validate();
+        let mut step = current_self.step;
+        let job = Arc::new(Mutex::new(current_self.job));
+        let execution = Arc::clone(&step.execution);
+        let step_name = step.name.clone();
+        step.callback = Some(Box::new(move || {
+            let job = job.lock().unwrap().take();
+            let execution = Arc::clone(&execution);
+            let step_name = step_name.clone();
+            return Box::pin(async move {
+                let Some(job) = job else {
+                    panic!("step {}: The job has already been executed", step_name);
+                };
+                let job_status = job.run().await;
+                execution.set_job_status(job_status.clone());
+                if let Err(message) = job_status.status {
+                    panic!("{}", message);
+                }
+                return Ok(());
+            });
+        }));
+        return step;
+    }
+}
+
+impl AsyncJobStepBuilderTrait for AsyncJobStepBuilder {
+    /// Sets the job run by the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The job to run.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn job(self, job: AsyncJob) -> Self {
+        AsyncJobStepBuilder {
+            job: Some(job),
+            ..self
+        }
+    }
+}
+
+/// Returns a new `AsyncJobStepBuilder` instance with the given name.
+///
+/// # Arguments
+///
+/// * `name` - The name of the step.
+///
+/// # Returns `Self`
+///
+/// Returns a new `AsyncJobStepBuilder` instance.
+pub fn get(name: String) -> AsyncJobStepBuilder {
+    AsyncJobStepBuilder::get(name)
+}
@@ -3,11 +3,20 @@ use futures::future::BoxFuture;
 use log::info;
 use tokio::task::JoinError;
 use crate::core::job::now_time;
-use crate::core::step::{mount_step_status, StepStatus, throw_tolerant_exception};
+use crate::sync::step::{step_finished_event, Decider as SyncDecider, Runner, SyncStep};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use crate::core::progress::{ProgressEvent, ProgressEvents};
+use crate::core::recorder::StepMetrics;
+use crate::core::step::{mount_step_status, StepExecution, StepStatus, throw_tolerant_exception};
+use crate::core::trace::TraceSpan;
+use crate::tokio::listener::AsyncStepExecutionListener;
 
 pub mod simple_step;
 pub mod step_builder;
 pub mod complex_step;
+pub mod job_step;
+pub mod partition_step;
 pub mod parallel_step_builder;
 
 /// A trait for running asynchronous tasks and returning a result.
@@ -42,12 +51,85 @@ pub struct AsyncStep {
     decider: Option<DeciderCallback>,
     /// The callback function for the step.
     callback: Option<Box<DynAsyncCallback<StepResult>>>,
+    /// The data collected while the step is running.
+    execution: Arc<StepExecution>,
+    /// The synchronous step run on the blocking thread pool in place of the callback.
+    blocking: Option<Mutex<SyncStep>>,
+    /// The names of the steps that must complete before this step runs.
+    pub depends_on: Vec<String>,
+    /// The listeners notified before and after the step runs.
+    listeners: Vec<Arc<dyn AsyncStepExecutionListener>>,
 }
 
 #[async_trait]
 impl AsyncStepRunner<StepStatus> for AsyncStep {
-    /// Executes the asynchronous step and returns its status.
-    async fn run(self) -> StepStatus {
+    /// Executes the asynchronous step, notifying its listeners, and returns its status.
+    async fn run(mut self) -> StepStatus {
+        let started = Instant::now();
+        // A blocking step records its duration and progress when its synchronous step runs.
+        let metrics = self.blocking.is_none().then(|| StepMetrics::new(self.execution.job_name(), &self.name));
+        if metrics.is_some() {
+            self.execution.emit(ProgressEvent::StepStarted {
+                step_name: self.name.clone(),
+                expected_total: self.execution.expected_total(),
+            });
+        }
+        let span = TraceSpan::step(&self.name);
+        let listeners = std::mem::take(&mut self.listeners);
+        let execution = Arc::clone(&self.execution);
+        let progress_execution = Arc::clone(&self.execution);
+        let step_status = span.clone().instrument(async move {
+            for listener in &listeners {
+                listener.before_step(&self.name, &execution).await;
+            }
+            let mut step_status = self.execute().await;
+            for listener in listeners.iter().rev() {
+                listener.after_step(&execution, &mut step_status).await;
+            }
+            step_status
+        }).await;
+        span.finish(step_status.status.is_ok());
+        if let Some(metrics) = metrics {
+            metrics.step_finished(started.elapsed());
+            progress_execution.emit(step_finished_event(&step_status, started.elapsed()));
+        }
+        step_status
+    }
+}
+
+impl AsyncStep {
+    /// Attaches the step, and its synchronous step when it has one, to the job running it.
+    pub(crate) fn join_job(&self, job_name: String, progress: ProgressEvents) {
+        if let Some(blocking) = &self.blocking {
+            blocking.lock().unwrap().execution.join_job(job_name.clone(), progress.clone());
+        }
+        self.execution.join_job(job_name, progress);
+    }
+
+    /// Attaches the step, and its synchronous step when it has one, to the job of a parent step.
+    pub(crate) fn inherit(&self, parent: &StepExecution) {
+        if let Some(blocking) = &self.blocking {
+            blocking.lock().unwrap().execution.inherit(parent);
+        }
+        self.execution.inherit(parent);
+    }
+
+    /// Executes the callback of the step and returns its status.
+    async fn execute(self) -> StepStatus {
+        if let Some(blocking) = self.blocking {
+            let step = blocking.into_inner().unwrap();
+            let start_time = now_time();
+            let span = TraceSpan::current();
+            return match tokio::task::spawn_blocking(move || span.in_scope(|| step.run())).await {
+                Ok(step_status) => step_status,
+                Err(error) => {
+                    let message = format!("Step {} failed to execute: {}", self.name, error);
+                    info!("{}", message);
+                    mount_step_status(self.name, Err(message), start_time)
+                }
+            };
+        }
+
         return match self.callback {
             None => {
                 throw_tolerant_exception(self.throw_tolerant.unwrap_or(false), self.name)
@@ -55,24 +137,24 @@ impl AsyncStepRunner<StepStatus> for AsyncStep {
             Some(callback) => {
                 let start_time = now_time();
                 info!("Step {} is running", self.name);
-                let callback_result = tokio::spawn(async move {
+                let callback_result = tokio::spawn(TraceSpan::current().instrument(async move {
                     return callback().await;
-                }).await;
+                })).await;
                 return match callback_result {
                     Ok(step_result) => {
                         if let Err(error) = step_result {
                             let message = format!("Step {} completed with error: {}", self.name, error.to_string());
                             info!("{}", message);
-                            return mount_step_status(self.name, Err(message), start_time)
+                            return self.execution.report(mount_step_status(self.name, Err(message), start_time))
                         }
                         let message = format!("Step {} executed successfully", self.name);
                         info!("{}", message);
-                        mount_step_status(self.name, Ok(message), start_time)
+                        self.execution.report(mount_step_status(self.name, Ok(message), start_time))
                     }
                     Err(error) => {
                         let message = format!("Step {} failed to execute: {}", self.name, error.to_string());
                         info!("{}", message);
-                        mount_step_status(self.name, Err(message), start_time)
+                        self.execution.report(mount_step_status(self.name, Err(message), start_time))
                     },
                 };
             }
@@ -84,6 +166,10 @@ impl AsyncStepRunner<StepStatus> for AsyncStep {
 impl Decider for AsyncStep {
     /// Decides whether the step should proceed or not based on the decider callback.
     async fn decide(&self) -> bool {
+        if let Some(blocking) = &self.blocking {
+            return blocking.lock().unwrap().is_run();
+        }
+
         return match &self.decider {
             None => true,
             Some(decider) => decider().await,
@@ -91,5 +177,23 @@ impl Decider for AsyncStep {
     }
 }
 
+impl From<SyncStep> for AsyncStep {
+    /// Wraps a synchronous step so it runs on the blocking thread pool of an asynchronous job.
+    ///
+    /// The synchronous step keeps its own decider and reports its own `StepStatus`.
+    fn from(step: SyncStep) -> Self {
+        AsyncStep {
+            name: step.name.clone(),
+            throw_tolerant: step.throw_tolerant,
+            decider: None,
+            callback: None,
+            execution: Arc::default(),
+            depends_on: step.depends_on.clone(),
+            blocking: Some(Mutex::new(step)),
+            listeners: Vec::new(),
+        }
+    }
+}
+
 // Allows `AsyncStep` to be sent between threads safely.
 unsafe impl Send for AsyncStep {}
\ No newline at end of file
@@ -0,0 +1,350 @@
+use std::sync::Arc;
+use log::{error, info};
+use tokio::task::JoinSet;
+use crate::core::partition::{Partition, Partitioner};
+use crate::core::progress::ProgressEvent;
+use crate::core::partition::process::ProcessPartitionHandler;
+use crate::core::trace::TraceSpan;
+use crate::tokio::step::{AsyncStep, AsyncStepRunner, Decider, DeciderCallback};
+use crate::tokio::step::parallel_step_builder::AsyncParallelStepBuilderTrait;
+use crate::tokio::step::step_builder::AsyncStepBuilderTrait;
+use crate::tokio::listener::AsyncStepExecutionListener;
+
+/// Alias for a function that builds the worker step of a partition.
+type WorkerCallback = Arc<dyn Send + Sync + Fn(Partition) -> AsyncStep>;
+
+/// This trait defines methods for building asynchronous steps that split their work into partitions.
+pub trait AsyncPartitionStepBuilderTrait {
+    /// Sets the partitioner that splits the work of the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `partitioner` - The partitioner.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn partitioner(self, partitioner: Arc<dyn Partitioner>) -> Self;
+
+    /// Sets the function building the worker step of each partition.
+    ///
+    /// Every partition runs the step returned for it, so the same reader, processor and writer
+    /// can be built with the parameters of the partition.
+    ///
+    /// # Arguments
+    ///
+    /// * `worker` - The function building the worker step of a partition.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn worker(self, worker: WorkerCallback) -> Self;
+
+    /// Sets the number of partitions requested from the partitioner. Defaults to 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid_size` - The number of partitions.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn grid_size(self, grid_size: usize) -> Self;
+
+    /// Runs each partition in a child process on the blocking thread pool instead of a task.
+    ///
+    /// The child serves the partition with `process::serve_stdio`, so the worker is only needed
+    /// in the child.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler spawning the worker processes.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn process_handler(self, handler: ProcessPartitionHandler) -> Self;
+}
+
+/// A builder struct for constructing asynchronous partitioned steps.
+pub struct AsyncPartitionStepBuilder {
+    /// The partitioner that splits the work of the step.
+    partitioner: Option<Arc<dyn Partitioner>>,
+    /// The function building the worker step of each partition.
+    worker: Option<WorkerCallback>,
+    /// The number of partitions requested from the partitioner.
+    grid_size: Option<usize>,
+    /// The maximum number of partitions running at once.
+    workers: Option<usize>,
+    /// The handler running the partitions in child processes.
+    process_handler: Option<ProcessPartitionHandler>,
+    /// The step being constructed.
+    step: AsyncStep,
+}
+
+impl AsyncStepBuilderTrait for AsyncPartitionStepBuilder {
+    /// Sets the decider callback for the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `decider` - The decider callback function.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn decider(self, decider: DeciderCallback) -> Self {
+        AsyncPartitionStepBuilder {
+            step: AsyncStep {
+                decider: Some(decider),
+                ..self.step
+            },
+            ..self
+        }
+    }
+
+    /// Sets the step to be tolerant to thrown errors.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn throw_tolerant(self) -> Self {
+        AsyncPartitionStepBuilder {
+            step: AsyncStep {
+                throw_tolerant: Some(true),
+                ..self.step
+            },
+            ..self
+        }
+    }
+
+    /// Declares a step that must complete before this step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step this step depends on.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn depends_on(mut self, step_name: String) -> Self {
+        self.step.depends_on.push(step_name);
+        self
+    }
+
+    /// Registers a listener notified before and after the step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn listener(mut self, listener: Arc<dyn AsyncStepExecutionListener>) -> Self {
+        self.step.listeners.push(listener);
+        self
+    }
+
+    /// Initializes a new builder instance with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the step.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a new builder instance.
+    #[inline]
+    fn get(name: String) -> Self {
+        AsyncPartitionStepBuilder {
+            partitioner: None,
+            worker: None,
+            grid_size: None,
+            workers: None,
+            process_handler: None,
+            step: AsyncStep {
+                name,
+                callback: None,
+                decider: None,
+                throw_tolerant: None,
+                execution: Arc::default(),
+                blocking: None,
+                depends_on: Vec::new(),
+                listeners: Vec::new(),
+            },
+        }
+    }
+
+    /// Validates the builder configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if name, partitioner or both worker and process handler are not provided, or if the grid size or the number
+    /// of workers is zero.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance if validation succeeds.
+    fn validate(self) -> Self {
+        if self.step.name.is_empty() {
+            panic!("Name is required");
+        }
+
+        if self.partitioner.is_none() {
+            panic!("Partitioner is required");
+        }
+
+        if self.worker.is_none() && self.process_handler.is_none() {
+            panic!("Worker or process handler is required");
+        }
+
+        if self.grid_size == Some(0) || self.workers == Some(0) {
+            panic!("Grid size and workers must be greater than zero");
+        }
+
+        return self;
+    }
+
+    /// Builds and returns the configured asynchronous step.
+    ///
+    /// The status of each partition is reported in `StepStatus::partitions_status`, and the step
+    /// fails when any partition fails.
+    ///
+    /// # Returns `AsyncStep`
+    ///
+    /// Returns the configured asynchronous step.
+    fn build(self) -> AsyncStep {
+        let current_self = self.validate();
+        let mut step = current_self.step;
+        let partitioner = current_self.partitioner.unwrap();
+        let worker = current_self.worker;
+        let process_handler = current_self.process_handler;
+        let grid_size = current_self.grid_size.unwrap_or(1);
+        let workers = current_self.workers.unwrap_or(1);
+        let execution = Arc::clone(&step.execution);
+        let step_name = step.name.clone();
+        step.callback = Some(Box::new(move || {
+            let partitioner = Arc::clone(&partitioner);
+            let worker = worker.clone();
+            let process_handler = process_handler.clone();
+            let execution = Arc::clone(&execution);
+            let step_name = step_name.clone();
+            return Box::pin(async move {
+                let mut partitions = partitioner.partition(grid_size).into_iter();
+                let mut tasks = JoinSet::new();
+                let mut failed = 0;
+
+                info!("Step {} is running {} partitions", step_name, partitions.len());
+
+                loop {
+                    while tasks.len() < workers {
+                        let Some(partition) = partitions.next() else {
+                            break;
+                        };
+                        if let Some(handler) = &process_handler {
+                            let handler = handler.clone();
+                            let step_name = step_name.clone();
+                            let execution = Arc::clone(&execution);
+                            let span = TraceSpan::current();
+                            tasks.spawn_blocking(move || span.in_scope(|| {
+                                handler.run_with_restarts(&step_name, &partition, |attempt, error| {
+                                    execution.emit(ProgressEvent::Retried {
+                                        step_name: partition.name.clone(),
+                                        attempt,
+                                        error: error.to_string(),
+                                    });
+                                })
+                            }));
+                            continue;
+                        }
+                        let step = worker.as_ref().unwrap()(partition);
+                        step.inherit(&execution);
+                        if !step.decide().await {
+                            info!("Step {} is skipped", &step.name);
+                            execution.emit(ProgressEvent::StepSkipped { step_name: step.name.clone() });
+                            continue;
+                        }
+                        tasks.spawn(TraceSpan::current().instrument(step.run()));
+                    }
+
+                    let Some(task_result) = tasks.join_next().await else {
+                        break;
+                    };
+                    let partition_status = task_result.unwrap();
+                    if let Err(error_message) = &partition_status.status {
+                        error!("{}", error_message);
+                        failed += 1;
+                    }
+                    execution.add_partition_status(partition_status);
+                }
+
+                if failed > 0 {
+                    panic!("step {}: {} partitions failed", step_name, failed);
+                }
+                return Ok(());
+            });
+        }));
+        return step;
+    }
+}
+
+impl AsyncPartitionStepBuilderTrait for AsyncPartitionStepBuilder {
+    fn partitioner(self, partitioner: Arc<dyn Partitioner>) -> Self {
+        AsyncPartitionStepBuilder {
+            partitioner: Some(partitioner),
+            ..self
+        }
+    }
+
+    fn worker(self, worker: WorkerCallback) -> Self {
+        AsyncPartitionStepBuilder {
+            worker: Some(worker),
+            ..self
+        }
+    }
+
+    fn grid_size(self, grid_size: usize) -> Self {
+        AsyncPartitionStepBuilder {
+            grid_size: Some(grid_size),
+            ..self
+        }
+    }
+
+    fn process_handler(self, handler: ProcessPartitionHandler) -> Self {
+        AsyncPartitionStepBuilder {
+            process_handler: Some(handler),
+            ..self
+        }
+    }
+}
+
+impl AsyncParallelStepBuilderTrait for AsyncPartitionStepBuilder {
+    /// Sets the maximum number of partitions running at once. Defaults to 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `workers` - The number of partitions running at once.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn workers(self, workers: usize) -> Self {
+        AsyncPartitionStepBuilder {
+            workers: Some(workers),
+            ..self
+        }
+    }
+}
+
+/// Returns a new `AsyncPartitionStepBuilder` instance with the given name.
+///
+/// # Arguments
+///
+/// * `name` - The name of the step.
+///
+/// # Returns `Self`
+///
+/// Returns a new `AsyncPartitionStepBuilder` instance.
+pub fn get(name: String) -> AsyncPartitionStepBuilder {
+    AsyncPartitionStepBuilder::get(name)
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use crate::tokio::step::{AsyncStep, DeciderCallback, DynAsyncCallback};
 use crate::tokio::step::step_builder::AsyncStepBuilderTrait;
+use crate::tokio::listener::AsyncStepExecutionListener;
 
 /// This trait defines methods for building asynchronous steps with simple configurations.
 pub trait AsyncSimpleStepBuilderTrait<I, O> {
@@ -14,6 +15,19 @@ pub trait AsyncSimpleStepBuilderTrait<I, O> {
     ///
     /// Returns a modified builder instance.
     fn tasklet(self, step_callback: Box<DynAsyncCallback<()>>) -> Self;
+
+    /// Sets a tasklet returning the exit status of the step.
+    ///
+    /// The returned exit status replaces `COMPLETED` and can be matched by the transitions of a flow.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_callback` - The callback function for the step.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn tasklet_with_exit_status(self, step_callback: Box<DynAsyncCallback<String>>) -> Self;
 }
 
 /// A builder struct for constructing asynchronous simple steps.
@@ -57,6 +71,34 @@ impl AsyncStepBuilderTrait for AsyncSimpleStepBuilder {
         }
     }
 
+    /// Declares a step that must complete before this step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_name` - The name of the step this step depends on.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn depends_on(mut self, step_name: String) -> Self {
+        self.step.depends_on.push(step_name);
+        self
+    }
+
+    /// Registers a listener notified before and after the step runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn listener(mut self, listener: Arc<dyn AsyncStepExecutionListener>) -> Self {
+        self.step.listeners.push(listener);
+        self
+    }
+
     /// Initializes a new builder instance with the given name.
     ///
     /// # Arguments
@@ -75,6 +117,10 @@ impl AsyncStepBuilderTrait for AsyncSimpleStepBuilder {
                 callback: None,
                 decider: None,
                 throw_tolerant: None,
+                execution: Arc::default(),
+                blocking: None,
+                depends_on: Vec::new(),
+                listeners: Vec::new(),
             }
         }
     }
@@ -139,6 +185,26 @@ impl AsyncSimpleStepBuilderTrait<fn(), fn()> for AsyncSimpleStepBuilder {
             }
         };
     }
+
+    /// Sets a tasklet returning the exit status of the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_callback` - The callback function for the step.
+    ///
+    /// # Returns `Self`
+    ///
+    /// Returns a modified builder instance.
+    fn tasklet_with_exit_status(self, step_callback: Box<DynAsyncCallback<String>>) -> Self {
+        let execution = Arc::clone(&self.step.execution);
+        self.tasklet(Box::new(move || {
+            let execution = Arc::clone(&execution);
+            let exit_status = step_callback();
+            Box::pin(async move {
+                execution.set_exit_status(exit_status.await);
+            })
+        }))
+    }
 }
 
 /// Returns a new `AsyncSimpleStepBuilder` instance with the given name.
@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod composite_test {
+    use std::sync::{Arc, Mutex};
+
+    use batch_processing::core::item::CompositeMode;
+    use batch_processing::core::progress::{ProgressEvent, ProgressEvents};
+    use batch_processing::sync::item::ItemWriter;
+    use batch_processing::sync::item::composite::{ClassifierItemWriterBuilder, CompositeItemWriterBuilder};
+    use batch_processing::sync::item::processor::FlattenItemWriter;
+    use batch_processing::sync::job::job_builder::{JobBuilder, JobBuilderTrait};
+    use batch_processing::sync::step::{complex_step, Runner, SyncStep};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+
+    fn collect(written: &Arc<Mutex<Vec<u32>>>) -> Box<dyn ItemWriter<u32> + Send> {
+        let written = written.clone();
+        Box::new(move |items: Vec<u32>| written.lock().unwrap().extend(items))
+    }
+
+    fn failing() -> Box<dyn ItemWriter<u32> + Send> {
+        Box::new(|_: Vec<u32>| panic!("Audit file unavailable"))
+    }
+
+    fn failing_on(item: u32, written: &Arc<Mutex<Vec<u32>>>) -> Box<dyn ItemWriter<u32> + Send> {
+        let written = written.clone();
+        Box::new(move |items: Vec<u32>| {
+            if items.contains(&item) {
+                panic!("Audit file unavailable");
+            }
+            written.lock().unwrap().extend(items)
+        })
+    }
+
+    fn build_step(writer: Box<dyn ItemWriter<u32> + Send>) -> SyncStep {
+        complex_step::get::<u32, u32>("composite_step".to_string())
+            .chunk_size(3)
+            .item_reader(Box::new(1..=7))
+            .item_processor(Box::new(|item: u32| item))
+            .item_writer(writer)
+            .build()
+    }
+
+    #[test]
+    fn test_composite_writer_fail_fast() {
+        let database = Arc::new(Mutex::new(Vec::new()));
+        let audit = Arc::new(Mutex::new(Vec::new()));
+
+        let step = build_step(Box::new(CompositeItemWriterBuilder::get()
+            .writer(collect(&database))
+            .writer(collect(&audit))
+            .build()));
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*database.lock().unwrap(), (1..=7).collect::<Vec<u32>>());
+        assert_eq!(*audit.lock().unwrap(), (1..=7).collect::<Vec<u32>>());
+
+        let database = Arc::new(Mutex::new(Vec::new()));
+        let step = build_step(Box::new(CompositeItemWriterBuilder::get()
+            .writer(failing())
+            .writer(collect(&database))
+            .build()));
+
+        assert!(step.run().status.is_err());
+        assert!(database.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_composite_writer_best_effort() {
+        let database = Arc::new(Mutex::new(Vec::new()));
+
+        let step = build_step(Box::new(CompositeItemWriterBuilder::get()
+            .writer(failing())
+            .writer(collect(&database))
+            .mode(CompositeMode::BestEffort)
+            .build()));
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*database.lock().unwrap(), (1..=7).collect::<Vec<u32>>());
+
+        let step = build_step(Box::new(CompositeItemWriterBuilder::get()
+            .writer(failing())
+            .writer(failing())
+            .mode(CompositeMode::BestEffort)
+            .build()));
+
+        assert!(step.run().status.is_err());
+    }
+
+    #[test]
+    fn test_composite_writer_all_or_nothing() {
+        let database = Arc::new(Mutex::new(Vec::new()));
+        let audit = Arc::new(Mutex::new(Vec::new()));
+        let compensated = database.clone();
+
+        // The audit writer fails on the second chunk, which is then removed from the database.
+        let step = build_step(Box::new(CompositeItemWriterBuilder::get()
+            .compensated_writer(collect(&database), Box::new(move |items: Vec<u32>| {
+                compensated.lock().unwrap().retain(|item| !items.contains(item));
+            }))
+            .writer(failing_on(5, &audit))
+            .mode(CompositeMode::AllOrNothing)
+            .build()));
+
+        assert!(step.run().status.is_err());
+        assert_eq!(*database.lock().unwrap(), vec![1, 2, 3]);
+        assert_eq!(*audit.lock().unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "requires a compensator")]
+    fn test_composite_writer_all_or_nothing_requires_compensators() {
+        let database = Arc::new(Mutex::new(Vec::new()));
+
+        CompositeItemWriterBuilder::get()
+            .writer(collect(&database))
+            .writer(failing())
+            .mode(CompositeMode::AllOrNothing)
+            .build();
+    }
+
+    #[test]
+    fn test_classifier_writer() {
+        let even = Arc::new(Mutex::new(Vec::new()));
+        let odd = Arc::new(Mutex::new(Vec::new()));
+        let other = Arc::new(Mutex::new(Vec::new()));
+
+        let step = build_step(Box::new(ClassifierItemWriterBuilder::get(Box::new(|item: &u32| match item {
+            7 => "seven",
+            item if item % 2 == 0 => "even",
+            _ => "odd",
+        }))
+            .route("even", collect(&even))
+            .route("odd", collect(&odd))
+            .default_writer(collect(&other))
+            .build()));
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*even.lock().unwrap(), vec![2, 4, 6]);
+        assert_eq!(*odd.lock().unwrap(), vec![1, 3, 5]);
+        assert_eq!(*other.lock().unwrap(), vec![7]);
+    }
+
+    #[test]
+    fn test_classifier_writer_without_route() {
+        let even = Arc::new(Mutex::new(Vec::new()));
+
+        let step = build_step(Box::new(ClassifierItemWriterBuilder::get(Box::new(|item: &u32| item % 2))
+            .route(0, collect(&even))
+            .build()));
+
+        assert!(step.run().status.is_err());
+        assert!(even.lock().unwrap().is_empty());
+    }
+
+    fn flatten() -> Box<dyn ItemWriter<Vec<u32>> + Send> {
+        Box::new(FlattenItemWriter::new(Box::new(|_: Vec<u32>| {})))
+    }
+
+    /// Runs a step expanding each item `n` into `n` copies, and returns the items written per chunk.
+    fn items_written(writer: Box<dyn ItemWriter<Vec<u32>> + Send>) -> Vec<usize> {
+        let step = complex_step::get::<u32, Vec<u32>>("expanding_step".to_string())
+            .chunk_size(3)
+            .item_reader(Box::new(1..=3))
+            .item_processor(Box::new(|item: u32| vec![item; item as usize]))
+            .item_writer(writer)
+            .build();
+        let progress = ProgressEvents::new();
+        let receiver = progress.subscribe();
+        JobBuilder::get("expanding_job".to_string())
+            .progress(progress)
+            .step(step)
+            .build()
+            .run();
+
+        receiver.try_iter()
+            .filter_map(|event| match event {
+                ProgressEvent::ChunkWritten { items_written, .. } => Some(items_written),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_combined_writers_count_items_as_their_delegates() {
+        let composite = CompositeItemWriterBuilder::get()
+            .writer(flatten())
+            .writer(Box::new(|_: Vec<Vec<u32>>| {}))
+            .build();
+        assert_eq!(items_written(Box::new(composite)), vec![6]);
+
+        // The odd items are flattened and the even ones written as a whole.
+        let classifier = ClassifierItemWriterBuilder::get(Box::new(|items: &Vec<u32>| items[0] % 2))
+            .route(1, flatten())
+            .route(0, Box::new(|_: Vec<Vec<u32>>| {}))
+            .build();
+        assert_eq!(items_written(Box::new(classifier)), vec![5]);
+    }
+}
@@ -0,0 +1,110 @@
+#[cfg(all(feature = "compression", feature = "csv", test))]
+mod compression_test {
+    use std::fs;
+
+    use serde::{Deserialize, Serialize};
+
+    use batch_processing::core::compression::Compression;
+    use batch_processing::core::flat_file::{FieldSpec, FieldType, FieldValue, FlatRecord, RecordLayout};
+    use batch_processing::sync::item::{ItemReader, ItemWriter};
+    use batch_processing::sync::item::csv::{CsvItemReaderBuilder, CsvItemWriterBuilder};
+    use batch_processing::sync::item::flat_file::{FlatFileItemReaderBuilder, FlatFileItemWriterBuilder};
+    use batch_processing::sync::step::{complex_step, Runner};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+    use tempfile::TempDir;
+
+    macro_rules! resource_file {($fname:expr) => (
+      concat!(env!("CARGO_MANIFEST_DIR"), "/tests/resources/", $fname)
+    )}
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct CarSale {
+        make: String,
+        model: String,
+        sellingprice: f64,
+    }
+
+    fn read_all<T>(reader: &mut dyn ItemReader<T>) -> Vec<T> {
+        let mut items = Vec::new();
+        reader.open();
+        while let Some(item) = reader.read() {
+            items.push(item);
+        }
+        reader.close();
+        items
+    }
+
+    #[test]
+    fn test_csv_reader_reads_zip_entry() {
+        let mut reader = CsvItemReaderBuilder::get(resource_file!("car_prices.zip"))
+            .zip_entry("car_prices.csv")
+            .build();
+
+        let sales: Vec<CarSale> = read_all(&mut reader);
+
+        assert_eq!(sales.len(), 8039);
+        assert_eq!(sales[0].make, "Kia");
+        assert_eq!(sales.iter().map(|sale| sale.sellingprice).sum::<f64>(), 113000176.0);
+    }
+
+    #[test]
+    fn test_gzip_csv_round_trip() {
+        let directory = TempDir::new().unwrap();
+        let output = directory.path().join("car_sales.csv.gz");
+
+        let step = complex_step::get::<CarSale, CarSale>("gzip_step".to_string())
+            .chunk_size(1000)
+            .item_reader(Box::new(CsvItemReaderBuilder::get(resource_file!("car_prices.zip")).build()))
+            .item_processor(Box::new(|sale: CarSale| sale))
+            .item_writer(Box::new(CsvItemWriterBuilder::get(&output).build()))
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_ok());
+        assert_eq!(&fs::read(&output).unwrap()[..2], &[0x1f, 0x8b]);
+
+        let mut reader = CsvItemReaderBuilder::get(&output).build();
+        let sales: Vec<CarSale> = read_all(&mut reader);
+        assert_eq!(sales.len(), 8039);
+        assert_eq!(sales.iter().map(|sale| sale.sellingprice).sum::<f64>(), 113000176.0);
+    }
+
+    #[test]
+    fn test_zstd_flat_file_round_trip() {
+        let directory = TempDir::new().unwrap();
+        let output = directory.path().join("accounts.dat");
+        let layout = RecordLayout::fixed_width("account", vec![
+            FieldSpec::new("name", 0, 8),
+            FieldSpec::new("balance", 8, 6).field_type(FieldType::Integer),
+        ]);
+
+        let mut writer = FlatFileItemWriterBuilder::get(&output)
+            .compression(Compression::Zstd)
+            .layout(layout.clone())
+            .formatter(Box::new(|account: &(&str, i64)| vec![FieldValue::from(account.0), FieldValue::from(account.1)]))
+            .build();
+        writer.open();
+        writer.write(vec![("alice", 1200), ("bob", 75)]);
+        writer.close();
+
+        assert_eq!(&fs::read(&output).unwrap()[..4], &[0x28, 0xb5, 0x2f, 0xfd]);
+
+        let mut reader = FlatFileItemReaderBuilder::get(&output)
+            .compression(Compression::Zstd)
+            .layout(layout)
+            .build();
+        let records: Vec<FlatRecord> = read_all(&mut reader);
+        let balances: Vec<(&str, i64)> = records.iter()
+            .map(|record| (record.text("name").unwrap(), record.integer("balance").unwrap()))
+            .collect();
+        assert_eq!(balances, vec![("alice", 1200), ("bob", 75)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Zip archives cannot be written")]
+    fn test_zip_writer_is_rejected() {
+        CsvItemWriterBuilder::<CarSale>::get("car_sales.zip").build();
+    }
+}
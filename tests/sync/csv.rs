@@ -0,0 +1,89 @@
+#[cfg(all(feature = "csv", test))]
+mod csv_test {
+    use std::fs;
+
+    use serde::{Deserialize, Serialize};
+
+    use batch_processing::core::item::Positioned;
+    use batch_processing::sync::item::ItemReader;
+    use batch_processing::sync::item::csv::{CsvItemReaderBuilder, CsvItemWriterBuilder};
+    use batch_processing::sync::step::{complex_step, Runner};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+    use tempfile::TempDir;
+
+    #[derive(Debug, Deserialize)]
+    struct CarPrice {
+        make: String,
+        model: String,
+        price: f64,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct Listing {
+        line: u64,
+        name: String,
+        price: f64,
+    }
+
+    #[test]
+    fn test_csv_reader_and_writer() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("car_prices.csv");
+        let output = directory.path().join("listings.csv");
+        fs::write(&input, "exported car prices\nmake;model;price\nFord;\"Focus; ST\";21000.5\nKia;Rio;15000\n").unwrap();
+
+        let step = complex_step::get::<Positioned<CarPrice>, Listing>("csv_step".to_string())
+            .chunk_size(1)
+            .item_reader(Box::new(CsvItemReaderBuilder::get(&input).delimiter(b';').skip_lines(1).build()))
+            .item_processor(Box::new(|car: Positioned<CarPrice>| Listing {
+                line: car.line,
+                name: format!("{} {}", car.item.make, car.item.model),
+                price: car.item.price,
+            }))
+            .item_writer(Box::new(CsvItemWriterBuilder::get(&output).build()))
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_ok());
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "line,name,price\n3,Ford Focus; ST,21000.5\n4,Kia Rio,15000.0\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_reader_fails_on_invalid_row() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("invalid_car_prices.csv");
+        fs::write(&input, "Ford,Focus,21000\nKia,Rio,unknown\n").unwrap();
+
+        let step = complex_step::get::<CarPrice, f64>("invalid_csv_step".to_string())
+            .item_reader(Box::new(CsvItemReaderBuilder::get(&input).has_headers(false).build()))
+            .item_processor(Box::new(|car: CarPrice| car.price))
+            .item_writer(Box::new(|_prices: Vec<f64>| {}))
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_err());
+    }
+
+    #[test]
+    fn test_csv_reader_tracks_lines() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("multiline_car_prices.csv");
+        fs::write(&input, "make,model,price\nFord,\"Focus\nST\",21000\nKia,Rio,15000\n").unwrap();
+
+        let mut reader = CsvItemReaderBuilder::<CarPrice>::get(&input).build();
+
+        ItemReader::<Positioned<CarPrice>>::open(&mut reader);
+        let lines: Vec<u64> = std::iter::from_fn(|| ItemReader::<Positioned<CarPrice>>::read(&mut reader))
+            .map(|car| car.line)
+            .collect();
+        ItemReader::<Positioned<CarPrice>>::close(&mut reader);
+
+        assert_eq!(lines, vec![2, 4]);
+    }
+}
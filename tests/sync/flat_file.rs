@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod flat_file_test {
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+
+    use batch_processing::core::flat_file::{Alignment, FieldSpec, FieldType, FieldValue, FlatRecord, RecordLayout};
+    use batch_processing::sync::item::flat_file::{FlatFileItemReaderBuilder, FlatFileItemWriterBuilder};
+    use batch_processing::sync::step::{complex_step, Runner};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+    use tempfile::TempDir;
+
+    struct Payment {
+        account: String,
+        amount: i64,
+        settled: bool,
+    }
+
+    #[test]
+    fn test_fixed_width_records() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("payments.dat");
+        let output = directory.path().join("payments.out");
+        fs::write(&input, concat!(
+            "PAYMENTS EXTRACT\n",
+            "H20240131\n",
+            "DACC-001  0001250Y\n",
+            "DACC-002  -000075N\n",
+            "Mfirst line of a memo\n",
+            "second line\n",
+            "T00002\n",
+        )).unwrap();
+
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let records_clone = records.clone();
+
+        let step = complex_step::get::<FlatRecord, Option<Payment>>("fixed_width_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(FlatFileItemReaderBuilder::get(&input)
+                .skip_lines(1)
+                .layout(RecordLayout::fixed_width("header", vec![
+                    FieldSpec::new("date", 1, 8).field_type(FieldType::Integer),
+                ]).prefix("H"))
+                .layout(RecordLayout::fixed_width("payment", vec![
+                    FieldSpec::new("account", 1, 9),
+                    FieldSpec::new("amount", 10, 7).field_type(FieldType::Integer).alignment(Alignment::Right).pad('0'),
+                    FieldSpec::new("settled", 17, 1).field_type(FieldType::Boolean),
+                ]).prefix("D"))
+                .layout(RecordLayout::fixed_width("memo", vec![
+                    FieldSpec::new("text", 1, 50),
+                ]).prefix("M").lines(2))
+                .layout(RecordLayout::fixed_width("trailer", vec![
+                    FieldSpec::new("count", 1, 5).field_type(FieldType::Integer),
+                ]).prefix("T"))
+                .build()))
+            .item_processor(Box::new(move |record: FlatRecord| {
+                records_clone.lock().unwrap().push(record.clone());
+                match record.record_type.as_str() {
+                    "payment" => Some(Payment {
+                        account: record.text("account").unwrap().to_string(),
+                        amount: record.integer("amount").unwrap(),
+                        settled: record.boolean("settled").unwrap(),
+                    }),
+                    _ => None,
+                }
+            }))
+            .item_writer(Box::new(FlatFileItemWriterBuilder::get(&output)
+                .layout(RecordLayout::fixed_width("payment", vec![
+                    FieldSpec::new("account", 0, 8),
+                    FieldSpec::new("amount", 8, 6).alignment(Alignment::Right).pad('0'),
+                    FieldSpec::new("settled", 14, 1),
+                ]))
+                .formatter(Box::new(|payment: &Option<Payment>| match payment {
+                    Some(payment) => vec![
+                        FieldValue::from(payment.account.as_str()),
+                        FieldValue::from(payment.amount),
+                        FieldValue::from(payment.settled),
+                    ],
+                    None => vec![FieldValue::from("-"), FieldValue::Empty, FieldValue::Empty],
+                }))
+                .build()))
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_ok());
+
+        let records = records.lock().unwrap();
+        let summary: Vec<(u64, &str)> = records.iter().map(|record| (record.line, record.record_type.as_str())).collect();
+        assert_eq!(summary, vec![(2, "header"), (3, "payment"), (4, "payment"), (5, "memo"), (7, "trailer")]);
+        assert_eq!(records[0].integer("date"), Some(20240131));
+        assert_eq!(records[2].text("account"), Some("ACC-002"));
+        assert_eq!(records[2].integer("amount"), Some(-75));
+        assert_eq!(records[3].text("text"), Some("first line of a memosecond line"));
+        assert_eq!(records[4].integer("count"), Some(2));
+
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "-       000000 \nACC-001 001250Y\nACC-002 -00075N\n-       000000 \n-       000000 \n"
+        );
+    }
+
+    #[test]
+    fn test_delimited_record_with_invalid_field() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("prices.txt");
+        let output = directory.path().join("prices.out");
+        fs::write(&input, "Ford|21000.5\nKia|x\n").unwrap();
+
+        let step = complex_step::get::<FlatRecord, f64>("delimited_step".to_string())
+            .item_reader(Box::new(FlatFileItemReaderBuilder::get(&input)
+                .layout(RecordLayout::delimited("price", '|', vec![
+                    FieldSpec::column("make"),
+                    FieldSpec::column("price").field_type(FieldType::Decimal),
+                ]))
+                .build()))
+            .item_processor(Box::new(|record: FlatRecord| record.decimal("price").unwrap()))
+            .item_writer(Box::new(FlatFileItemWriterBuilder::get(&output)
+                .layout(RecordLayout::delimited("price", ';', vec![FieldSpec::column("price")]))
+                .formatter(Box::new(|price: &f64| vec![FieldValue::from(*price)]))
+                .build()))
+            .build();
+
+        assert!(step.run().status.is_err());
+    }
+
+    #[test]
+    fn test_delimited_value_holding_the_delimiter() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("makes.txt");
+        let output = directory.path().join("makes.out");
+        fs::write(&input, "Ford\nRolls;Royce\n").unwrap();
+
+        let step = complex_step::get::<FlatRecord, String>("delimiter_step".to_string())
+            .item_reader(Box::new(FlatFileItemReaderBuilder::get(&input)
+                .layout(RecordLayout::delimited("make", '|', vec![FieldSpec::column("make")]))
+                .build()))
+            .item_processor(Box::new(|record: FlatRecord| record.text("make").unwrap().to_string()))
+            .item_writer(Box::new(FlatFileItemWriterBuilder::get(&output)
+                .layout(RecordLayout::delimited("make", ';', vec![FieldSpec::column("make")]))
+                .formatter(Box::new(|make: &String| vec![FieldValue::from(make.as_str())]))
+                .build()))
+            .build();
+
+        assert!(step.run().status.is_err(), "A value holding the delimiter should fail the chunk");
+    }
+
+    #[test]
+    #[should_panic(expected = "overlap")]
+    fn test_overlapping_fixed_width_fields() {
+        FlatFileItemWriterBuilder::get("overlap.out")
+            .layout(RecordLayout::fixed_width("payment", vec![
+                FieldSpec::new("account", 0, 8),
+                FieldSpec::new("amount", 6, 6),
+            ]))
+            .formatter(Box::new(|_: &String| vec![FieldValue::Empty, FieldValue::Empty]))
+            .build();
+    }
+}
@@ -1,7 +1,14 @@
 #[cfg(test)]
 mod job_test {
+    use std::sync::{Arc, Mutex};
+    use batch_processing::core::flow::FlowBuilder;
+    use batch_processing::core::job::{JobContext, JobStatus};
+    use batch_processing::core::progress::{ProgressEvent, ProgressEvents};
+    use batch_processing::core::step::{StepExecution, StepStatus};
+    use batch_processing::sync::listener::{JobExecutionListener, StepExecutionListener};
     use batch_processing::sync::job::job_builder::{JobBuilder, JobBuilderTrait};
-    use batch_processing::sync::step::Runner;
+    use batch_processing::sync::step::{complex_step, Runner};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
     use batch_processing::sync::step::simple_step::{SimpleStepBuilder, SimpleStepBuilderTrait};
     use batch_processing::sync::step::step_builder::StepBuilderTrait;
 
@@ -37,4 +44,199 @@ mod job_test {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn job_flow_with_custom_exit_status() {
+        let executed = Arc::new(Mutex::new(Vec::new()));
+        let executed_clone = executed.clone();
+        let read_step = SimpleStepBuilder::get(String::from("read"))
+            .tasklet_with_exit_status(Box::new(move || {
+                executed_clone.lock().unwrap().push("read");
+                String::from("NO_DATA")
+            }))
+            .build();
+        let executed_clone = executed.clone();
+        let process_step = SimpleStepBuilder::get(String::from("process"))
+            .tasklet(Box::new(move || {
+                executed_clone.lock().unwrap().push("process");
+            }))
+            .build();
+        let executed_clone = executed.clone();
+        let cleanup_step = SimpleStepBuilder::get(String::from("cleanup"))
+            .tasklet(Box::new(move || {
+                executed_clone.lock().unwrap().push("cleanup");
+            }))
+            .build();
+
+        let flow = FlowBuilder::start("read")
+            .on("NO_DATA").to("cleanup")
+            .on("*").to("process")
+            .from("cleanup")
+            .on("*").stop()
+            .build();
+
+        let job = JobBuilder::get(String::from("flow-job"))
+            .step(read_step)
+            .step(process_step)
+            .step(cleanup_step)
+            .flow(flow)
+            .build();
+
+        let job_status = job.run();
+
+        assert!(job_status.status.is_ok(), "The stopped job should not fail");
+        assert_eq!(*executed.lock().unwrap(), vec!["read", "cleanup"]);
+        assert_eq!(job_status.steps_status[0].exit_status, "NO_DATA");
+    }
+
+    #[test]
+    fn job_with_step_dependencies() {
+        let executed = Arc::new(Mutex::new(Vec::new()));
+        let executed_clone = executed.clone();
+        let load_step = SimpleStepBuilder::get(String::from("load"))
+            .tasklet(Box::new(move || {
+                executed_clone.lock().unwrap().push("load");
+            }))
+            .depends_on(String::from("extract"))
+            .build();
+        let executed_clone = executed.clone();
+        let extract_step = SimpleStepBuilder::get(String::from("extract"))
+            .tasklet(Box::new(move || {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+                executed_clone.lock().unwrap().push("extract");
+            }))
+            .build();
+
+        let job = JobBuilder::get(String::from("dag-job"))
+            .step(load_step)
+            .step(extract_step)
+            .multi_threaded(2)
+            .build();
+
+        let job_status = job.run();
+
+        assert!(job_status.status.is_ok());
+        assert_eq!(*executed.lock().unwrap(), vec!["extract", "load"], "The load step should wait for the extract step");
+    }
+
+    #[test]
+    #[should_panic(expected = "greater than zero")]
+    fn job_with_zero_threads() {
+        let step = SimpleStepBuilder::get(String::from("step"))
+            .tasklet(Box::new(|| {}))
+            .build();
+
+        JobBuilder::get(String::from("zero-threads-job"))
+            .step(step)
+            .multi_threaded(0)
+            .build();
+    }
+
+    struct RecordingListener {
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl JobExecutionListener for RecordingListener {
+        fn before_job(&self, context: &JobContext) {
+            self.events.lock().unwrap().push(format!("before {} with {}", context.name, context.step_names.join(", ")));
+            assert!(context.start_time > 0);
+        }
+
+        fn after_job(&self, job_status: &mut JobStatus) {
+            self.events.lock().unwrap().push(format!("after {}", job_status.name));
+        }
+    }
+
+    impl StepExecutionListener for RecordingListener {
+        fn before_step(&self, step_name: &str, _execution: &StepExecution) {
+            self.events.lock().unwrap().push(format!("before {}", step_name));
+        }
+
+        fn after_step(&self, _execution: &StepExecution, step_status: &mut StepStatus) {
+            self.events.lock().unwrap().push(format!("after {}", step_status.name));
+            if step_status.status.is_err() {
+                step_status.exit_status = String::from("RECOVERED");
+            }
+        }
+    }
+
+    #[test]
+    fn job_with_listeners() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let listener = Arc::new(RecordingListener { events: Arc::clone(&events) });
+
+        let failing = SimpleStepBuilder::get(String::from("failing"))
+            .tasklet(Box::new(|| panic!("Step failed")))
+            .listener(listener.clone())
+            .build();
+        let recovery = SimpleStepBuilder::get(String::from("recovery"))
+            .tasklet(Box::new(|| println!("Recovering")))
+            .listener(listener.clone())
+            .build();
+
+        let flow = FlowBuilder::start("failing")
+            .from("failing").on("RECOVERED").to("recovery")
+            .build();
+
+        let job = JobBuilder::get(String::from("listened-job"))
+            .step(failing)
+            .step(recovery)
+            .flow(flow)
+            .listener(listener)
+            .build();
+
+        let job_status = job.run();
+
+        assert!(job_status.status.is_ok(), "The exit status set by the listener should lead to the recovery step");
+        assert_eq!(*events.lock().unwrap(), vec![
+            "before listened-job with failing, recovery",
+            "before failing",
+            "after failing",
+            "before recovery",
+            "after recovery",
+            "after listened-job",
+        ]);
+    }
+
+    #[test]
+    fn job_with_progress_events() {
+        let skipped = SimpleStepBuilder::get(String::from("skipped"))
+            .tasklet(Box::new(|| {}))
+            .decider(Box::new(|| false))
+            .build();
+        let copy = complex_step::get::<i32, i32>(String::from("copy"))
+            .chunk_size(2)
+            .expected_total(5)
+            .reader(Box::new(|| Box::new(vec![1, 2, 3, 4, 5].into_iter())))
+            .processor(Box::new(|| Box::new(|item: i32| item)))
+            .writer(Box::new(|| Box::new(|_items: &Vec<i32>| {})))
+            .build();
+        let failing = SimpleStepBuilder::get(String::from("failing"))
+            .throw_tolerant()
+            .tasklet(Box::new(|| panic!("Step failed")))
+            .build();
+
+        let progress = ProgressEvents::new();
+        let receiver = progress.subscribe();
+        let job = JobBuilder::get(String::from("progress-job"))
+            .progress(progress)
+            .step(skipped)
+            .step(copy)
+            .step(failing)
+            .build();
+
+        let job_status = job.run();
+
+        assert!(job_status.status.is_ok());
+        let events: Vec<ProgressEvent> = receiver.try_iter().collect();
+        assert_eq!(events[0], ProgressEvent::JobStarted { job_name: String::from("progress-job"), step_count: 3 });
+        assert_eq!(events[1], ProgressEvent::StepSkipped { step_name: String::from("skipped") });
+        assert_eq!(events[2], ProgressEvent::StepStarted { step_name: String::from("copy"), expected_total: Some(5) });
+        let fractions: Vec<f64> = events.iter().filter_map(ProgressEvent::fraction).collect();
+        assert_eq!(fractions, vec![0.4, 0.8, 1.0]);
+        assert!(matches!(&events[6], ProgressEvent::StepCompleted { step_name, .. } if step_name == "copy"));
+        assert!(matches!(&events[8], ProgressEvent::StepFailed { step_name, .. } if step_name == "failing"));
+        assert!(matches!(&events[9], ProgressEvent::JobFinished { succeeded: true, .. }));
+        assert_eq!(events.len(), 10);
+    }
+}
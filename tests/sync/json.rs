@@ -0,0 +1,98 @@
+#[cfg(all(feature = "json", test))]
+mod json_test {
+    use std::fs;
+
+    use serde::{Deserialize, Serialize};
+
+    use batch_processing::core::item::Positioned;
+    use batch_processing::core::json::JsonFormat;
+    use batch_processing::sync::item::json::{JsonItemReaderBuilder, JsonItemWriterBuilder};
+    use batch_processing::sync::step::{complex_step, Runner};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+    use tempfile::TempDir;
+
+    #[derive(Debug, Deserialize)]
+    struct Event {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct Summary {
+        line: u64,
+        name: String,
+        tag_count: usize,
+    }
+
+    #[test]
+    fn test_json_array_to_lines() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("events.json");
+        let output = directory.path().join("summaries.ndjson");
+        fs::write(&input, "[\n  {\"name\": \"a, \\\"quoted\\\" ]\", \"tags\": [\"x\", \"y\"]},\n  {\n    \"name\": \"b\",\n    \"tags\": []\n  }\n]\n").unwrap();
+
+        let step = complex_step::get::<Positioned<Event>, Summary>("json_array_step".to_string())
+            .chunk_size(1)
+            .item_reader(Box::new(JsonItemReaderBuilder::get(&input).format(JsonFormat::Array).build()))
+            .item_processor(Box::new(|event: Positioned<Event>| Summary {
+                line: event.line,
+                name: event.item.name,
+                tag_count: event.item.tags.len(),
+            }))
+            .item_writer(Box::new(JsonItemWriterBuilder::get(&output).build()))
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_ok());
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "{\"line\":2,\"name\":\"a, \\\"quoted\\\" ]\",\"tag_count\":2}\n{\"line\":3,\"name\":\"b\",\"tag_count\":0}\n"
+        );
+    }
+
+    #[test]
+    fn test_json_lines_to_array() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("events.ndjson");
+        let output = directory.path().join("names.json");
+        let empty_output = directory.path().join("empty.json");
+        fs::write(&input, "{\"name\":\"a\",\"tags\":[]}\n\n{\"name\":\"b\",\"tags\":[\"x\"]}\n").unwrap();
+
+        let step = complex_step::get::<Event, String>("json_lines_step".to_string())
+            .chunk_size(1)
+            .item_reader(Box::new(JsonItemReaderBuilder::get(&input).build()))
+            .item_processor(Box::new(|event: Event| event.name))
+            .item_writer(Box::new(JsonItemWriterBuilder::get(&output).format(JsonFormat::Array).build()))
+            .build();
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(fs::read_to_string(&output).unwrap(), "[\n\"a\",\n\"b\"\n]\n");
+
+        let empty_step = complex_step::get::<Event, String>("empty_json_step".to_string())
+            .item_reader(Box::new(std::iter::empty()))
+            .item_processor(Box::new(|event: Event| event.name))
+            .item_writer(Box::new(JsonItemWriterBuilder::get(&empty_output).format(JsonFormat::Array).build()))
+            .build();
+
+        assert!(empty_step.run().status.is_ok());
+        assert_eq!(fs::read_to_string(&empty_output).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn test_json_array_with_trailing_comma() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("trailing.json");
+        fs::write(&input, "[\n  {\"name\": \"a\", \"tags\": []},\n]\n").unwrap();
+
+        let step = complex_step::get::<Positioned<Event>, String>("trailing_comma_step".to_string())
+            .chunk_size(1)
+            .item_reader(Box::new(JsonItemReaderBuilder::get(&input).format(JsonFormat::Array).build()))
+            .item_processor(Box::new(|event: Positioned<Event>| event.item.name))
+            .item_writer(Box::new(|_names: Vec<String>| {}))
+            .build();
+
+        assert!(step.run().status.is_err(), "A trailing comma should fail the step");
+    }
+}
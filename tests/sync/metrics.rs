@@ -0,0 +1,39 @@
+#[cfg(all(feature = "metrics", test))]
+mod metrics_test {
+    use batch_processing::core::metrics::registry;
+    use batch_processing::sync::job::job_builder::{JobBuilder, JobBuilderTrait};
+    use batch_processing::sync::step::{complex_step, Runner};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+
+    #[test]
+    fn test_job_metrics() {
+        let step = complex_step::get::<i32, i32>("measured-step".to_string())
+            .chunk_size(2)
+            .reader(Box::new(|| Box::new(vec![1, 2, 3].into_iter())))
+            .processor(Box::new(|| Box::new(|item: i32| item)))
+            .writer(Box::new(|| Box::new(|_items: &Vec<i32>| {})))
+            .build();
+
+        let job = JobBuilder::get(String::from("measured-job"))
+            .step(step)
+            .build();
+
+        let job_status = job.run();
+
+        assert!(job_status.status.is_ok());
+
+        let output = registry().render();
+        let labels = "{job=\"measured-job\",step=\"measured-step\"}";
+        assert!(output.contains("# TYPE batch_items_read_total counter"));
+        assert!(output.contains(&format!("batch_items_read_total{} 3", labels)));
+        assert!(output.contains(&format!("batch_items_written_total{} 3", labels)));
+        assert!(output.contains(&format!("batch_chunk_write_seconds_count{} 2", labels)));
+        assert!(output.contains(&format!("batch_step_duration_seconds_count{} 1", labels)));
+        assert!(output.contains("batch_job_runs_total{job=\"measured-job\",status=\"completed\"} 1"));
+        assert!(output.contains("batch_job_duration_seconds_bucket{job=\"measured-job\",le=\"21600\"} 1"));
+        assert!(output.contains("batch_job_duration_seconds_bucket{job=\"measured-job\",le=\"+Inf\"} 1"));
+        assert!(output.contains("batch_chunk_write_seconds_bucket{job=\"measured-job\",step=\"measured-step\",le=\"10\"} 2"));
+        assert!(!output.contains("batch_chunk_write_seconds_bucket{job=\"measured-job\",step=\"measured-step\",le=\"21600\"}"));
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(all(feature = "glob", test))]
+mod multi_resource_test {
+    use std::fs;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    use batch_processing::core::flat_file::{FieldSpec, FlatRecord, RecordLayout};
+    use batch_processing::core::item::ResourceItem;
+    use batch_processing::sync::item::flat_file::FlatFileItemReaderBuilder;
+    use batch_processing::sync::item::multi_resource::MultiResourceItemReaderBuilder;
+    use batch_processing::sync::step::{complex_step, Runner, SyncStep};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+    use tempfile::TempDir;
+
+    fn build_step(pattern: &str, restart_file: &Path, chunk_size: usize, failing_name: &'static str, written: Arc<Mutex<Vec<String>>>) -> SyncStep {
+        complex_step::get::<ResourceItem<FlatRecord>, String>("multi_resource_step".to_string())
+            .chunk_size(chunk_size)
+            .item_reader(Box::new(MultiResourceItemReaderBuilder::get(pattern)
+                .reader(Box::new(|path| Box::new(FlatFileItemReaderBuilder::get(path)
+                    .layout(RecordLayout::delimited("name", ',', vec![FieldSpec::column("name")]))
+                    .build())))
+                .restart_file(restart_file)
+                .build()))
+            .item_processor(Box::new(|record: ResourceItem<FlatRecord>| {
+                let name = record.item.text("name").unwrap();
+                format!("{}:{}:{}", record.resource.file_name().unwrap().to_string_lossy(), record.item.line, name)
+            }))
+            .item_writer(Box::new(move |names: Vec<String>| {
+                if names.iter().any(|name| name.ends_with(&format!(":{}", failing_name))) {
+                    panic!("Invalid name");
+                }
+                written.lock().unwrap().extend(names)
+            }))
+            .build()
+    }
+
+    #[test]
+    fn test_multi_resource_reader_resumes() {
+        let directory = TempDir::new().unwrap();
+        fs::create_dir_all(directory.path().join("nested.txt")).unwrap();
+        fs::write(directory.path().join("b.txt"), "carol\n\ndave\n").unwrap();
+        fs::write(directory.path().join("a.txt"), "alice\nbob\n").unwrap();
+        fs::write(directory.path().join("c.txt"), "erin\n").unwrap();
+        fs::write(directory.path().join("ignored.csv"), "frank\n").unwrap();
+        let pattern = directory.path().join("*.txt").to_string_lossy().to_string();
+        let restart_file = directory.path().join("restart");
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_step(&pattern, &restart_file, 1, "dave", written.clone());
+
+        assert!(step.run().status.is_err());
+        assert_eq!(*written.lock().unwrap(), vec!["a.txt:1:alice", "a.txt:2:bob", "b.txt:1:carol"]);
+        assert!(restart_file.exists());
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_step(&pattern, &restart_file, 1, "", written.clone());
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*written.lock().unwrap(), vec!["b.txt:1:carol", "b.txt:3:dave", "c.txt:1:erin"]);
+        assert!(!restart_file.exists());
+    }
+
+    #[test]
+    fn test_multi_resource_reader_resumes_unwritten_chunks() {
+        let directory = TempDir::new().unwrap();
+        fs::write(directory.path().join("a.txt"), "alice\nbob\ncarol\n").unwrap();
+        fs::write(directory.path().join("b.txt"), "dave\nerin\n").unwrap();
+        let pattern = directory.path().join("*.txt").to_string_lossy().to_string();
+        let restart_file = directory.path().join("restart");
+
+        // The second chunk spans both files, so b.txt is opened before carol is written.
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_step(&pattern, &restart_file, 2, "dave", written.clone());
+
+        assert!(step.run().status.is_err());
+        assert_eq!(*written.lock().unwrap(), vec!["a.txt:1:alice", "a.txt:2:bob"]);
+        assert_eq!(fs::read_to_string(&restart_file).unwrap(), directory.path().join("a.txt").to_string_lossy());
+
+        // The last chunk is written after every file has been read.
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_step(&pattern, &restart_file, 2, "erin", written.clone());
+
+        assert!(step.run().status.is_err());
+        assert_eq!(*written.lock().unwrap(), vec!["a.txt:1:alice", "a.txt:2:bob", "a.txt:3:carol", "b.txt:1:dave"]);
+        assert_eq!(fs::read_to_string(&restart_file).unwrap(), directory.path().join("b.txt").to_string_lossy());
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_step(&pattern, &restart_file, 2, "", written.clone());
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*written.lock().unwrap(), vec!["b.txt:1:dave", "b.txt:2:erin"]);
+        assert!(!restart_file.exists());
+    }
+}
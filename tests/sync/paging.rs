@@ -0,0 +1,213 @@
+#[cfg(test)]
+mod paging_test {
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    use batch_processing::sync::item::ItemReader;
+    use batch_processing::sync::item::paging::{KeysetItemReaderBuilder, PagingItemReaderBuilder};
+    use batch_processing::sync::step::{complex_step, Runner, SyncStep};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+    use tempfile::TempDir;
+
+    fn build_step(restart_file: std::path::PathBuf, chunk_size: usize, failing: u64, written: Arc<Mutex<Vec<u64>>>) -> SyncStep {
+        let items: Vec<u64> = (0..10).collect();
+        complex_step::get::<u64, u64>("paging_step".to_string())
+            .chunk_size(chunk_size)
+            .item_reader(Box::new(PagingItemReaderBuilder::get(Box::new(move |offset, limit| {
+                items.iter().skip(offset as usize).take(limit).copied().collect()
+            }))
+                .page_size(3)
+                .restart_file(restart_file)
+                .build()))
+            .item_processor(Box::new(move |item: u64| {
+                if item == failing {
+                    panic!("Invalid item");
+                }
+                item
+            }))
+            .item_writer(Box::new(move |items: Vec<u64>| written.lock().unwrap().extend(items)))
+            .build()
+    }
+
+    #[test]
+    fn test_paging_reader_resumes() {
+        let directory = TempDir::new().unwrap();
+        let restart_file = directory.path().join("restart");
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_step(restart_file.clone(), 1, 7, written.clone());
+
+        assert!(step.run().status.is_err());
+        assert_eq!(*written.lock().unwrap(), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(fs::read_to_string(&restart_file).unwrap().trim(), "7");
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_step(restart_file.clone(), 1, u64::MAX, written.clone());
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*written.lock().unwrap(), vec![7, 8, 9]);
+        assert!(!restart_file.exists());
+    }
+
+    #[test]
+    fn test_paging_reader_resumes_unwritten_chunks() {
+        let directory = TempDir::new().unwrap();
+        let restart_file = directory.path().join("restart");
+
+        // The second chunk holds items of the second and third pages, and is never written.
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_step(restart_file.clone(), 4, 6, written.clone());
+
+        assert!(step.run().status.is_err());
+        assert_eq!(*written.lock().unwrap(), vec![0, 1, 2, 3]);
+        assert_eq!(fs::read_to_string(&restart_file).unwrap().trim(), "4");
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_step(restart_file.clone(), 4, u64::MAX, written.clone());
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*written.lock().unwrap(), vec![4, 5, 6, 7, 8, 9]);
+        assert!(!restart_file.exists());
+    }
+
+    #[test]
+    fn test_keyset_reader() {
+        let ids: Vec<u32> = (1..=11).map(|id| id * 2).collect();
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let requested_clone = requested.clone();
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let written_clone = written.clone();
+
+        let step = complex_step::get::<u32, u32>("keyset_step".to_string())
+            .chunk_size(4)
+            .item_reader(Box::new(KeysetItemReaderBuilder::get(Box::new(move |after: Option<&u32>, limit| {
+                requested_clone.lock().unwrap().push(after.copied());
+                ids.iter().filter(|id| after.is_none_or(|after| *id > after)).take(limit).copied().collect()
+            }))
+                .key(Box::new(|id: &u32| *id))
+                .page_size(5)
+                .build()))
+            .item_processor(Box::new(|id: u32| id))
+            .item_writer(Box::new(move |ids: Vec<u32>| written_clone.lock().unwrap().extend(ids)))
+            .build();
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*written.lock().unwrap(), (1..=11).map(|id| id * 2).collect::<Vec<u32>>());
+        assert_eq!(*requested.lock().unwrap(), vec![None, Some(10), Some(20)]);
+    }
+
+    fn build_keyset_step(restart_file: std::path::PathBuf, failing: u32, written: Arc<Mutex<Vec<u32>>>) -> SyncStep {
+        let ids: Vec<u32> = (1..=11).map(|id| id * 2).collect();
+        complex_step::get::<u32, u32>("keyset_step".to_string())
+            .chunk_size(4)
+            .item_reader(Box::new(KeysetItemReaderBuilder::get(Box::new(move |after: Option<&u32>, limit| {
+                ids.iter().filter(|id| after.is_none_or(|after| *id > after)).take(limit).copied().collect()
+            }))
+                .key(Box::new(|id: &u32| *id))
+                .page_size(5)
+                .restart_file(restart_file)
+                .build()))
+            .item_processor(Box::new(|id: u32| id))
+            .item_writer(Box::new(move |ids: Vec<u32>| {
+                if ids.contains(&failing) {
+                    panic!("Invalid id");
+                }
+                written.lock().unwrap().extend(ids)
+            }))
+            .build()
+    }
+
+    #[test]
+    fn test_keyset_reader_resumes_unwritten_chunks() {
+        let directory = TempDir::new().unwrap();
+        let restart_file = directory.path().join("restart");
+
+        // The second chunk spans the first two pages, and is never written.
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_keyset_step(restart_file.clone(), 14, written.clone());
+
+        assert!(step.run().status.is_err());
+        assert_eq!(*written.lock().unwrap(), vec![2, 4, 6, 8]);
+        assert_eq!(fs::read_to_string(&restart_file).unwrap().trim(), "8");
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_keyset_step(restart_file.clone(), 0, written.clone());
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*written.lock().unwrap(), vec![10, 12, 14, 16, 18, 20, 22]);
+        assert!(!restart_file.exists());
+    }
+
+    fn build_string_keyset_step(restart_file: std::path::PathBuf, failing: &'static str, written: Arc<Mutex<Vec<String>>>) -> SyncStep {
+        // Sorted as bytes, so the keys with a leading space come first.
+        let keys: Vec<String> = [" a", " b", "a", "a ", "b"].iter().map(|key| key.to_string()).collect();
+        complex_step::get::<String, String>("string_keyset_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(KeysetItemReaderBuilder::get(Box::new(move |after: Option<&String>, limit| {
+                keys.iter().filter(|key| after.is_none_or(|after| *key > after)).take(limit).cloned().collect()
+            }))
+                .key(Box::new(|key: &String| key.clone()))
+                .page_size(2)
+                .restart_file(restart_file)
+                .build()))
+            .item_processor(Box::new(|key: String| key))
+            .item_writer(Box::new(move |keys: Vec<String>| {
+                if keys.iter().any(|key| key == failing) {
+                    panic!("Invalid key");
+                }
+                written.lock().unwrap().extend(keys)
+            }))
+            .build()
+    }
+
+    #[test]
+    fn test_keyset_reader_resumes_after_key_with_spaces() {
+        let directory = TempDir::new().unwrap();
+        let restart_file = directory.path().join("restart");
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_string_keyset_step(restart_file.clone(), "a", written.clone());
+
+        assert!(step.run().status.is_err());
+        assert_eq!(*written.lock().unwrap(), vec![" a", " b"]);
+        assert_eq!(fs::read_to_string(&restart_file).unwrap(), " b");
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step = build_string_keyset_step(restart_file.clone(), "", written.clone());
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*written.lock().unwrap(), vec!["a", "a ", "b"]);
+    }
+
+    #[test]
+    fn test_paging_reader_close_waits_for_fetch() {
+        let fetched = Arc::new(Mutex::new(Vec::new()));
+        let fetched_clone = fetched.clone();
+        let mut reader = PagingItemReaderBuilder::get(Box::new(move |offset, limit| {
+            if offset > 0 {
+                thread::sleep(Duration::from_millis(100));
+            }
+            fetched_clone.lock().unwrap().push(offset);
+            (offset..offset + limit as u64).collect()
+        }))
+            .page_size(2)
+            .build();
+
+        reader.open();
+        assert_eq!(reader.read(), Some(0));
+        assert_eq!(reader.read(), Some(1));
+        assert_eq!(reader.read(), Some(2));
+        reader.close();
+
+        assert_eq!(*fetched.lock().unwrap(), vec![0, 2, 4], "The page requested last should be fetched before the reader closes");
+    }
+
+    #[test]
+    #[should_panic(expected = "Key is required")]
+    fn test_keyset_reader_requires_key() {
+        KeysetItemReaderBuilder::<u32, u32>::get(Box::new(|_, _| Vec::new())).build();
+    }
+}
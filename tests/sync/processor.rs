@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod processor_test {
+    use std::sync::{Arc, Mutex};
+
+    use batch_processing::core::progress::{ProgressEvent, ProgressEvents};
+    use batch_processing::sync::item::processor::{ChainedItemProcessorBuilder, FlatMapItemProcessor, FlattenItemWriter, ValidatingItemProcessor};
+    use batch_processing::sync::job::job_builder::{JobBuilder, JobBuilderTrait};
+    use batch_processing::sync::step::{complex_step, Runner, SyncStep};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Car {
+        make: String,
+        price: u32,
+    }
+
+    fn parse(line: &'static str) -> Car {
+        let (make, price) = line.split_once(',').unwrap();
+        Car { make: make.to_string(), price: price.parse().unwrap() }
+    }
+
+    fn validate(car: &Car) -> Result<(), String> {
+        if car.make.is_empty() {
+            return Err(format!("car priced {} has no make", car.price));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_chained_processor() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let written_clone = written.clone();
+
+        let step = complex_step::get::<&'static str, String>("chained_processor_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(vec!["Kia,16000", "BMW,31000", "Ford,12500"].into_iter()))
+            .item_processor(Box::new(ChainedItemProcessorBuilder::get(Box::new(parse))
+                .then(Box::new(ValidatingItemProcessor::new(Box::new(validate))))
+                .then(Box::new(|car: Car| Car { price: car.price * 2, ..car }))
+                .then(Box::new(|car: Car| format!("{}:{}", car.make, car.price)))
+                .build()))
+            .item_writer(Box::new(move |cars: Vec<String>| written_clone.lock().unwrap().extend(cars)))
+            .build();
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*written.lock().unwrap(), vec!["Kia:32000", "BMW:62000", "Ford:25000"]);
+    }
+
+    #[test]
+    fn test_validating_processor_fails_step() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let written_clone = written.clone();
+
+        let step = complex_step::get::<&'static str, Car>("validating_processor_step".to_string())
+            .chunk_size(1)
+            .item_reader(Box::new(vec!["Kia,16000", ",9000", "Ford,12500"].into_iter()))
+            .item_processor(Box::new(ChainedItemProcessorBuilder::get(Box::new(parse))
+                .then(Box::new(ValidatingItemProcessor::new(Box::new(validate))))
+                .build()))
+            .item_writer(Box::new(move |cars: Vec<Car>| written_clone.lock().unwrap().extend(cars)))
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_err());
+        assert_eq!(*written.lock().unwrap(), vec![parse("Kia,16000")]);
+    }
+
+    #[test]
+    fn test_validating_processor_skips_invalid_items() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let written_clone = written.clone();
+
+        let step = complex_step::get::<&'static str, Car>("skipping_processor_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(vec!["Kia,16000", ",9000", "Ford,12500", ",7000"].into_iter()))
+            .item_processor(Box::new(ChainedItemProcessorBuilder::get(Box::new(parse))
+                .then(Box::new(ValidatingItemProcessor::new(Box::new(validate)).skip_invalid()))
+                .build()))
+            .item_writer(Box::new(move |cars: Vec<Car>| written_clone.lock().unwrap().extend(cars)))
+            .build();
+
+        let events = run_with_progress(step);
+
+        assert!(matches!(events.last(), Some(ProgressEvent::JobFinished { succeeded: true, .. })));
+        assert_eq!(*written.lock().unwrap(), vec![parse("Kia,16000"), parse("Ford,12500")]);
+        let skipped: Vec<&String> = events.iter()
+            .filter_map(|event| match event {
+                ProgressEvent::ItemsSkipped { item_count: 1, error, .. } => Some(error),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(skipped, vec!["Invalid item: car priced 9000 has no make", "Invalid item: car priced 7000 has no make"]);
+    }
+
+    fn run_with_progress(step: SyncStep) -> Vec<ProgressEvent> {
+        let progress = ProgressEvents::new();
+        let receiver = progress.subscribe();
+        let job = JobBuilder::get("processor_job".to_string())
+            .progress(progress)
+            .step(step)
+            .build();
+
+        job.run();
+        receiver.try_iter().collect()
+    }
+
+    #[test]
+    fn test_flat_map_processor() {
+        let chunks = Arc::new(Mutex::new(Vec::new()));
+        let chunks_clone = chunks.clone();
+
+        let step = complex_step::get::<&'static str, Vec<String>>("flat_map_processor_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(vec!["1:a,b", "2:", "3:c", "4:d,e,f"].into_iter()))
+            .item_processor(Box::new(FlatMapItemProcessor::new(Box::new(|order: &'static str| {
+                let (id, lines) = order.split_once(':').unwrap();
+                lines.split(',').filter(|line| !line.is_empty()).map(|line| format!("{}{}", id, line)).collect()
+            }))))
+            .item_writer(Box::new(FlattenItemWriter::new(Box::new(move |lines: Vec<String>| {
+                chunks_clone.lock().unwrap().push(lines)
+            }))))
+            .build();
+
+        let events = run_with_progress(step);
+
+        assert!(matches!(events.last(), Some(ProgressEvent::JobFinished { succeeded: true, .. })));
+        assert_eq!(*chunks.lock().unwrap(), vec![vec!["1a", "1b"], vec!["3c", "4d", "4e", "4f"]]);
+        let items_written: Vec<usize> = events.iter()
+            .filter_map(|event| match event {
+                ProgressEvent::ChunkWritten { items_written, .. } => Some(*items_written),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(items_written, vec![2, 6]);
+    }
+}
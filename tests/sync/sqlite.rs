@@ -0,0 +1,122 @@
+#[cfg(all(feature = "sqlite", test))]
+mod sqlite_test {
+    use std::path::{Path, PathBuf};
+
+    use rusqlite::Connection;
+    use rusqlite::types::Value;
+
+    use batch_processing::sync::item::sqlite::{SqliteItemReaderBuilder, SqliteItemWriterBuilder};
+    use batch_processing::sync::step::{complex_step, Runner};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+    use tempfile::TempDir;
+
+    struct Car {
+        id: i64,
+        make: Option<String>,
+        price: f64,
+    }
+
+    fn create_database(directory: &TempDir) -> PathBuf {
+        let path = directory.path().join("cars.db");
+
+        let connection = Connection::open(&path).unwrap();
+        connection.execute_batch(concat!(
+            "CREATE TABLE cars (id INTEGER PRIMARY KEY, make TEXT, price REAL NOT NULL);",
+            "CREATE TABLE listings (id INTEGER PRIMARY KEY, make TEXT NOT NULL, price REAL NOT NULL);",
+            "INSERT INTO cars VALUES (1, 'Ford', 21000.5), (2, 'Kia', 15000), (3, NULL, 9000), (4, 'Fiat', 8000), (5, 'Audi', 42000);",
+        )).unwrap();
+        path
+    }
+
+    fn listings(path: &Path) -> Vec<(i64, String, f64)> {
+        let connection = Connection::open(path).unwrap();
+        let mut statement = connection.prepare("SELECT id, make, price FROM listings ORDER BY id").unwrap();
+        statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    fn journal_mode(path: &Path) -> String {
+        Connection::open(path).unwrap().query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap()
+    }
+
+    fn run_step(path: &Path, minimum_price: f64, wal: bool) -> bool {
+        let mut reader = SqliteItemReaderBuilder::get(path);
+        let mut writer = SqliteItemWriterBuilder::get(path);
+        if wal {
+            reader = reader.wal();
+            writer = writer.wal();
+        }
+
+        let step = complex_step::get::<Car, Car>("sqlite_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(reader
+                .query("SELECT id, make, price FROM cars WHERE price >= ?1 ORDER BY id")
+                .parameters(vec![Value::Real(minimum_price)])
+                .mapper(Box::new(|row| Ok(Car { id: row.get("id")?, make: row.get("make")?, price: row.get("price")? })))
+                .build()))
+            .item_processor(Box::new(|car: Car| car))
+            .item_writer(Box::new(writer
+                .upsert("listings", &["id", "make", "price"], &["id"])
+                .binder(Box::new(|car: &Car| vec![car.id.into(), car.make.clone().into(), car.price.into()]))
+                .build()))
+            .throw_tolerant()
+            .build();
+
+        step.run().status.is_ok()
+    }
+
+    #[test]
+    fn test_sqlite_reader_and_upsert_writer() {
+        let directory = TempDir::new().unwrap();
+        let path = create_database(&directory);
+
+        assert!(run_step(&path, 10000.0, false));
+        assert_eq!(journal_mode(&path), "delete", "The journal mode should be left alone by default");
+        assert_eq!(listings(&path), vec![
+            (1, "Ford".to_string(), 21000.5),
+            (2, "Kia".to_string(), 15000.0),
+            (5, "Audi".to_string(), 42000.0),
+        ]);
+
+        Connection::open(&path).unwrap().execute("UPDATE cars SET price = 16000 WHERE id = 2", []).unwrap();
+
+        assert!(run_step(&path, 10000.0, false));
+        assert_eq!(listings(&path), vec![
+            (1, "Ford".to_string(), 21000.5),
+            (2, "Kia".to_string(), 16000.0),
+            (5, "Audi".to_string(), 42000.0),
+        ]);
+    }
+
+    #[test]
+    fn test_sqlite_writer_rolls_back_failed_chunk() {
+        let directory = TempDir::new().unwrap();
+        let path = create_database(&directory);
+
+        assert!(run_step(&path, 0.0, false));
+        assert_eq!(listings(&path), vec![
+            (1, "Ford".to_string(), 21000.5),
+            (2, "Kia".to_string(), 15000.0),
+            (5, "Audi".to_string(), 42000.0),
+        ]);
+    }
+
+    #[test]
+    fn test_sqlite_reader_and_writer_on_the_same_database() {
+        let directory = TempDir::new().unwrap();
+        let path = create_database(&directory);
+        // More rows than the reader maps ahead, so the query is still running while chunks are written.
+        Connection::open(&path).unwrap()
+            .execute("WITH RECURSIVE ids(id) AS (SELECT 6 UNION ALL SELECT id + 1 FROM ids WHERE id < 1000) INSERT INTO cars SELECT id, 'Fiat', 10000 + id FROM ids", [])
+            .unwrap();
+
+        assert!(run_step(&path, 10000.0, true));
+        assert_eq!(journal_mode(&path), "wal");
+        let listings = listings(&path);
+        assert_eq!(listings.len(), 998);
+        assert_eq!(listings.last(), Some(&(1000, "Fiat".to_string(), 11000.0)));
+    }
+}
@@ -1,7 +1,13 @@
 #[cfg(test)]
 mod complex_step_test {
     use std::sync::{Arc, Mutex};
+    use std::thread::sleep;
+    use std::time::Duration;
 
+    use batch_processing::core::chunk::AdaptiveChunkSize;
+    use batch_processing::core::listener::{ChunkListener, ItemListener};
+    use batch_processing::sync::item::{ItemReader, ItemWriter};
+    use batch_processing::sync::item::processor::ValidatingItemProcessor;
     use batch_processing::sync::step::{complex_step, Runner};
     use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
     use batch_processing::sync::step::step_builder::StepBuilderTrait;
@@ -42,4 +48,332 @@ mod complex_step_test {
 
         assert!(step_result.status.is_ok(), "The step should be successful")
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_complex_step_with_flush_interval() {
+        let chunks: Arc<Mutex<Vec<Vec<i64>>>> = Arc::new(Mutex::new(Vec::new()));
+        let chunks_clone = chunks.clone();
+
+        let step = complex_step::get::<i64, i64>("complex_step_with_flush_interval".to_string())
+            .chunk_size(10)
+            .flush_interval(Duration::from_millis(200))
+            .reader(Box::new(|| {
+                Box::new((1..=5).inspect(|x| {
+                    if *x == 3 {
+                        sleep(Duration::from_millis(250));
+                    }
+                }))
+            }))
+            .processor(Box::new(|| {
+                Box::new(|x: i64| x)
+            }))
+            .writer(Box::new(move || {
+                let chunks = chunks_clone.clone();
+                Box::new(
+                    move |x: &Vec<i64>| {
+                        chunks.lock().unwrap().push(x.clone());
+                    }
+                )
+            }))
+        .build();
+
+        let step_result = step.run();
+
+        let chunks = chunks.lock().unwrap();
+
+        assert!(step_result.status.is_ok(), "The step should be successful");
+
+        assert_eq!(*chunks, vec![vec![1, 2, 3], vec![4, 5]], "The expired chunk should be written with the next item read");
+    }
+
+    #[test]
+    fn test_complex_step_with_weigher() {
+        let chunks: Arc<Mutex<Vec<Vec<String>>>> = Arc::new(Mutex::new(Vec::new()));
+        let chunks_clone = chunks.clone();
+
+        let step = complex_step::get::<String, String>("complex_step_with_weigher".to_string())
+            .chunk_size(10)
+            .weigher(Box::new(|x: &String| x.len()), 5)
+            .reader(Box::new(|| {
+                Box::new(vec![String::from("aaaa"), String::from("bb"), String::from("cc"), String::from("dddddd")].into_iter())
+            }))
+            .processor(Box::new(|| {
+                Box::new(|x: String| x)
+            }))
+            .writer(Box::new(move || {
+                let chunks = chunks_clone.clone();
+                Box::new(
+                    move |x: &Vec<String>| {
+                        chunks.lock().unwrap().push(x.clone());
+                    }
+                )
+            }))
+        .build();
+
+        let step_result = step.run();
+
+        let chunks = chunks.lock().unwrap();
+
+        assert!(step_result.status.is_ok(), "The step should be successful");
+
+        assert_eq!(chunks.len(), 2, "A chunk should be written each time the weight limit is reached");
+
+        assert_eq!(chunks[0], vec![String::from("aaaa"), String::from("bb")]);
+    }
+
+    #[test]
+    fn test_complex_step_with_adaptive_chunk_size() {
+        let step = complex_step::get::<i64, i64>("complex_step_with_adaptive_chunk_size".to_string())
+            .chunk_size(8)
+            .adaptive_chunk_size(AdaptiveChunkSize {
+                min: 2,
+                max: 8,
+                target_latency: Duration::from_millis(1),
+            })
+            .reader(Box::new(|| {
+                Box::new(0..20)
+            }))
+            .processor(Box::new(|| {
+                Box::new(|x: i64| x)
+            }))
+            .writer(Box::new(|| {
+                Box::new(|_: &Vec<i64>| {
+                    sleep(Duration::from_millis(10));
+                })
+            }))
+        .build();
+
+        let step_result = step.run();
+
+        assert!(step_result.status.is_ok(), "The step should be successful");
+
+        assert_eq!(step_result.chunk_sizes.first(), Some(&8), "The chunk size should start from the configured one");
+
+        assert_eq!(step_result.chunk_sizes.last(), Some(&2), "Slow writes should shrink the chunk size down to the minimum");
+    }
+
+    #[test]
+    fn test_tolerant_complex_step_with_adaptive_chunk_size() {
+        let failures = Arc::new(Mutex::new(0));
+
+        let step = complex_step::get::<i64, i64>("tolerant_complex_step_with_adaptive_chunk_size".to_string())
+            .throw_tolerant()
+            .chunk_size(8)
+            .adaptive_chunk_size(AdaptiveChunkSize {
+                min: 1,
+                max: 8,
+                target_latency: Duration::from_secs(1),
+            })
+            .reader(Box::new(|| {
+                Box::new(0..20)
+            }))
+            .processor(Box::new(|| {
+                Box::new(|x: i64| x)
+            }))
+            .writer(Box::new(move || {
+                let failures = failures.clone();
+                Box::new(move |_: &Vec<i64>| {
+                    let mut failures = failures.lock().unwrap();
+                    if *failures < 3 {
+                        *failures += 1;
+                        panic!("Writer failed");
+                    }
+                })
+            }))
+        .build();
+
+        let step_result = step.run();
+
+        assert!(step_result.status.is_ok(), "The tolerant step should skip the failed writes");
+
+        assert_eq!(step_result.chunk_sizes[..4], [8, 4, 2, 1], "Every failed write should halve the chunk size");
+    }
+
+    #[derive(Default)]
+    struct RecordingListener {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl ChunkListener for RecordingListener {
+        fn before_chunk(&self, chunk_index: usize) {
+            self.events.lock().unwrap().push(format!("before chunk {}", chunk_index));
+        }
+
+        fn after_chunk(&self, chunk_index: usize) {
+            self.events.lock().unwrap().push(format!("after chunk {}", chunk_index));
+        }
+
+        fn on_chunk_error(&self, chunk_index: usize, _error: &str) {
+            self.events.lock().unwrap().push(format!("error chunk {}", chunk_index));
+        }
+    }
+
+    impl ItemListener<i32, i32> for RecordingListener {
+        fn after_read(&self, item: &i32) {
+            self.events.lock().unwrap().push(format!("read {}", item));
+        }
+
+        fn after_process(&self, output: &i32) {
+            self.events.lock().unwrap().push(format!("process {}", output));
+        }
+
+        fn after_write(&self, count: usize) {
+            self.events.lock().unwrap().push(format!("write {}", count));
+        }
+
+        fn on_write_error(&self, error: &str) {
+            self.events.lock().unwrap().push(format!("write error {}", error));
+        }
+    }
+
+    #[test]
+    fn test_complex_step_with_listeners() {
+        let listener = Arc::new(RecordingListener::default());
+
+        let step = complex_step::get::<i32, i32>("complex_step".to_string())
+            .throw_tolerant()
+            .chunk_size(2)
+            .reader(Box::new(|| Box::new(vec![1, 2, 3].into_iter())))
+            .processor(Box::new(|| Box::new(|item: i32| item * 10)))
+            .writer(Box::new(|| Box::new(|items: &Vec<i32>| {
+                if items.len() == 1 {
+                    panic!("Writer failed");
+                }
+            })))
+            .chunk_listener(listener.clone())
+            .item_listener(listener.clone())
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_ok(), "The tolerant step should skip the failed write");
+        assert_eq!(*listener.events.lock().unwrap(), vec![
+            "read 1", "before chunk 0", "process 10",
+            "read 2", "process 20", "write 2", "after chunk 0",
+            "read 3", "before chunk 1", "process 30", "write error Writer failed", "error chunk 1",
+        ]);
+    }
+
+    #[derive(Default)]
+    struct ProcessListener {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl ItemListener<i32, i32> for ProcessListener {
+        fn before_process(&self, item: &i32) {
+            self.events.lock().unwrap().push(format!("before process {}", item));
+        }
+
+        fn after_process(&self, output: &i32) {
+            self.events.lock().unwrap().push(format!("after process {}", output));
+        }
+
+        fn on_skip(&self, reason: &str) {
+            self.events.lock().unwrap().push(format!("skip: {}", reason));
+        }
+    }
+
+    #[test]
+    fn test_complex_step_with_skipped_item_listener() {
+        let listener = Arc::new(ProcessListener::default());
+        let validator = |item: &i32| if *item == 2 { Err(String::from("two")) } else { Ok(()) };
+
+        let step = complex_step::get::<i32, i32>("skipping_step".to_string())
+            .chunk_size(3)
+            .item_reader(Box::new(1..=3))
+            .item_processor(Box::new(ValidatingItemProcessor::new(Box::new(validator)).skip_invalid()))
+            .item_writer(Box::new(|_: Vec<i32>| {}))
+            .item_listener(listener.clone())
+            .build();
+
+        assert!(step.run().status.is_ok());
+        assert_eq!(*listener.events.lock().unwrap(), vec![
+            "before process 1", "after process 1",
+            "before process 2", "skip: Invalid item: two",
+            "before process 3", "after process 3",
+        ]);
+    }
+
+    /// A reader counting down from its start, recording its lifecycle.
+    struct CountdownReader {
+        next: u32,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ItemReader<u32> for CountdownReader {
+        fn open(&mut self) {
+            self.events.lock().unwrap().push(String::from("open reader"));
+        }
+
+        fn read(&mut self) -> Option<u32> {
+            let item = self.next;
+            self.next = self.next.checked_sub(1)?;
+            Some(item)
+        }
+
+        fn close(&mut self) {
+            self.events.lock().unwrap().push(String::from("close reader"));
+        }
+    }
+
+    /// A writer buffering its chunks and flushing them when closed.
+    struct BufferedWriter {
+        buffer: Vec<u32>,
+        flushed: Arc<Mutex<Vec<u32>>>,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ItemWriter<u32> for BufferedWriter {
+        fn write(&mut self, items: Vec<u32>) {
+            self.buffer.extend(items);
+        }
+
+        fn close(&mut self) {
+            self.flushed.lock().unwrap().append(&mut self.buffer);
+            self.events.lock().unwrap().push(String::from("close writer"));
+        }
+    }
+
+    #[test]
+    fn test_complex_step_with_item_traits() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let flushed = Arc::new(Mutex::new(Vec::new()));
+        let mut seen = 0;
+
+        let step = complex_step::get::<u32, u32>("item_traits_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(CountdownReader { next: 4, events: Arc::clone(&events) }))
+            .item_processor(Box::new(move |item: u32| {
+                seen += 1;
+                item * 10 + seen
+            }))
+            .item_writer(Box::new(BufferedWriter {
+                buffer: Vec::new(),
+                flushed: Arc::clone(&flushed),
+                events: Arc::clone(&events),
+            }))
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_ok());
+        assert_eq!(*flushed.lock().unwrap(), vec![41, 32, 23, 14]);
+        assert_eq!(*events.lock().unwrap(), vec!["open reader", "close reader", "close writer"]);
+    }
+
+    #[test]
+    fn test_complex_step_closes_on_failure() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let step = complex_step::get::<u32, u32>("failing_item_traits_step".to_string())
+            .item_reader(Box::new(CountdownReader { next: 2, events: Arc::clone(&events) }))
+            .item_processor(Box::new(|item: u32| item))
+            .item_writer(Box::new(|_items: Vec<u32>| panic!("Writer failed")))
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_err());
+        assert_eq!(*events.lock().unwrap(), vec!["open reader", "close reader"]);
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod job_step_test {
+    use std::sync::{Arc, Mutex};
+
+    use batch_processing::sync::job::Job;
+    use batch_processing::sync::job::job_builder::{JobBuilder, JobBuilderTrait};
+    use batch_processing::sync::step::{job_step, Runner};
+    use batch_processing::sync::step::job_step::JobStepBuilderTrait;
+    use batch_processing::sync::step::simple_step::{SimpleStepBuilder, SimpleStepBuilderTrait};
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+
+    #[test]
+    fn test_job_step() {
+        let child_step = SimpleStepBuilder::get(String::from("child-step"))
+            .tasklet(Box::new(|| {
+                println!("Child step");
+            }))
+            .build();
+        let child_job = JobBuilder::get(String::from("child-job"))
+            .step(child_step)
+            .build();
+
+        let step = job_step::get(String::from("job-step"))
+            .job(child_job)
+            .build();
+
+        let parent_job = JobBuilder::get(String::from("parent-job"))
+            .step(step)
+            .build();
+
+        let job_status = parent_job.run();
+
+        assert!(job_status.status.is_ok(), "The parent job should be successful");
+
+        let child_status = job_status.steps_status[0].job_status.as_ref().expect("The child job status should be nested");
+
+        assert_eq!(child_status.name, "child-job");
+
+        assert_eq!(child_status.steps_status[0].name, "child-step");
+    }
+
+    fn failing_job() -> Job {
+        let child_step = SimpleStepBuilder::get(String::from("child-step"))
+            .tasklet(Box::new(|| panic!("Child step failed")))
+            .build();
+        JobBuilder::get(String::from("child-job"))
+            .step(child_step)
+            .build()
+    }
+
+    fn recording_step(name: &str, ran: &Arc<Mutex<Vec<String>>>) -> SimpleStepBuilder {
+        let ran = Arc::clone(ran);
+        let step_name = name.to_string();
+        SimpleStepBuilder::get(name.to_string())
+            .tasklet(Box::new(move || ran.lock().unwrap().push(step_name.clone())))
+    }
+
+    #[test]
+    fn test_failing_job_step_stops_parent_job() {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+
+        let parent_job = JobBuilder::get(String::from("parent-job"))
+            .step(recording_step("first", &ran).decider(Box::new(|| true)).build())
+            .step(job_step::get(String::from("job-step")).job(failing_job()).build())
+            .step(recording_step("last", &ran).build())
+            .build();
+
+        let job_status = parent_job.run();
+
+        assert!(job_status.status.is_err(), "The failed child job should fail the parent job");
+        assert_eq!(*ran.lock().unwrap(), vec!["first"], "The steps after the failed job step should not run");
+        assert!(job_status.steps_status[1].job_status.as_ref().unwrap().status.is_err());
+    }
+
+    #[test]
+    fn test_tolerant_job_step_continues_parent_job() {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+
+        let parent_job = JobBuilder::get(String::from("parent-job"))
+            .step(job_step::get(String::from("job-step")).job(failing_job()).throw_tolerant().build())
+            .step(recording_step("skipped", &ran).decider(Box::new(|| false)).build())
+            .step(recording_step("last", &ran).build())
+            .build();
+
+        let job_status = parent_job.run();
+
+        assert!(job_status.status.is_ok(), "The tolerant job step should not fail the parent job");
+        assert_eq!(*ran.lock().unwrap(), vec!["last"]);
+    }
+}
@@ -0,0 +1,205 @@
+#[cfg(test)]
+mod partition_step_test {
+    use std::fs;
+    use std::io::{self, Write};
+    use std::path::Path;
+    use std::process;
+    use std::sync::{Arc, Mutex};
+    use batch_processing::core::partition::{Partition, Partitioner, RangePartitioner, RANGE_MAX, RANGE_MIN};
+    use batch_processing::core::partition::process::{self as partition_process, ProcessPartitionHandler};
+    use batch_processing::core::progress::{ProgressEvent, ProgressEvents};
+    use batch_processing::core::step::StepStatus;
+    use batch_processing::sync::job::job_builder::{JobBuilder, JobBuilderTrait};
+    use batch_processing::sync::step::{partition_step, Runner};
+    use batch_processing::sync::step::partition_step::PartitionStepBuilderTrait;
+    use batch_processing::sync::step::simple_step::{SimpleStepBuilder, SimpleStepBuilderTrait};
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_range_partitioner() {
+        let partitions = RangePartitioner { min: 1, max: 10 }.partition(3);
+
+        let ranges: Vec<(i64, i64)> = partitions.iter()
+            .map(|partition| (partition.parse(RANGE_MIN).unwrap(), partition.parse(RANGE_MAX).unwrap()))
+            .collect();
+
+        assert_eq!(ranges, vec![(1, 4), (5, 7), (8, 10)]);
+    }
+
+    #[test]
+    fn test_range_partitioner_with_widest_ranges() {
+        let ranges = |partitioner: RangePartitioner, grid_size: usize| -> Vec<(i64, i64)> {
+            partitioner.partition(grid_size).iter()
+                .map(|partition| (partition.parse(RANGE_MIN).unwrap(), partition.parse(RANGE_MAX).unwrap()))
+                .collect()
+        };
+
+        assert_eq!(ranges(RangePartitioner { min: i64::MIN, max: i64::MAX }, 2), vec![(i64::MIN, -1), (0, i64::MAX)]);
+        assert_eq!(ranges(RangePartitioner { min: i64::MIN, max: i64::MAX }, 1), vec![(i64::MIN, i64::MAX)]);
+        assert_eq!(ranges(RangePartitioner { min: i64::MAX, max: i64::MAX }, 3), vec![(i64::MAX, i64::MAX)]);
+    }
+
+    #[test]
+    fn test_partition_step() {
+        let sum = Arc::new(Mutex::new(0));
+        let worker_sum = Arc::clone(&sum);
+
+        let step = partition_step::get(String::from("partition-step"))
+            .partitioner(Box::new(RangePartitioner { min: 1, max: 100 }))
+            .grid_size(4)
+            .workers(2)
+            .worker(Box::new(move |partition| {
+                let sum = Arc::clone(&worker_sum);
+                let min: i64 = partition.parse(RANGE_MIN).unwrap();
+                let max: i64 = partition.parse(RANGE_MAX).unwrap();
+                SimpleStepBuilder::get(partition.name)
+                    .tasklet(Box::new(move || {
+                        *sum.lock().unwrap() += (min..=max).sum::<i64>();
+                    }))
+                    .build()
+            }))
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_ok());
+        assert_eq!(step_status.partitions_status.len(), 4);
+        assert_eq!(*sum.lock().unwrap(), 5050);
+    }
+
+    #[test]
+    fn test_failed_partition() {
+        let step = partition_step::get(String::from("partition-step"))
+            .partitioner(Box::new(RangePartitioner { min: 0, max: 2 }))
+            .grid_size(3)
+            .worker(Box::new(|partition| {
+                let fail = partition.get(RANGE_MIN) == Some("1");
+                SimpleStepBuilder::get(partition.name)
+                    .tasklet(Box::new(move || {
+                        if fail {
+                            panic!("Partition failed");
+                        }
+                    }))
+                    .build()
+            }))
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_err(), "A failed partition should fail the step");
+        assert_eq!(step_status.partitions_status.len(), 3, "The other partitions should still run");
+        assert_eq!(step_status.partitions_status.iter().filter(|status| status.status.is_err()).count(), 1);
+    }
+
+    fn run_process_partition(step_name: &str, partition: Partition) -> StepStatus {
+        let min: i64 = partition.parse(RANGE_MIN).unwrap();
+        let max: i64 = partition.parse(RANGE_MAX).unwrap();
+        // The second partition of `process-step` crashes once, the one of `crashing-step` always
+        // crashes and the one of `oversized-step` announces a frame larger than any valid one.
+        if min == 2 && step_name == "oversized-step" {
+            let mut stdout = io::stdout();
+            stdout.write_all(b"BPPF\xff\xff\xff\xff").unwrap();
+            stdout.flush().unwrap();
+        }
+        let marker = Path::new(partition.get("crash_marker").unwrap());
+        if min == 2 && (step_name == "crashing-step" || !marker.exists()) {
+            fs::write(marker, "").unwrap();
+            process::abort();
+        }
+        SimpleStepBuilder::get(partition.name)
+            .tasklet_with_exit_status(Box::new(move || (min..=max).sum::<i64>().to_string()))
+            .build()
+            .run()
+    }
+
+    /// Serves a partition when the test binary is spawned as a partition worker.
+    #[test]
+    fn partition_worker() {
+        if let Some(step_name) = partition_process::worker_step_name() {
+            partition_process::serve_stdio(|partition| run_process_partition(&step_name, partition)).unwrap();
+        }
+    }
+
+    fn process_handler() -> ProcessPartitionHandler {
+        ProcessPartitionHandler::new()
+            .args(vec![
+                String::from("--exact"),
+                String::from("sync::step::partition_step::partition_step_test::partition_worker"),
+                String::from("--test-threads=1"),
+            ])
+            .max_restarts(1)
+    }
+
+    /// Partitions the ids from 1 to 3, telling the workers where to record that a partition crashed.
+    fn crash_partitioner(directory: &TempDir) -> Box<dyn Partitioner> {
+        let marker = directory.path().join("crashed").to_string_lossy().to_string();
+        Box::new(move |grid_size| {
+            RangePartitioner { min: 1, max: 3 }.partition(grid_size).into_iter()
+                .map(|partition| partition.parameter("crash_marker", marker.clone()))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_process_partition_step() {
+        let directory = TempDir::new().unwrap();
+
+        let step = partition_step::get(String::from("process-step"))
+            .partitioner(crash_partitioner(&directory))
+            .grid_size(3)
+            .workers(3)
+            .process_handler(process_handler())
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_ok(), "The crashed partition should be restarted");
+        let sum: i64 = step_status.partitions_status.iter()
+            .map(|status| status.exit_status.parse::<i64>().unwrap())
+            .sum();
+        assert_eq!(sum, 6);
+
+        let step = partition_step::get(String::from("crashing-step"))
+            .partitioner(crash_partitioner(&directory))
+            .grid_size(3)
+            .workers(3)
+            .process_handler(process_handler())
+            .build();
+
+        let step_status = step.run();
+
+        assert!(step_status.status.is_err(), "A partition crashing after its restarts should fail the step");
+        assert_eq!(step_status.partitions_status.iter().filter(|status| status.status.is_err()).count(), 1);
+    }
+
+    #[test]
+    fn test_process_partition_with_oversized_frame() {
+        let directory = TempDir::new().unwrap();
+
+        let step = partition_step::get(String::from("oversized-step"))
+            .partitioner(crash_partitioner(&directory))
+            .grid_size(3)
+            .workers(3)
+            .process_handler(process_handler())
+            .build();
+        let progress = ProgressEvents::new();
+        let receiver = progress.subscribe();
+        let job = JobBuilder::get(String::from("oversized-job"))
+            .progress(progress)
+            .step(step)
+            .build();
+
+        let job_status = job.run();
+
+        assert!(job_status.status.is_err(), "An oversized frame should fail its partition");
+        let errors: Vec<String> = receiver.try_iter()
+            .filter_map(|event| match event {
+                ProgressEvent::Retried { error, .. } => Some(error),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("maximum frame length"), "The frame should be rejected before reading it: {}", errors[0]);
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(all(feature = "tracing", test))]
+mod trace_test {
+    use batch_processing::sync::job::job_builder::{JobBuilder, JobBuilderTrait};
+    use batch_processing::sync::step::{complex_step, Runner};
+    use batch_processing::sync::step::complex_step::ComplexStepBuilderTrait;
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+
+    use crate::utils::trace::span_recorder;
+
+    #[test]
+    fn test_job_spans() {
+        let recorder = span_recorder();
+
+        let step = complex_step::get::<i32, i32>("traced-step".to_string())
+            .chunk_size(2)
+            .reader(Box::new(|| Box::new(vec![1, 2, 3].into_iter())))
+            .processor(Box::new(|| Box::new(|item: i32| item)))
+            .writer(Box::new(|| Box::new(|_items: &Vec<i32>| {})))
+            .build();
+
+        let job = JobBuilder::get(String::from("traced-job"))
+            .step(step)
+            .build();
+
+        let job_status = job.run();
+
+        assert!(job_status.status.is_ok());
+
+        let job_spans = recorder.spans("job", "job_name", "traced-job");
+        assert_eq!(job_spans.len(), 1);
+        assert_eq!(job_spans[0].fields["status"], "COMPLETED");
+        assert!(job_spans[0].fields.contains_key("duration_ms"));
+
+        let step_spans = recorder.spans("step", "step_name", "traced-step");
+        assert_eq!(step_spans.len(), 1);
+        assert_eq!(step_spans[0].fields["status"], "COMPLETED");
+
+        let chunk_spans = recorder.spans("chunk", "step_name", "traced-step");
+        let item_counts: Vec<&str> = chunk_spans.iter().map(|span| span.fields["item_count"].as_str()).collect();
+        assert_eq!(item_counts, vec!["2", "1"]);
+    }
+}
@@ -0,0 +1,113 @@
+#[cfg(all(feature = "async", test))]
+mod async_composite_test {
+    use std::sync::{Arc, Mutex};
+
+    use futures::future::BoxFuture;
+
+    use batch_processing::core::item::CompositeMode;
+    use batch_processing::tokio::item::AsyncItemWriter;
+    use batch_processing::tokio::item::composite::{AsyncClassifierItemWriterBuilder, AsyncCompositeItemWriterBuilder};
+    use batch_processing::tokio::step::AsyncStepRunner;
+    use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+
+    fn collect(written: &Arc<Mutex<Vec<u32>>>) -> Box<dyn AsyncItemWriter<u32>> {
+        let written = written.clone();
+        Box::new(move |items: Vec<u32>| -> BoxFuture<'static, ()> {
+            let written = written.clone();
+            Box::pin(async move { written.lock().unwrap().extend(items) })
+        })
+    }
+
+    fn failing() -> Box<dyn AsyncItemWriter<u32>> {
+        Box::new(|_: Vec<u32>| -> BoxFuture<'static, ()> { Box::pin(async move { panic!("Audit file unavailable") }) })
+    }
+
+    fn failing_on(item: u32, written: &Arc<Mutex<Vec<u32>>>) -> Box<dyn AsyncItemWriter<u32>> {
+        let written = written.clone();
+        Box::new(move |items: Vec<u32>| -> BoxFuture<'static, ()> {
+            let written = written.clone();
+            Box::pin(async move {
+                if items.contains(&item) {
+                    panic!("Audit file unavailable");
+                }
+                written.lock().unwrap().extend(items)
+            })
+        })
+    }
+
+    async fn run_step(writer: Box<dyn AsyncItemWriter<u32>>) -> bool {
+        let step: AsyncComplexStepBuilder<u32, u32> = AsyncComplexStepBuilder::get("async_composite_step".to_string())
+            .chunk_size(3)
+            .item_reader(Box::new(futures::stream::iter(1..=7)))
+            .item_processor(Box::new(|item: u32| -> BoxFuture<'static, u32> { Box::pin(async move { item }) }))
+            .item_writer(writer);
+
+        step.build().run().await.status.is_ok()
+    }
+
+    fn sorted(written: &Arc<Mutex<Vec<u32>>>) -> Vec<u32> {
+        let mut written = written.lock().unwrap().clone();
+        written.sort();
+        written
+    }
+
+    #[tokio::test]
+    async fn test_composite_writer() {
+        let database = Arc::new(Mutex::new(Vec::new()));
+        let audit = Arc::new(Mutex::new(Vec::new()));
+
+        assert!(run_step(Box::new(AsyncCompositeItemWriterBuilder::get()
+            .writer(collect(&database))
+            .writer(collect(&audit))
+            .build())).await);
+        assert_eq!(sorted(&database), (1..=7).collect::<Vec<u32>>());
+        assert_eq!(sorted(&audit), (1..=7).collect::<Vec<u32>>());
+
+        let database = Arc::new(Mutex::new(Vec::new()));
+        assert!(!run_step(Box::new(AsyncCompositeItemWriterBuilder::get()
+            .writer(failing())
+            .writer(collect(&database))
+            .build())).await);
+        assert!(database.lock().unwrap().is_empty(), "The writers after a failing one should not be called");
+
+        let database = Arc::new(Mutex::new(Vec::new()));
+        assert!(run_step(Box::new(AsyncCompositeItemWriterBuilder::get()
+            .writer(failing())
+            .writer(collect(&database))
+            .mode(CompositeMode::BestEffort)
+            .build())).await);
+        assert_eq!(sorted(&database), (1..=7).collect::<Vec<u32>>());
+    }
+
+    #[tokio::test]
+    async fn test_composite_writer_all_or_nothing() {
+        let database = Arc::new(Mutex::new(Vec::new()));
+        let audit = Arc::new(Mutex::new(Vec::new()));
+        let compensated = database.clone();
+
+        assert!(!run_step(Box::new(AsyncCompositeItemWriterBuilder::get()
+            .compensated_writer(collect(&database), Box::new(move |items: Vec<u32>| -> BoxFuture<'static, ()> {
+                let compensated = compensated.clone();
+                Box::pin(async move { compensated.lock().unwrap().retain(|item| !items.contains(item)) })
+            }))
+            .writer(failing_on(5, &audit))
+            .mode(CompositeMode::AllOrNothing)
+            .build())).await);
+        assert_eq!(sorted(&database), sorted(&audit), "The chunk failing the audit should be removed from the database");
+        assert!(!sorted(&database).contains(&5));
+    }
+
+    #[tokio::test]
+    async fn test_classifier_writer() {
+        let even = Arc::new(Mutex::new(Vec::new()));
+        let odd = Arc::new(Mutex::new(Vec::new()));
+
+        assert!(run_step(Box::new(AsyncClassifierItemWriterBuilder::get(Box::new(|item: &u32| item.is_multiple_of(2)))
+            .route(true, collect(&even))
+            .route(false, collect(&odd))
+            .build())).await);
+        assert_eq!(sorted(&even), vec![2, 4, 6]);
+        assert_eq!(sorted(&odd), vec![1, 3, 5, 7]);
+    }
+}
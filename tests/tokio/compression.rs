@@ -0,0 +1,88 @@
+#[cfg(all(feature = "async", feature = "compression", feature = "csv", feature = "json", test))]
+mod async_compression_test {
+    use std::fs;
+
+    use serde::{Deserialize, Serialize};
+
+    use batch_processing::core::compression::Compression;
+    use batch_processing::core::json::JsonFormat;
+    use batch_processing::tokio::item::{AsyncItemReader, AsyncItemWriter};
+    use batch_processing::tokio::item::csv::{AsyncCsvItemReaderBuilder, AsyncCsvItemWriterBuilder};
+    use batch_processing::tokio::item::json::{AsyncJsonItemReaderBuilder, AsyncJsonItemWriterBuilder};
+    use batch_processing::tokio::step::AsyncStepRunner;
+    use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+    use tempfile::TempDir;
+
+    macro_rules! resource_file {($fname:expr) => (
+      concat!(env!("CARGO_MANIFEST_DIR"), "/tests/resources/", $fname)
+    )}
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct CarSale {
+        make: String,
+        model: String,
+        sellingprice: f64,
+    }
+
+    async fn read_all<T>(reader: &mut dyn AsyncItemReader<T>) -> Vec<T> {
+        let mut items = Vec::new();
+        reader.open().await;
+        while let Some(item) = reader.read().await {
+            items.push(item);
+        }
+        reader.close().await;
+        items
+    }
+
+    #[tokio::test]
+    async fn test_zip_to_gzip_csv() {
+        let directory = TempDir::new().unwrap();
+        let output = directory.path().join("car_sales.csv.gz");
+
+        let step: AsyncComplexStepBuilder<CarSale, CarSale> = AsyncComplexStepBuilder::get("async_gzip_step".to_string())
+            .chunk_size(1000)
+            .item_reader(Box::new(AsyncCsvItemReaderBuilder::get(resource_file!("car_prices.zip")).zip_entry("car_prices.csv").build()))
+            .item_processor(Box::new(|sale: CarSale| -> futures::future::BoxFuture<'static, CarSale> {
+                Box::pin(async move { sale })
+            }))
+            .item_writer(Box::new(AsyncCsvItemWriterBuilder::get(&output).build()));
+
+        let step_status = step.build().run().await;
+
+        assert!(step_status.status.is_ok());
+        assert_eq!(&fs::read(&output).unwrap()[..2], &[0x1f, 0x8b]);
+
+        let mut reader = AsyncCsvItemReaderBuilder::get(&output).build();
+        let sales: Vec<CarSale> = read_all(&mut reader).await;
+        assert_eq!(sales.len(), 8039);
+        assert_eq!(sales.iter().map(|sale| sale.sellingprice).sum::<f64>(), 113000176.0);
+    }
+
+    #[tokio::test]
+    async fn test_zstd_json_round_trip() {
+        let directory = TempDir::new().unwrap();
+        let output = directory.path().join("car_sales.json.zst");
+        let sales = vec![
+            CarSale { make: "Kia".to_string(), model: "Rio".to_string(), sellingprice: 15000.0 },
+            CarSale { make: "Ford".to_string(), model: "Focus".to_string(), sellingprice: 21000.5 },
+        ];
+
+        let writer = AsyncJsonItemWriterBuilder::get(&output).format(JsonFormat::Array).build();
+        writer.open().await;
+        writer.write(sales).await;
+        writer.close().await;
+
+        assert_eq!(&fs::read(&output).unwrap()[..4], &[0x28, 0xb5, 0x2f, 0xfd]);
+
+        let mut reader = AsyncJsonItemReaderBuilder::get(&output)
+            .compression(Compression::Zstd)
+            .format(JsonFormat::Array)
+            .build();
+        let read: Vec<CarSale> = read_all(&mut reader).await;
+        assert_eq!(read, vec![
+            CarSale { make: "Kia".to_string(), model: "Rio".to_string(), sellingprice: 15000.0 },
+            CarSale { make: "Ford".to_string(), model: "Focus".to_string(), sellingprice: 21000.5 },
+        ]);
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(all(feature = "async", feature = "csv", test))]
+mod async_csv_test {
+    use std::fs;
+
+    use serde::{Deserialize, Serialize};
+
+    use batch_processing::core::item::Positioned;
+    use batch_processing::tokio::item::csv::{AsyncCsvItemReaderBuilder, AsyncCsvItemWriterBuilder};
+    use batch_processing::tokio::step::AsyncStepRunner;
+    use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+    use tempfile::TempDir;
+
+    #[derive(Debug, Deserialize)]
+    struct CarPrice {
+        make: String,
+        model: String,
+        price: f64,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct Listing {
+        line: u64,
+        name: String,
+        price: f64,
+    }
+
+    #[tokio::test]
+    async fn test_csv_reader_and_writer() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("car_prices.csv");
+        let output = directory.path().join("listings.csv");
+        fs::write(&input, "# exported car prices\nmake|model|price\nFord|'Focus | ST'|21000.5\nKia|Rio|15000\n").unwrap();
+
+        let step: AsyncComplexStepBuilder<Positioned<CarPrice>, Listing> = AsyncComplexStepBuilder::get("async_csv_step".to_string())
+            .chunk_size(10)
+            .item_reader(Box::new(AsyncCsvItemReaderBuilder::get(&input).delimiter(b'|').quote(b'\'').skip_lines(1).build()))
+            .item_processor(Box::new(|car: Positioned<CarPrice>| -> futures::future::BoxFuture<'static, Listing> {
+                Box::pin(async move {
+                    Listing {
+                        line: car.line,
+                        name: format!("{} {}", car.item.make, car.item.model),
+                        price: car.item.price,
+                    }
+                })
+            }))
+            .item_writer(Box::new(AsyncCsvItemWriterBuilder::get(&output).delimiter(b';').build()));
+
+        let step_status = step.build().run().await;
+
+        assert!(step_status.status.is_ok());
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "line;name;price\n3;Ford Focus | ST;21000.5\n4;Kia Rio;15000.0\n"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(all(feature = "async", test))]
+mod async_flat_file_test {
+    use std::fs;
+
+    use futures::future::BoxFuture;
+
+    use batch_processing::core::flat_file::{Alignment, FieldSpec, FieldType, FieldValue, FlatRecord, RecordLayout};
+    use batch_processing::tokio::item::flat_file::{AsyncFlatFileItemReaderBuilder, AsyncFlatFileItemWriterBuilder};
+    use batch_processing::tokio::step::AsyncStepRunner;
+    use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_fixed_width_to_delimited() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("stock.dat");
+        let output = directory.path().join("stock.csv");
+        fs::write(&input, "BOLT      000120\r\nNUT       000007\r\n").unwrap();
+
+        let step: AsyncComplexStepBuilder<FlatRecord, (String, i64)> = AsyncComplexStepBuilder::get("async_flat_file_step".to_string())
+            .item_reader(Box::new(AsyncFlatFileItemReaderBuilder::get(&input)
+                .layout(RecordLayout::fixed_width("stock", vec![
+                    FieldSpec::new("part", 0, 10),
+                    FieldSpec::new("quantity", 10, 6).field_type(FieldType::Integer).alignment(Alignment::Right).pad('0'),
+                ]))
+                .build()))
+            .item_processor(Box::new(|record: FlatRecord| -> BoxFuture<'static, (String, i64)> {
+                Box::pin(async move {
+                    (record.text("part").unwrap().to_string(), record.integer("quantity").unwrap())
+                })
+            }))
+            .item_writer(Box::new(AsyncFlatFileItemWriterBuilder::get(&output)
+                .layout(RecordLayout::delimited("stock", ',', vec![FieldSpec::column("part"), FieldSpec::column("quantity")]))
+                .formatter(Box::new(|(part, quantity): &(String, i64)| vec![FieldValue::from(part.as_str()), FieldValue::from(*quantity)]))
+                .build()));
+
+        let step_status = step.build().run().await;
+
+        assert!(step_status.status.is_ok());
+        assert_eq!(fs::read_to_string(&output).unwrap(), "BOLT,120\nNUT,7\n");
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(all(feature = "async", test))]
+mod launcher_test {
+    use std::sync::{Arc, Mutex};
+
+    use batch_processing::tokio::job::AsyncJob;
+    use batch_processing::tokio::job::job_builder::{AsyncJobBuilder, AsyncJobBuilderTrait};
+    use batch_processing::tokio::job::launcher::JobLauncher;
+    use batch_processing::tokio::step::simple_step::{AsyncSimpleStepBuilder, AsyncSimpleStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+
+    /// The name of the thread the step ran on and the number of workers of its runtime.
+    type RuntimeSeen = Arc<Mutex<Option<(String, usize)>>>;
+
+    fn generate_job(seen: RuntimeSeen) -> AsyncJob {
+        let step = AsyncSimpleStepBuilder::get(String::from("step"))
+            .tasklet(Box::new(move || {
+                let seen = Arc::clone(&seen);
+                return Box::pin(async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    let thread_name = std::thread::current().name().unwrap_or_default().to_string();
+                    let workers = tokio::runtime::Handle::current().metrics().num_workers();
+                    *seen.lock().unwrap() = Some((thread_name, workers));
+                });
+            }))
+            .build();
+
+        return AsyncJobBuilder::get(String::from("launched-job"))
+            .step(step)
+            .build();
+    }
+
+    #[test]
+    fn launch_from_sync_code() {
+        let launcher = JobLauncher::multi_thread()
+            .worker_threads(2)
+            .thread_name(String::from("batch-worker"));
+
+        let seen = RuntimeSeen::default();
+        let job_status = launcher.run(generate_job(Arc::clone(&seen)));
+
+        assert!(job_status.status.is_ok());
+        assert_eq!(job_status.steps_status.len(), 1);
+        assert_eq!(*seen.lock().unwrap(), Some((String::from("batch-worker"), 2)));
+    }
+
+    #[test]
+    fn launch_on_current_thread() {
+        let job_status = JobLauncher::current_thread().run(generate_job(RuntimeSeen::default()));
+
+        assert!(job_status.status.is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn launch_from_multi_thread_runtime() {
+        let launcher = JobLauncher::multi_thread()
+            .worker_threads(3)
+            .thread_name(String::from("nested-worker"));
+
+        let seen = RuntimeSeen::default();
+        let job_status = launcher.run(generate_job(Arc::clone(&seen)));
+
+        assert!(job_status.status.is_ok());
+        assert_eq!(*seen.lock().unwrap(), Some((String::from("nested-worker"), 3)));
+    }
+
+    #[tokio::test]
+    async fn launch_from_current_thread_runtime() {
+        let job_status = JobLauncher::default().run(generate_job(RuntimeSeen::default()));
+
+        assert!(job_status.status.is_ok());
+    }
+
+    #[test]
+    fn launch_on_runtime_handle() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("handle-worker")
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let seen = RuntimeSeen::default();
+        let job_status = JobLauncher::from_handle(runtime.handle().clone()).run(generate_job(Arc::clone(&seen)));
+
+        assert!(job_status.status.is_ok());
+        assert_eq!(*seen.lock().unwrap(), Some((String::from("handle-worker"), 2)));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "multi-threaded runtime")]
+    async fn launch_on_current_thread_handle() {
+        JobLauncher::from_handle(tokio::runtime::Handle::current());
+    }
+}
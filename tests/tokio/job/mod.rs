@@ -1,7 +1,17 @@
+pub mod launcher;
+
 #[cfg(all(feature = "async", test))]
 mod job_test {
     use std::pin::Pin;
-    use futures::{Stream, stream};
+    use futures::{Stream, StreamExt, stream};
+    use std::sync::{Arc, Mutex};
+    use batch_processing::sync::step::simple_step::{SimpleStepBuilder, SimpleStepBuilderTrait};
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+    use batch_processing::core::flow::FlowBuilder;
+    use batch_processing::core::job::{JobContext, JobStatus};
+    use batch_processing::core::progress::{ProgressEvent, ProgressEvents};
+    use batch_processing::core::step::{StepExecution, StepStatus};
+    use batch_processing::tokio::listener::{AsyncJobExecutionListener, AsyncStepExecutionListener};
     use batch_processing::tokio::job::job_builder::{AsyncJobBuilder, AsyncJobBuilderTrait};
     use batch_processing::tokio::step::{AsyncStepRunner, AsyncStep};
     use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
@@ -88,4 +98,250 @@ mod job_test {
 
         job.run().await;
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn job_with_sync_step() {
+        let executed = Arc::new(Mutex::new(Vec::new()));
+        let executed_sync = executed.clone();
+        let sync_step = SimpleStepBuilder::get(String::from("sync-step"))
+            .tasklet(Box::new(move || {
+                executed_sync.lock().unwrap().push("sync-step");
+            }))
+            .build();
+        let skipped_step = SimpleStepBuilder::get(String::from("skipped-sync-step"))
+            .tasklet(Box::new(|| {
+                panic!("The skipped step should not run");
+            }))
+            .decider(Box::new(|| false))
+            .build();
+        let executed_async = executed.clone();
+        let async_step = AsyncSimpleStepBuilder::get(String::from("async-step"))
+            .tasklet(Box::new(move || {
+                let executed_async = executed_async.clone();
+                return Box::pin(async move {
+                    executed_async.lock().unwrap().push("async-step");
+                });
+            }))
+            .build();
+
+        let job = AsyncJobBuilder::get(String::from("mixed-job"))
+            .sync_step(sync_step)
+            .sync_step(skipped_step)
+            .step(async_step)
+            .build();
+
+        let job_status = job.run().await;
+
+        assert!(job_status.status.is_ok());
+        assert_eq!(*executed.lock().unwrap(), vec!["sync-step", "async-step"]);
+        let steps: Vec<&str> = job_status.steps_status.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(steps, vec!["sync-step", "async-step"]);
+    }
+
+    #[tokio::test]
+    async fn job_flow_on_failed_step() {
+        let executed = Arc::new(Mutex::new(Vec::new()));
+
+        fn generate_step(name: &'static str, executed: Arc<Mutex<Vec<&'static str>>>) -> AsyncStep {
+            return AsyncSimpleStepBuilder::get(String::from(name))
+                .tasklet(Box::new(move || {
+                    let executed = executed.clone();
+                    return Box::pin(async move {
+                        executed.lock().unwrap().push(name);
+                        if name == "load" {
+                            panic!("Error loading data");
+                        }
+                    });
+                }))
+                .build();
+        }
+
+        let flow = FlowBuilder::start("load")
+            .on("FAILED").to("cleanup")
+            .on("COMPLETED").to("report")
+            .from("cleanup")
+            .on("*").fail()
+            .build();
+
+        let job = AsyncJobBuilder::get(String::from("flow-job"))
+            .step(generate_step("load", executed.clone()))
+            .step(generate_step("report", executed.clone()))
+            .step(generate_step("cleanup", executed.clone()))
+            .flow(flow)
+            .build();
+
+        let job_status = job.run().await;
+
+        assert!(job_status.status.is_err());
+        assert_eq!(*executed.lock().unwrap(), vec!["load", "cleanup"]);
+        assert_eq!(job_status.steps_status[0].exit_status, "FAILED");
+    }
+
+    #[tokio::test]
+    async fn job_with_step_dependencies() {
+        let executed = Arc::new(Mutex::new(Vec::new()));
+
+        fn generate_step(name: &'static str, depends_on: Vec<&str>, executed: Arc<Mutex<Vec<&'static str>>>) -> AsyncStep {
+            let mut step_builder = AsyncSimpleStepBuilder::get(String::from(name))
+                .tasklet(Box::new(move || {
+                    let executed = executed.clone();
+                    return Box::pin(async move {
+                        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                        executed.lock().unwrap().push(name);
+                        if name == "fail" {
+                            panic!("Step failed");
+                        }
+                    });
+                }));
+            for dependency in depends_on {
+                step_builder = step_builder.depends_on(String::from(dependency));
+            }
+            return step_builder.build();
+        }
+
+        let job = AsyncJobBuilder::get(String::from("dag-job"))
+            .step(generate_step("merge", vec!["left", "right"], executed.clone()))
+            .step(generate_step("left", vec!["extract"], executed.clone()))
+            .step(generate_step("right", vec!["extract"], executed.clone()))
+            .step(generate_step("extract", vec![], executed.clone()))
+            .step(generate_step("fail", vec![], executed.clone()))
+            .step(generate_step("after-fail", vec!["fail"], executed.clone()))
+            .multi_tasks(2)
+            .build();
+
+        let job_status = job.run().await;
+        let executed = executed.lock().unwrap();
+        let position = |name: &str| executed.iter().position(|step| *step == name).unwrap();
+
+        assert!(job_status.status.is_err(), "The failed step should fail the job");
+        assert_eq!(executed.len(), 5);
+        assert!(!executed.contains(&"after-fail"), "The steps depending on a failed step should be pruned");
+        assert!(position("extract") < position("left"));
+        assert!(position("extract") < position("right"));
+        assert!(position("left") < position("merge"));
+        assert!(position("right") < position("merge"));
+    }
+
+    #[test]
+    #[should_panic(expected = "greater than zero")]
+    fn job_with_zero_tasks() {
+        let step = AsyncSimpleStepBuilder::get(String::from("step"))
+            .tasklet(Box::new(move || Box::pin(async move {})))
+            .build();
+
+        AsyncJobBuilder::get(String::from("zero-tasks-job"))
+            .step(step)
+            .multi_tasks(0)
+            .build();
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle")]
+    fn job_with_cyclic_step_dependencies() {
+        fn generate_step(name: &str, depends_on: &str) -> AsyncStep {
+            return AsyncSimpleStepBuilder::get(String::from(name))
+                .tasklet(Box::new(move || Box::pin(async move {})))
+                .depends_on(String::from(depends_on))
+                .build();
+        }
+
+        AsyncJobBuilder::get(String::from("cyclic-job"))
+            .step(generate_step("a", "b"))
+            .step(generate_step("b", "a"))
+            .build();
+    }
+
+    struct CountingListener {
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncJobExecutionListener for CountingListener {
+        async fn before_job(&self, context: &JobContext) {
+            self.events.lock().unwrap().push(format!("before {} with {}", context.name, context.step_names.join(", ")));
+        }
+
+        async fn after_job(&self, job_status: &mut JobStatus) {
+            self.events.lock().unwrap().push(format!("after {}", job_status.name));
+            job_status.status = Err(String::from("Rejected by listener"));
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncStepExecutionListener for CountingListener {
+        async fn before_step(&self, step_name: &str, _execution: &StepExecution) {
+            self.events.lock().unwrap().push(format!("before {}", step_name));
+        }
+
+        async fn after_step(&self, _execution: &StepExecution, step_status: &mut StepStatus) {
+            self.events.lock().unwrap().push(format!("after {}", step_status.name));
+        }
+    }
+
+    #[tokio::test]
+    async fn job_with_listeners() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let listener = Arc::new(CountingListener { events: Arc::clone(&events) });
+
+        let step = AsyncSimpleStepBuilder::get(String::from("step"))
+            .tasklet(Box::new(|| Box::pin(async {})))
+            .listener(listener.clone())
+            .build();
+
+        let job = AsyncJobBuilder::get(String::from("listened-job"))
+            .step(step)
+            .listener(listener)
+            .build();
+
+        let job_status = job.run().await;
+
+        assert!(job_status.status.is_err(), "The listener should be able to change the job status");
+        assert_eq!(*events.lock().unwrap(), vec![
+            "before listened-job with step",
+            "before step",
+            "after step",
+            "after listened-job",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn job_with_progress_stream() {
+        let step: AsyncComplexStepBuilder<i32, i32> = AsyncComplexStepBuilder::get(String::from("tolerant-copy"))
+            .chunk_size(4)
+            .expected_total(8)
+            .throw_tolerant()
+            .reader(Box::new(|| Box::pin(async {
+                let stream: Pin<Box<dyn Stream<Item=i32> + Send>> = Box::pin(stream::iter(0..8));
+                stream
+            })))
+            .processor(Box::new(|item: i32| Box::pin(async move {
+                if item == 5 {
+                    panic!("Invalid item");
+                }
+                item
+            })))
+            .writer(Box::new(|_items: Vec<i32>| Box::pin(async {})));
+
+        let progress = ProgressEvents::new();
+        let events = progress.stream();
+        let job = AsyncJobBuilder::get(String::from("progress-stream-job"))
+            .progress(progress)
+            .step(step.build())
+            .build();
+
+        job.run().await;
+
+        let events: Vec<ProgressEvent> = events.collect().await;
+        assert_eq!(events[0], ProgressEvent::JobStarted { job_name: String::from("progress-stream-job"), step_count: 1 });
+        assert_eq!(events[1], ProgressEvent::StepStarted { step_name: String::from("tolerant-copy"), expected_total: Some(8) });
+        assert!(events.iter().any(|event| matches!(event, ProgressEvent::ItemsSkipped { item_count: 1, .. })));
+        let items_written: Vec<usize> = events.iter()
+            .filter_map(|event| match event {
+                ProgressEvent::ChunkWritten { items_written, .. } => Some(*items_written),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(items_written, vec![4, 7]);
+        assert!(matches!(events.last(), Some(ProgressEvent::JobFinished { .. })));
+    }
+}
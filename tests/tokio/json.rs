@@ -0,0 +1,45 @@
+#[cfg(all(feature = "async", feature = "json", test))]
+mod async_json_test {
+    use std::fs;
+
+    use futures::future::BoxFuture;
+    use serde::{Deserialize, Serialize};
+
+    use batch_processing::core::json::JsonFormat;
+    use batch_processing::tokio::item::json::{AsyncJsonItemReaderBuilder, AsyncJsonItemWriterBuilder};
+    use batch_processing::tokio::step::AsyncStepRunner;
+    use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+    use tempfile::TempDir;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Reading {
+        sensor: u32,
+        value: f64,
+    }
+
+    #[tokio::test]
+    async fn test_json_array_round_trip() {
+        let directory = TempDir::new().unwrap();
+        let input = directory.path().join("readings.json");
+        let output = directory.path().join("doubled.json");
+        let readings: Vec<Reading> = (0..100).map(|sensor| Reading { sensor, value: sensor as f64 / 2.0 }).collect();
+        fs::write(&input, serde_json::to_string_pretty(&readings).unwrap()).unwrap();
+
+        let step: AsyncComplexStepBuilder<Reading, Reading> = AsyncComplexStepBuilder::get("async_json_step".to_string())
+            .chunk_size(7)
+            .item_reader(Box::new(AsyncJsonItemReaderBuilder::get(&input).format(JsonFormat::Array).build()))
+            .item_processor(Box::new(|reading: Reading| -> BoxFuture<'static, Reading> {
+                Box::pin(async move { Reading { sensor: reading.sensor, value: reading.value * 2.0 } })
+            }))
+            .item_writer(Box::new(AsyncJsonItemWriterBuilder::get(&output).format(JsonFormat::Array).build()));
+
+        let step_status = step.build().run().await;
+
+        assert!(step_status.status.is_ok());
+        let mut written: Vec<Reading> = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        written.sort_by_key(|reading| reading.sensor);
+        let expected: Vec<Reading> = (0..100).map(|sensor| Reading { sensor, value: sensor as f64 }).collect();
+        assert_eq!(written, expected);
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(all(feature = "async", feature = "metrics", test))]
+mod async_metrics_test {
+    use std::pin::Pin;
+    use futures::{stream, Stream};
+    use batch_processing::core::metrics::registry;
+    use batch_processing::tokio::job::job_builder::{AsyncJobBuilder, AsyncJobBuilderTrait};
+    use batch_processing::tokio::step::AsyncStepRunner;
+    use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
+    use batch_processing::tokio::step::parallel_step_builder::AsyncParallelStepBuilderTrait;
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+
+    #[tokio::test]
+    async fn test_job_metrics() {
+        let step: AsyncComplexStepBuilder<i32, i32> = AsyncComplexStepBuilder::get("async-measured-step".to_string())
+            .chunk_size(5)
+            .workers(2)
+            .throw_tolerant()
+            .reader(Box::new(|| Box::pin(async {
+                let stream: Pin<Box<dyn Stream<Item=i32> + Send>> = Box::pin(stream::iter(0..10));
+                stream
+            })))
+            .processor(Box::new(|item: i32| Box::pin(async move {
+                if item == 3 {
+                    panic!("Invalid item");
+                }
+                item
+            })))
+            .writer(Box::new(|_items: Vec<i32>| Box::pin(async {})));
+
+        let job = AsyncJobBuilder::get(String::from("async-measured-job"))
+            .step(step.build())
+            .build();
+
+        job.run().await;
+
+        let output = registry().render();
+        let labels = "{job=\"async-measured-job\",step=\"async-measured-step\"}";
+        assert!(output.contains(&format!("batch_items_read_total{} 10", labels)));
+        assert!(output.contains(&format!("batch_items_skipped_total{} 1", labels)));
+        assert!(output.contains(&format!("batch_items_written_total{} 9", labels)));
+        assert!(output.contains(&format!("batch_worker_queue_depth{} 0", labels)));
+        assert!(output.contains(&format!("batch_step_duration_seconds_count{} 1", labels)));
+        assert!(output.contains("batch_job_runs_total{job=\"async-measured-job\",status=\"completed\"} 1"));
+    }
+}
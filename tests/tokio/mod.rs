@@ -1,2 +1,13 @@
 pub mod step;
-pub mod job;
\ No newline at end of file
+pub mod job;
+pub mod trace;
+pub mod metrics;
+pub mod csv;
+pub mod json;
+pub mod flat_file;
+pub mod multi_resource;
+pub mod compression;
+pub mod sqlite;
+pub mod paging;
+pub mod composite;
+pub mod processor;
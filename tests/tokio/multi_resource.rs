@@ -0,0 +1,111 @@
+#[cfg(all(feature = "async", feature = "glob", test))]
+mod async_multi_resource_test {
+    use std::fs;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    use futures::future::BoxFuture;
+
+    use batch_processing::core::flat_file::{FieldSpec, FieldType, FlatRecord, RecordLayout};
+    use batch_processing::core::item::ResourceItem;
+    use batch_processing::tokio::item::flat_file::AsyncFlatFileItemReaderBuilder;
+    use batch_processing::tokio::item::multi_resource::AsyncMultiResourceItemReaderBuilder;
+    use batch_processing::tokio::step::AsyncStepRunner;
+    use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_multi_resource_reader() {
+        let directory = TempDir::new().unwrap();
+        for day in 1..=3 {
+            let lines: String = (1..=day).map(|amount| format!("{}\n", amount * 10)).collect();
+            fs::write(directory.path().join(format!("2024-01-0{}.txt", day)), lines).unwrap();
+        }
+        let pattern = directory.path().join("2024-01-*.txt").to_string_lossy().to_string();
+
+        let totals = Arc::new(Mutex::new(Vec::new()));
+        let totals_clone = totals.clone();
+
+        let step: AsyncComplexStepBuilder<ResourceItem<FlatRecord>, (String, i64)> = AsyncComplexStepBuilder::get("async_multi_resource_step".to_string())
+            .item_reader(Box::new(AsyncMultiResourceItemReaderBuilder::get(pattern)
+                .reader(Box::new(|path| Box::new(AsyncFlatFileItemReaderBuilder::get(path)
+                    .layout(RecordLayout::delimited("amount", ',', vec![FieldSpec::column("amount").field_type(FieldType::Integer)]))
+                    .build())))
+                .build()))
+            .item_processor(Box::new(|record: ResourceItem<FlatRecord>| -> BoxFuture<'static, (String, i64)> {
+                Box::pin(async move {
+                    let file = record.resource.file_stem().unwrap().to_string_lossy().to_string();
+                    (file, record.item.integer("amount").unwrap())
+                })
+            }))
+            .item_writer(Box::new(move |amounts: Vec<(String, i64)>| -> BoxFuture<'static, ()> {
+                let totals = totals_clone.clone();
+                Box::pin(async move { totals.lock().unwrap().extend(amounts) })
+            }));
+
+        let step_status = step.build().run().await;
+
+        assert!(step_status.status.is_ok());
+        let mut totals = totals.lock().unwrap().clone();
+        totals.sort();
+        assert_eq!(totals, vec![
+            ("2024-01-01".to_string(), 10),
+            ("2024-01-02".to_string(), 10),
+            ("2024-01-02".to_string(), 20),
+            ("2024-01-03".to_string(), 10),
+            ("2024-01-03".to_string(), 20),
+            ("2024-01-03".to_string(), 30),
+        ]);
+    }
+
+    fn build_step(pattern: &str, restart_file: &Path, failing_name: &'static str, written: Arc<Mutex<Vec<String>>>) -> AsyncComplexStepBuilder<ResourceItem<FlatRecord>, String> {
+        AsyncComplexStepBuilder::get("async_multi_resource_restart_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(AsyncMultiResourceItemReaderBuilder::get(pattern)
+                .reader(Box::new(|path| Box::new(AsyncFlatFileItemReaderBuilder::get(path)
+                    .layout(RecordLayout::delimited("name", ',', vec![FieldSpec::column("name")]))
+                    .build())))
+                .restart_file(restart_file)
+                .build()))
+            .item_processor(Box::new(|record: ResourceItem<FlatRecord>| -> BoxFuture<'static, String> {
+                Box::pin(async move {
+                    let file = record.resource.file_name().unwrap().to_string_lossy().to_string();
+                    format!("{}:{}", file, record.item.text("name").unwrap())
+                })
+            }))
+            .item_writer(Box::new(move |names: Vec<String>| -> BoxFuture<'static, ()> {
+                let written = written.clone();
+                Box::pin(async move {
+                    if names.iter().any(|name| name.ends_with(&format!(":{}", failing_name))) {
+                        panic!("Invalid name");
+                    }
+                    written.lock().unwrap().extend(names)
+                })
+            }))
+    }
+
+    #[tokio::test]
+    async fn test_multi_resource_reader_resumes_unwritten_chunks() {
+        let directory = TempDir::new().unwrap();
+        fs::write(directory.path().join("a.txt"), "alice\nbob\ncarol\n").unwrap();
+        fs::write(directory.path().join("b.txt"), "dave\nerin\n").unwrap();
+        let pattern = directory.path().join("*.txt").to_string_lossy().to_string();
+        let restart_file = directory.path().join("restart");
+
+        // The reader runs ahead of the writes, and the second chunk spans both files.
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step_status = build_step(&pattern, &restart_file, "dave", written.clone()).build().run().await;
+
+        assert!(step_status.status.is_err());
+        assert_eq!(*written.lock().unwrap(), vec!["a.txt:alice", "a.txt:bob"]);
+        assert_eq!(fs::read_to_string(&restart_file).unwrap(), directory.path().join("a.txt").to_string_lossy());
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step_status = build_step(&pattern, &restart_file, "", written.clone()).build().run().await;
+
+        assert!(step_status.status.is_ok());
+        assert_eq!(*written.lock().unwrap(), vec!["a.txt:alice", "a.txt:bob", "a.txt:carol", "b.txt:dave", "b.txt:erin"]);
+        assert!(!restart_file.exists());
+    }
+}
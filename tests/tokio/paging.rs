@@ -0,0 +1,123 @@
+#[cfg(all(feature = "async", test))]
+mod async_paging_test {
+    use std::fs;
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    use futures::future::BoxFuture;
+
+    use batch_processing::tokio::item::paging::{AsyncKeysetItemReaderBuilder, AsyncPagingItemReaderBuilder};
+    use batch_processing::tokio::step::AsyncStepRunner;
+    use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_paging_reader() {
+        let items: Arc<Vec<u64>> = Arc::new((0..25).collect());
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let written_clone = written.clone();
+
+        let step: AsyncComplexStepBuilder<u64, u64> = AsyncComplexStepBuilder::get("async_paging_step".to_string())
+            .item_reader(Box::new(AsyncPagingItemReaderBuilder::get(Box::new(move |offset, limit| -> BoxFuture<'static, Vec<u64>> {
+                let items = items.clone();
+                Box::pin(async move { items.iter().skip(offset as usize).take(limit).copied().collect() })
+            }))
+                .page_size(10)
+                .build()))
+            .item_processor(Box::new(|item: u64| -> BoxFuture<'static, u64> { Box::pin(async move { item * 2 }) }))
+            .item_writer(Box::new(move |items: Vec<u64>| -> BoxFuture<'static, ()> {
+                let written = written_clone.clone();
+                Box::pin(async move { written.lock().unwrap().extend(items) })
+            }));
+
+        let step_status = step.build().run().await;
+
+        assert!(step_status.status.is_ok());
+        let mut written = written.lock().unwrap().clone();
+        written.sort();
+        assert_eq!(written, (0..25).map(|item| item * 2).collect::<Vec<u64>>());
+    }
+
+    fn build_step(restart_file: &Path, failing: u64, written: Arc<Mutex<Vec<u64>>>) -> AsyncComplexStepBuilder<u64, u64> {
+        let items: Arc<Vec<u64>> = Arc::new((0..10).collect());
+        AsyncComplexStepBuilder::get("async_paging_restart_step".to_string())
+            .chunk_size(4)
+            .item_reader(Box::new(AsyncPagingItemReaderBuilder::get(Box::new(move |offset, limit| -> BoxFuture<'static, Vec<u64>> {
+                let items = items.clone();
+                Box::pin(async move { items.iter().skip(offset as usize).take(limit).copied().collect() })
+            }))
+                .page_size(3)
+                .restart_file(restart_file)
+                .build()))
+            .item_processor(Box::new(|item: u64| -> BoxFuture<'static, u64> { Box::pin(async move { item }) }))
+            .item_writer(Box::new(move |items: Vec<u64>| -> BoxFuture<'static, ()> {
+                let written = written.clone();
+                Box::pin(async move {
+                    if items.contains(&failing) {
+                        panic!("Invalid item");
+                    }
+                    written.lock().unwrap().extend(items)
+                })
+            }))
+    }
+
+    #[tokio::test]
+    async fn test_paging_reader_resumes_unwritten_chunks() {
+        let directory = TempDir::new().unwrap();
+        let restart_file = directory.path().join("restart");
+
+        // The reader runs ahead of the writes, and the second chunk spans the second and third pages.
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step_status = build_step(&restart_file, 6, written.clone()).build().run().await;
+
+        assert!(step_status.status.is_err());
+        assert_eq!(*written.lock().unwrap(), vec![0, 1, 2, 3]);
+        assert_eq!(fs::read_to_string(&restart_file).unwrap().trim(), "4");
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let step_status = build_step(&restart_file, u64::MAX, written.clone()).build().run().await;
+
+        assert!(step_status.status.is_ok());
+        let mut written = written.lock().unwrap().clone();
+        written.sort();
+        assert_eq!(written, vec![4, 5, 6, 7, 8, 9]);
+        assert!(!restart_file.exists());
+    }
+
+    #[tokio::test]
+    async fn test_keyset_reader_resumes() {
+        let directory = TempDir::new().unwrap();
+        let restart_file = directory.path().join("restart");
+        fs::write(&restart_file, "12\n").unwrap();
+
+        let ids: Arc<Vec<u32>> = Arc::new((1..=10).map(|id| id * 3).collect());
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let written_clone = written.clone();
+
+        let step: AsyncComplexStepBuilder<u32, u32> = AsyncComplexStepBuilder::get("async_keyset_step".to_string())
+            .item_reader(Box::new(AsyncKeysetItemReaderBuilder::get(Box::new(move |after: Option<u32>, limit| -> BoxFuture<'static, Vec<u32>> {
+                let ids = ids.clone();
+                Box::pin(async move {
+                    ids.iter().filter(|id| after.is_none_or(|after| **id > after)).take(limit).copied().collect()
+                })
+            }))
+                .key(Box::new(|id: &u32| *id))
+                .page_size(4)
+                .restart_file(restart_file.clone())
+                .build()))
+            .item_processor(Box::new(|id: u32| -> BoxFuture<'static, u32> { Box::pin(async move { id }) }))
+            .item_writer(Box::new(move |ids: Vec<u32>| -> BoxFuture<'static, ()> {
+                let written = written_clone.clone();
+                Box::pin(async move { written.lock().unwrap().extend(ids) })
+            }));
+
+        let step_status = step.build().run().await;
+
+        assert!(step_status.status.is_ok());
+        let mut written = written.lock().unwrap().clone();
+        written.sort();
+        assert_eq!(written, vec![15, 18, 21, 24, 27, 30]);
+        assert!(!restart_file.exists());
+    }
+}
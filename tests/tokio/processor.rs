@@ -0,0 +1,119 @@
+#[cfg(all(feature = "async", test))]
+mod async_processor_test {
+    use std::sync::{Arc, Mutex};
+
+    use futures::StreamExt;
+    use futures::future::BoxFuture;
+
+    use batch_processing::core::progress::{ProgressEvent, ProgressEvents};
+    use batch_processing::tokio::item::processor::{AsyncChainedItemProcessorBuilder, AsyncFlatMapItemProcessor, AsyncFlattenItemWriter, AsyncValidatingItemProcessor};
+    use batch_processing::tokio::job::job_builder::{AsyncJobBuilder, AsyncJobBuilderTrait};
+    use batch_processing::tokio::step::{AsyncStep, AsyncStepRunner};
+    use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+
+    #[tokio::test]
+    async fn test_chained_processor_skips_invalid_items() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let written_clone = written.clone();
+
+        let step: AsyncComplexStepBuilder<String, u32> = AsyncComplexStepBuilder::get("async_chained_processor_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(futures::stream::iter(vec!["16000", "-1", "31000", "abc", "12500"].into_iter().map(String::from))))
+            .item_processor(Box::new(AsyncChainedItemProcessorBuilder::get(Box::new(|price: String| -> BoxFuture<'static, i64> {
+                Box::pin(async move { price.parse().unwrap() })
+            }))
+                .then(Box::new(AsyncValidatingItemProcessor::new(Box::new(|price: &i64| {
+                    if *price < 0 { Err(format!("negative price {}", price)) } else { Ok(()) }
+                }))))
+                .then(Box::new(|price: i64| -> BoxFuture<'static, u32> { Box::pin(async move { price as u32 / 100 }) }))
+                .build()))
+            .item_writer(Box::new(move |prices: Vec<u32>| -> BoxFuture<'static, ()> {
+                let written = written_clone.clone();
+                Box::pin(async move { written.lock().unwrap().extend(prices) })
+            }))
+            .throw_tolerant();
+
+        let step_status = step.build().run().await;
+
+        assert!(step_status.status.is_err());
+        let mut written = written.lock().unwrap().clone();
+        written.sort();
+        assert_eq!(written, vec![125, 160, 310]);
+    }
+
+    async fn run_with_progress(step: AsyncStep) -> Vec<ProgressEvent> {
+        let progress = ProgressEvents::new();
+        let events = progress.stream();
+        let job = AsyncJobBuilder::get("async_processor_job".to_string())
+            .progress(progress)
+            .step(step)
+            .build();
+
+        job.run().await;
+        events.collect().await
+    }
+
+    #[tokio::test]
+    async fn test_validating_processor_skips_invalid_items() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let written_clone = written.clone();
+
+        let step: AsyncComplexStepBuilder<i64, i64> = AsyncComplexStepBuilder::get("async_skipping_processor_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(futures::stream::iter(vec![16000, -1, 31000, -2, 12500])))
+            .item_processor(Box::new(AsyncValidatingItemProcessor::new(Box::new(|price: &i64| {
+                if *price < 0 { Err(format!("negative price {}", price)) } else { Ok(()) }
+            })).skip_invalid()))
+            .item_writer(Box::new(move |prices: Vec<i64>| -> BoxFuture<'static, ()> {
+                let written = written_clone.clone();
+                Box::pin(async move { written.lock().unwrap().extend(prices) })
+            }));
+
+        let events = run_with_progress(step.build()).await;
+
+        assert!(matches!(events.last(), Some(ProgressEvent::JobFinished { succeeded: true, .. })));
+        let mut written = written.lock().unwrap().clone();
+        written.sort();
+        assert_eq!(written, vec![12500, 16000, 31000]);
+        let mut skipped: Vec<&String> = events.iter()
+            .filter_map(|event| match event {
+                ProgressEvent::ItemsSkipped { item_count: 1, error, .. } => Some(error),
+                _ => None,
+            })
+            .collect();
+        skipped.sort();
+        assert_eq!(skipped, vec!["Invalid item: negative price -1", "Invalid item: negative price -2"]);
+    }
+
+    #[tokio::test]
+    async fn test_flat_map_processor() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let written_clone = written.clone();
+
+        let step: AsyncComplexStepBuilder<u32, Vec<u32>> = AsyncComplexStepBuilder::get("async_flat_map_processor_step".to_string())
+            .chunk_size(2)
+            .item_reader(Box::new(futures::stream::iter(0..5)))
+            .item_processor(Box::new(AsyncFlatMapItemProcessor::new(Box::new(|count: u32| -> BoxFuture<'static, Vec<u32>> {
+                Box::pin(async move { vec![count; count as usize] })
+            }))))
+            .item_writer(Box::new(AsyncFlattenItemWriter::new(Box::new(move |items: Vec<u32>| -> BoxFuture<'static, ()> {
+                let written = written_clone.clone();
+                Box::pin(async move { written.lock().unwrap().extend(items) })
+            }))));
+
+        let events = run_with_progress(step.build()).await;
+
+        assert!(matches!(events.last(), Some(ProgressEvent::JobFinished { succeeded: true, .. })));
+        let mut written = written.lock().unwrap().clone();
+        written.sort();
+        assert_eq!(written, vec![1, 2, 2, 3, 3, 3, 4, 4, 4, 4]);
+        let items_written = events.iter()
+            .filter_map(|event| match event {
+                ProgressEvent::ChunkWritten { items_written, .. } => Some(*items_written),
+                _ => None,
+            })
+            .max();
+        assert_eq!(items_written, Some(10));
+    }
+}
@@ -1,11 +1,17 @@
 #[cfg(all(feature = "async", test))]
 mod async_complex_step_test {
+    use batch_processing::core::chunk::AdaptiveChunkSize;
+    use batch_processing::core::listener::{ChunkListener, ItemListener};
+    use batch_processing::tokio::item::{AsyncItemProcessor, AsyncItemWriter};
+    use batch_processing::tokio::step::parallel_step_builder::AsyncParallelStepBuilderTrait;
     use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
     use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
     use batch_processing::tokio::step::AsyncStepRunner;
-    use futures::{stream, Stream};
+    use futures::{stream, Stream, StreamExt};
     use std::pin::Pin;
     use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
     use tokio::sync::Mutex;
 
     #[tokio::test]
@@ -144,4 +150,218 @@ mod async_complex_step_test {
         assert_eq!(step_result.status.is_err(), true);
         assert_eq!(vec.len(), 1);
     }
-}
\ No newline at end of file
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flush_interval() {
+        let chunks = Arc::new(Mutex::new(Vec::new()));
+        let chunks_write = chunks.clone();
+        let step_builder: AsyncComplexStepBuilder<i32, i32> = AsyncComplexStepBuilder::get("test".to_string())
+            .chunk_size(10)
+            .flush_interval(Duration::from_millis(50))
+            .reader(Box::new(move ||
+                {
+                    return Box::pin(async move {
+                        let stream: Pin<Box<dyn Stream<Item=i32> + Send>> =
+                            Box::pin(stream::iter(vec![(1, 0), (2, 300)]).then(|(item, delay)| async move {
+                                tokio::time::sleep(Duration::from_millis(delay)).await;
+                                item
+                            }));
+                        stream
+                    }
+                    );
+                }))
+            .processor(
+                Box::new(
+                    move |item: i32| Box::pin(
+                        async move {
+                            item
+                        }
+                    )
+                )
+            )
+            .writer(
+                Box::new(
+                    move |items: Vec<i32>| {
+                        let chunks_write = chunks_write.clone();
+                        Box::pin(
+                            async move {
+                                chunks_write.lock().await.push(items);
+                            }
+                        )
+                    }
+                )
+            );
+
+        let step = step_builder.build();
+        let step_result = step.run().await;
+        let chunks = chunks.lock().await;
+        assert!(step_result.status.is_ok());
+        assert_eq!(*chunks, vec![vec![1], vec![2]], "The partial chunk should be flushed before the next item arrives");
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_chunk_size() {
+        let step_builder: AsyncComplexStepBuilder<i32, i32> = AsyncComplexStepBuilder::get("test".to_string())
+            .chunk_size(2)
+            .adaptive_chunk_size(AdaptiveChunkSize {
+                min: 1,
+                max: 8,
+                target_latency: Duration::from_secs(1),
+            })
+            .reader(Box::new(move ||
+                {
+                    return Box::pin(async move {
+                        let stream: Pin<Box<dyn Stream<Item=i32> + Send>> = Box::pin(stream::iter(0..50));
+                        stream
+                    }
+                    );
+                }))
+            .processor(
+                Box::new(
+                    move |item: i32| Box::pin(
+                        async move {
+                            item
+                        }
+                    )
+                )
+            )
+            .writer(
+                Box::new(
+                    move |_items: Vec<i32>| Box::pin(async move {})
+                )
+            );
+
+        let step = step_builder.build();
+        let step_result = step.run().await;
+        assert!(step_result.status.is_ok());
+        assert_eq!(step_result.chunk_sizes.first(), Some(&2), "The chunk size should start from the configured one");
+        assert_eq!(step_result.chunk_sizes.last(), Some(&8), "Fast writes should grow the chunk size up to the maximum");
+    }
+
+    #[derive(Default)]
+    struct CountingListener {
+        chunks_started: AtomicUsize,
+        chunks_written: AtomicUsize,
+        items_read: AtomicUsize,
+        items_processed: AtomicUsize,
+        items_written: AtomicUsize,
+    }
+
+    impl ChunkListener for CountingListener {
+        fn before_chunk(&self, _chunk_index: usize) {
+            self.chunks_started.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn after_chunk(&self, _chunk_index: usize) {
+            self.chunks_written.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    impl ItemListener<i32, i32> for CountingListener {
+        fn after_read(&self, _item: &i32) {
+            self.items_read.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn after_process(&self, _output: &i32) {
+            self.items_processed.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn after_write(&self, count: usize) {
+            self.items_written.fetch_add(count, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_listeners_with_workers() {
+        let listener = Arc::new(CountingListener::default());
+        let step_builder: AsyncComplexStepBuilder<i32, i32> = AsyncComplexStepBuilder::get("test".to_string())
+            .chunk_size(10)
+            .workers(4)
+            .reader(Box::new(move ||
+                {
+                    return Box::pin(async move {
+                        let stream: Pin<Box<dyn Stream<Item=i32> + Send>> = Box::pin(stream::iter(0..100));
+                        stream
+                    }
+                    );
+                }))
+            .processor(
+                Box::new(
+                    move |item: i32| Box::pin(
+                        async move {
+                            item
+                        }
+                    )
+                )
+            )
+            .writer(
+                Box::new(
+                    move |_items: Vec<i32>| Box::pin(async move {})
+                )
+            )
+            .chunk_listener(listener.clone())
+            .item_listener(listener.clone());
+
+        let step = step_builder.build();
+        let step_result = step.run().await;
+        assert!(step_result.status.is_ok());
+        assert_eq!(listener.items_read.load(Ordering::SeqCst), 100);
+        assert_eq!(listener.items_processed.load(Ordering::SeqCst), 100);
+        assert_eq!(listener.items_written.load(Ordering::SeqCst), 100);
+        assert_eq!(listener.chunks_started.load(Ordering::SeqCst), 12, "Each worker writes 2 full chunks and a partial one");
+        assert_eq!(listener.chunks_written.load(Ordering::SeqCst), 12);
+    }
+
+    /// A processor scaling the items by a factor loaded when it is opened.
+    struct ScalingProcessor {
+        factor: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncItemProcessor<usize, usize> for ScalingProcessor {
+        async fn open(&self) {
+            self.factor.store(3, Ordering::SeqCst);
+        }
+
+        async fn process(&self, item: usize) -> usize {
+            item * self.factor.load(Ordering::SeqCst)
+        }
+    }
+
+    /// A writer buffering the chunks of all the workers and flushing them when closed.
+    struct BufferedWriter {
+        buffer: Mutex<Vec<usize>>,
+        flushed: Arc<Mutex<Vec<usize>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncItemWriter<usize> for BufferedWriter {
+        async fn write(&self, items: Vec<usize>) {
+            self.buffer.lock().await.extend(items);
+        }
+
+        async fn close(&self) {
+            let mut buffer = self.buffer.lock().await;
+            self.flushed.lock().await.append(&mut buffer);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_item_traits() {
+        let flushed = Arc::new(Mutex::new(Vec::new()));
+
+        let step: AsyncComplexStepBuilder<usize, usize> = AsyncComplexStepBuilder::get("item_traits".to_string())
+            .chunk_size(2)
+            .workers(2)
+            .item_reader(Box::new(stream::iter(1..=5)))
+            .item_processor(Box::new(ScalingProcessor { factor: AtomicUsize::new(0) }))
+            .item_writer(Box::new(BufferedWriter { buffer: Mutex::new(Vec::new()), flushed: Arc::clone(&flushed) }));
+
+        let step_status = step.build().run().await;
+
+        assert!(step_status.status.is_ok());
+        let mut flushed = flushed.lock().await.clone();
+        flushed.sort();
+        assert_eq!(flushed, vec![3, 6, 9, 12, 15]);
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(all(feature = "async", test))]
+mod async_job_step_test {
+    use batch_processing::tokio::job::AsyncJob;
+    use batch_processing::tokio::job::job_builder::{AsyncJobBuilder, AsyncJobBuilderTrait};
+    use batch_processing::tokio::step::{AsyncStepRunner, job_step};
+    use batch_processing::tokio::step::job_step::AsyncJobStepBuilderTrait;
+    use batch_processing::tokio::step::simple_step::{AsyncSimpleStepBuilder, AsyncSimpleStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+
+    fn generate_child_job(fail: bool) -> AsyncJob {
+        let step = AsyncSimpleStepBuilder::get(String::from("child-step"))
+            .tasklet(Box::new(move || {
+                return Box::pin(async move {
+                    if fail {
+                        panic!("Child step failed");
+                    }
+                });
+            }))
+            .build();
+
+        return AsyncJobBuilder::get(String::from("child-job"))
+            .step(step)
+            .build();
+    }
+
+    #[tokio::test]
+    async fn test_job_step() {
+        let step = job_step::get(String::from("job-step"))
+            .job(generate_child_job(false))
+            .build();
+
+        let step_status = step.run().await;
+
+        assert!(step_status.status.is_ok());
+        let child_status = step_status.job_status.expect("The child job status should be nested");
+        assert!(child_status.status.is_ok());
+        assert_eq!(child_status.steps_status.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_job_step() {
+        let failed_step = job_step::get(String::from("failed-job-step"))
+            .job(generate_child_job(true))
+            .build();
+        let tolerant_step = job_step::get(String::from("tolerant-job-step"))
+            .job(generate_child_job(true))
+            .throw_tolerant()
+            .build();
+
+        let parent_job = AsyncJobBuilder::get(String::from("parent-job"))
+            .step(tolerant_step)
+            .step(failed_step)
+            .build();
+
+        let job_status = parent_job.run().await;
+
+        assert!(job_status.status.is_err(), "The failed child job should fail the parent job");
+        assert_eq!(job_status.steps_status.len(), 2, "The tolerant step should not stop the parent job");
+        assert!(job_status.steps_status[0].job_status.as_ref().unwrap().status.is_err());
+    }
+}
@@ -1,2 +1,4 @@
 pub mod simple_step;
-pub mod complex_step;
\ No newline at end of file
+pub mod complex_step;
+pub mod job_step;
+pub mod partition_step;
\ No newline at end of file
@@ -0,0 +1,140 @@
+#[cfg(all(feature = "async", test))]
+mod async_partition_step_test {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+    use futures::StreamExt;
+    use batch_processing::core::partition::{FileListPartitioner, Partition, RangePartitioner, FILE, RANGE_MAX, RANGE_MIN};
+    use batch_processing::core::partition::process::{self as partition_process, ProcessPartitionHandler};
+    use batch_processing::core::progress::{ProgressEvent, ProgressEvents};
+    use batch_processing::core::step::StepStatus;
+    use batch_processing::sync::step::Runner;
+    use batch_processing::sync::step::simple_step::{SimpleStepBuilder, SimpleStepBuilderTrait};
+    use batch_processing::sync::step::step_builder::StepBuilderTrait;
+    use batch_processing::tokio::job::job_builder::{AsyncJobBuilder, AsyncJobBuilderTrait};
+    use batch_processing::tokio::step::{AsyncStepRunner, partition_step};
+    use batch_processing::tokio::step::parallel_step_builder::AsyncParallelStepBuilderTrait;
+    use batch_processing::tokio::step::partition_step::AsyncPartitionStepBuilderTrait;
+    use batch_processing::tokio::step::simple_step::{AsyncSimpleStepBuilder, AsyncSimpleStepBuilderTrait};
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_file_list_partition_step() {
+        let directory = TempDir::new().unwrap();
+        for (index, lines) in ["a\nb\n", "c\n", "d\ne\nf\n"].iter().enumerate() {
+            fs::write(directory.path().join(format!("file{}.txt", index)), lines).unwrap();
+        }
+
+        let lines = Arc::new(AtomicUsize::new(0));
+        let worker_lines = Arc::clone(&lines);
+
+        let step = partition_step::get(String::from("partition-step"))
+            .partitioner(Arc::new(FileListPartitioner::from_directory(directory.path()).unwrap()))
+            .workers(2)
+            .worker(Arc::new(move |partition| {
+                let lines = Arc::clone(&worker_lines);
+                let file = PathBuf::from(partition.get(FILE).unwrap());
+                AsyncSimpleStepBuilder::get(partition.name)
+                    .tasklet(Box::new(move || {
+                        let lines = Arc::clone(&lines);
+                        let file = file.clone();
+                        return Box::pin(async move {
+                            let content = tokio::fs::read_to_string(file).await.unwrap();
+                            lines.fetch_add(content.lines().count(), Ordering::SeqCst);
+                        });
+                    }))
+                    .build()
+            }))
+            .build();
+
+        let step_status = step.run().await;
+
+        assert!(step_status.status.is_ok());
+        assert_eq!(step_status.partitions_status.len(), 3);
+        assert_eq!(lines.load(Ordering::SeqCst), 6);
+    }
+
+    fn run_process_partition(step_name: &str, partition: Partition) -> StepStatus {
+        let min: i64 = partition.parse(RANGE_MIN).unwrap();
+        let max: i64 = partition.parse(RANGE_MAX).unwrap();
+        // The second partition of `hanging-step` never answers.
+        if min == 2 && step_name == "hanging-step" {
+            thread::sleep(Duration::from_secs(600));
+        }
+        SimpleStepBuilder::get(partition.name)
+            .tasklet_with_exit_status(Box::new(move || (min..=max).sum::<i64>().to_string()))
+            .build()
+            .run()
+    }
+
+    /// Serves a partition when the test binary is spawned as a partition worker.
+    #[test]
+    fn partition_worker() {
+        if let Some(step_name) = partition_process::worker_step_name() {
+            partition_process::serve_stdio(|partition| run_process_partition(&step_name, partition)).unwrap();
+        }
+    }
+
+    fn process_handler() -> ProcessPartitionHandler {
+        ProcessPartitionHandler::new()
+            .args(vec![
+                String::from("--exact"),
+                String::from("tokio::step::partition_step::async_partition_step_test::partition_worker"),
+                String::from("--test-threads=1"),
+            ])
+            .max_restarts(1)
+    }
+
+    #[tokio::test]
+    async fn test_process_partition_step() {
+        let step = partition_step::get(String::from("process-step"))
+            .partitioner(Arc::new(RangePartitioner { min: 1, max: 3 }))
+            .grid_size(3)
+            .workers(3)
+            .process_handler(process_handler())
+            .build();
+
+        let step_status = step.run().await;
+
+        assert!(step_status.status.is_ok());
+        let sum: i64 = step_status.partitions_status.iter()
+            .map(|status| status.exit_status.parse::<i64>().unwrap())
+            .sum();
+        assert_eq!(sum, 6);
+    }
+
+    #[tokio::test]
+    async fn test_process_partition_timeout() {
+        let step = partition_step::get(String::from("hanging-step"))
+            .partitioner(Arc::new(RangePartitioner { min: 1, max: 3 }))
+            .grid_size(3)
+            .workers(3)
+            .process_handler(process_handler().timeout(Duration::from_secs(1)))
+            .build();
+        let progress = ProgressEvents::new();
+        let events = progress.stream();
+        let job = AsyncJobBuilder::get(String::from("hanging-job"))
+            .progress(progress)
+            .step(step)
+            .build();
+
+        let job_status = job.run().await;
+
+        assert!(job_status.status.is_err(), "A hung partition should fail after its restarts");
+        let errors: Vec<String> = events
+            .filter_map(|event| async move {
+                match event {
+                    ProgressEvent::Retried { error, .. } => Some(error),
+                    _ => None,
+                }
+            })
+            .collect()
+            .await;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("timed out"), "The hung worker should be killed: {}", errors[0]);
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(all(feature = "async", feature = "tracing", test))]
+mod async_trace_test {
+    use std::pin::Pin;
+    use futures::{stream, Stream};
+    use batch_processing::tokio::job::job_builder::{AsyncJobBuilder, AsyncJobBuilderTrait};
+    use batch_processing::tokio::step::AsyncStepRunner;
+    use batch_processing::tokio::step::complex_step::{AsyncComplexStepBuilder, ComplexStepBuilderTrait};
+    use batch_processing::tokio::step::parallel_step_builder::AsyncParallelStepBuilderTrait;
+    use batch_processing::tokio::step::step_builder::AsyncStepBuilderTrait;
+
+    use crate::utils::trace::span_recorder;
+
+    #[tokio::test]
+    async fn test_job_spans() {
+        let recorder = span_recorder();
+
+        let step: AsyncComplexStepBuilder<i32, i32> = AsyncComplexStepBuilder::get("async-traced-step".to_string())
+            .chunk_size(5)
+            .workers(2)
+            .reader(Box::new(|| Box::pin(async {
+                let stream: Pin<Box<dyn Stream<Item=i32> + Send>> = Box::pin(stream::iter(0..10));
+                stream
+            })))
+            .processor(Box::new(|item: i32| Box::pin(async move { item })))
+            .writer(Box::new(|_items: Vec<i32>| Box::pin(async {})));
+
+        let job = AsyncJobBuilder::get(String::from("async-traced-job"))
+            .step(step.build())
+            .build();
+
+        let job_status = job.run().await;
+
+        assert!(job_status.status.is_ok());
+        assert_eq!(recorder.spans("job", "job_name", "async-traced-job")[0].fields["status"], "COMPLETED");
+        assert_eq!(recorder.spans("step", "step_name", "async-traced-step").len(), 1);
+        assert_eq!(recorder.spans("worker", "step_name", "async-traced-step").len(), 2);
+        let item_count: usize = recorder.spans("chunk", "step_name", "async-traced-step").iter()
+            .map(|span| span.fields["item_count"].parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(item_count, 10);
+    }
+}
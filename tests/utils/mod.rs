@@ -1 +1,2 @@
-pub mod log;
\ No newline at end of file
+pub mod log;
+pub mod trace;
\ No newline at end of file
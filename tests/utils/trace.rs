@@ -0,0 +1,86 @@
+#![cfg(feature = "tracing")]
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tracing::{Event, Id, Metadata, Subscriber};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Record};
+
+/// A span recorded by `SpanRecorder`, with the values of its fields.
+#[derive(Debug, Clone)]
+pub struct RecordedSpan {
+    pub name: &'static str,
+    pub fields: HashMap<String, String>,
+}
+
+/// A subscriber keeping every span opened in the process, so tests can inspect their fields.
+#[derive(Default)]
+pub struct SpanRecorder {
+    next_id: AtomicU64,
+    spans: Mutex<HashMap<u64, RecordedSpan>>,
+}
+
+impl SpanRecorder {
+    /// Returns the recorded spans with the given name and field value.
+    pub fn spans(&self, name: &str, field: &str, value: &str) -> Vec<RecordedSpan> {
+        let mut spans: Vec<(u64, RecordedSpan)> = self.spans.lock().unwrap().iter()
+            .filter(|(_, span)| span.name == name && span.fields.get(field).map(String::as_str) == Some(value))
+            .map(|(id, span)| (*id, span.clone()))
+            .collect();
+        spans.sort_by_key(|(id, _)| *id);
+        spans.into_iter().map(|(_, span)| span).collect()
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl Subscriber for SpanRecorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut fields = HashMap::new();
+        attributes.record(&mut FieldVisitor(&mut fields));
+        self.spans.lock().unwrap().insert(id, RecordedSpan { name: attributes.metadata().name(), fields });
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        if let Some(span) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            values.record(&mut FieldVisitor(&mut span.fields));
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+/// Installs a `SpanRecorder` as the global subscriber, once per test binary.
+pub fn span_recorder() -> Arc<SpanRecorder> {
+    static RECORDER: OnceLock<Arc<SpanRecorder>> = OnceLock::new();
+    RECORDER.get_or_init(|| {
+        let recorder = Arc::new(SpanRecorder::default());
+        tracing::subscriber::set_global_default(Arc::clone(&recorder)).unwrap();
+        recorder
+    }).clone()
+}